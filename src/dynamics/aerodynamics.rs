@@ -0,0 +1,326 @@
+use crate::dynamics::{IslandManager, RigidBodySet};
+use crate::geometry::ColliderSet;
+use crate::math::{Point, Real, Vector, DIM};
+use crate::utils::{WCross, WDot};
+use na::Unit;
+
+/// A surface attached to a rigid-body, generating lift perpendicular to the airflow.
+///
+/// The surface is modeled as a flat plate. Its lift is maximal at an angle of attack of
+/// 45 degrees, and its drag is maximal when the airflow is perpendicular to the plate.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LiftSurface {
+    /// The point where the aerodynamic forces of this surface are applied, in the
+    /// rigid-body’s local-space.
+    pub local_point: Point<Real>,
+    /// The normal of this surface, in the rigid-body’s local-space.
+    ///
+    /// Lift pushes toward this normal when the airflow hits the side the normal points to.
+    pub local_normal: Unit<Vector<Real>>,
+    /// The area of this surface.
+    pub area: Real,
+    /// The lift coefficient of this surface (default: `1.0`).
+    pub lift_coefficient: Real,
+    /// The drag coefficient of this surface when the airflow is perpendicular to it (default: `1.0`).
+    pub drag_coefficient: Real,
+}
+
+impl LiftSurface {
+    /// Creates a lift surface with default lift and drag coefficients.
+    pub fn new(local_point: Point<Real>, local_normal: Unit<Vector<Real>>, area: Real) -> Self {
+        Self {
+            local_point,
+            local_normal,
+            area,
+            lift_coefficient: 1.0,
+            drag_coefficient: 1.0,
+        }
+    }
+
+    /// Sets the lift coefficient of this surface.
+    pub fn lift_coefficient(mut self, lift_coefficient: Real) -> Self {
+        self.lift_coefficient = lift_coefficient;
+        self
+    }
+
+    /// Sets the drag coefficient of this surface.
+    pub fn drag_coefficient(mut self, drag_coefficient: Real) -> Self {
+        self.drag_coefficient = drag_coefficient;
+        self
+    }
+}
+
+/// The aerodynamic model of a rigid-body.
+///
+/// Unlike damping, the aerodynamic drag is quadratic in the velocity of the rigid-body. It
+/// is computed at each timestep, together with the lift of the lift surfaces, and added to the
+/// forces applied to the rigid-body. The linear and angular drags are capped so that they
+/// slow the rigid-body down without ever reversing its motion, however light and fast it is.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RigidBodyAerodynamics {
+    /// The density of the air (default: `1.2`).
+    pub air_density: Real,
+    /// The quadratic drag coefficient of the rigid-body (default: `0.5`).
+    pub drag_coefficient: Real,
+    /// The area of the rigid-body facing the airflow, used by the drag computation.
+    ///
+    /// If `None` (the default), it is computed at each timestep from the local AABBs of the
+    /// colliders attached to the rigid-body, projected on the plane perpendicular to the
+    /// airflow.
+    pub reference_area: Option<Real>,
+    /// The quadratic angular drag coefficient of the rigid-body (default: `0.0`).
+    pub angular_drag_coefficient: Real,
+    /// The lift surfaces attached to the rigid-body.
+    pub lift_surfaces: Vec<LiftSurface>,
+}
+
+impl Default for RigidBodyAerodynamics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RigidBodyAerodynamics {
+    /// Creates an aerodynamic model with default drag, and no lift surface.
+    pub fn new() -> Self {
+        Self {
+            air_density: 1.2,
+            drag_coefficient: 0.5,
+            reference_area: None,
+            angular_drag_coefficient: 0.0,
+            lift_surfaces: vec![],
+        }
+    }
+
+    /// Sets the density of the air.
+    pub fn air_density(mut self, air_density: Real) -> Self {
+        self.air_density = air_density;
+        self
+    }
+
+    /// Sets the quadratic drag coefficient.
+    pub fn drag_coefficient(mut self, drag_coefficient: Real) -> Self {
+        self.drag_coefficient = drag_coefficient;
+        self
+    }
+
+    /// Sets the area facing the airflow, instead of computing it from the colliders.
+    pub fn reference_area(mut self, reference_area: Real) -> Self {
+        self.reference_area = Some(reference_area);
+        self
+    }
+
+    /// Sets the quadratic angular drag coefficient.
+    pub fn angular_drag_coefficient(mut self, angular_drag_coefficient: Real) -> Self {
+        self.angular_drag_coefficient = angular_drag_coefficient;
+        self
+    }
+
+    /// Adds a lift surface.
+    pub fn lift_surface(mut self, surface: LiftSurface) -> Self {
+        self.lift_surfaces.push(surface);
+        self
+    }
+}
+
+/// Adds the aerodynamic forces to the active dynamic bodies with an aerodynamic model.
+pub(crate) fn apply_aerodynamic_forces(
+    dt: Real,
+    islands: &IslandManager,
+    bodies: &mut RigidBodySet,
+    colliders: &ColliderSet,
+) {
+    for handle in islands.active_dynamic_bodies() {
+        let rb = bodies.index_mut_internal(*handle);
+        let aero = match rb.aerodynamics.as_deref() {
+            Some(aero) => aero,
+            None => continue,
+        };
+
+        let linvel = rb.vels.linvel;
+        let speed = linvel.norm();
+        let mut force = Vector::zeros();
+
+        // The drag forces are capped so that their impulse over the timestep doesn’t exceed the
+        // momentum of the rigid-body. Otherwise, they would reverse, and amplify, the motion of
+        // light and fast rigid-bodies.
+        let angular_speed = rb.vels.angvel.gdot(rb.vels.angvel).sqrt();
+        let angular_drag = aero.angular_drag_coefficient * angular_speed;
+        let angular_inv_inertia = if angular_speed > 0.0 {
+            let axis =
+                rb.mprops.effective_world_inv_inertia_sqrt * (rb.vels.angvel / angular_speed);
+            axis.gdot(axis)
+        } else {
+            0.0
+        };
+        let mut torque = -rb.vels.angvel * angular_drag.min(1.0 / (dt * angular_inv_inertia));
+
+        if speed > 0.0 {
+            let dir = linvel / speed;
+            let area = aero.reference_area.unwrap_or_else(|| {
+                rb.colliders
+                    .0
+                    .iter()
+                    .filter_map(|h| colliders.get(*h))
+                    .map(|co| {
+                        let local_dir = co.pos.inverse_transform_vector(&dir);
+                        let half_extents = co.shape.compute_local_aabb().half_extents();
+                        projected_box_area(&half_extents, &local_dir)
+                    })
+                    .sum()
+            });
+
+            let drag = 0.5 * aero.air_density * aero.drag_coefficient * area * speed;
+            let inv_mass = dir.dot(&rb.mprops.effective_inv_mass.component_mul(&dir));
+            force -= linvel * drag.min(1.0 / (dt * inv_mass));
+        }
+
+        for surface in &aero.lift_surfaces {
+            let point = rb.pos.position * surface.local_point;
+            let normal = rb.pos.position * surface.local_normal;
+            let lever = point - rb.mprops.world_com;
+            let vel = linvel + rb.vels.angvel.gcross(lever);
+            let speed = vel.norm();
+
+            if speed == 0.0 {
+                continue;
+            }
+
+            let dir = vel / speed;
+            let dynamic_pressure = 0.5 * aero.air_density * surface.area * speed * speed;
+            // The angle of attack, positive if the airflow hits the side the normal points to.
+            let sin_aoa = -dir.dot(&normal);
+            // Perpendicular to the airflow, with a norm equal to the cosine of the angle of attack.
+            let lift_dir = *normal + dir * sin_aoa;
+
+            // Flat plate model: the lift is proportional to `sin(2 * aoa)`, and the drag to `sin²(aoa)`.
+            let surface_force = lift_dir
+                * (dynamic_pressure * surface.lift_coefficient * 2.0 * sin_aoa)
+                - dir * (dynamic_pressure * surface.drag_coefficient * sin_aoa * sin_aoa);
+
+            force += surface_force;
+            torque += lever.gcross(surface_force);
+        }
+
+        rb.forces.force += force;
+        rb.forces.torque += torque;
+    }
+}
+
+/// The area of a box with the given half-extents, projected on a plane orthogonal to `dir`.
+fn projected_box_area(half_extents: &Vector<Real>, dir: &Vector<Real>) -> Real {
+    let mut area = 0.0;
+
+    for i in 0..DIM {
+        // The area of the box face orthogonal to the i-th axis.
+        let mut face_area = 1.0;
+        for j in 0..DIM {
+            if j != i {
+                face_area *= 2.0 * half_extents[j];
+            }
+        }
+
+        area += face_area * dir[i].abs();
+    }
+
+    area
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LiftSurface, RigidBodyAerodynamics};
+    use crate::dynamics::{RigidBodyBuilder, RigidBodyGravity};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{AngVector, Point, Real, Vector};
+    use crate::pipeline::test_utils;
+    use crate::pipeline::PhysicsWorld;
+
+    #[test]
+    fn terminal_velocity_and_lift() {
+        let mut world = test_utils::world();
+
+        // A unit box with a unit mass, falling face first.
+        let falling = world.insert_rigid_body(
+            RigidBodyBuilder::dynamic().aerodynamics(RigidBodyAerodynamics::new()),
+        );
+        let collider = test_utils::cuboid(0.5, 0.5);
+        world.insert_collider_with_parent(collider, falling);
+
+        // Two gliders without gravity, with and without lift.
+        let glider = |lift_coefficient: Real| {
+            let surface = LiftSurface::new(Point::origin(), Vector::y_axis(), 1.0)
+                .lift_coefficient(lift_coefficient);
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * 10.0)
+                .linvel(Vector::x() * 10.0 - Vector::y())
+                .additional_mass(1.0)
+                .gravity(RigidBodyGravity::Constant(Vector::zeros()))
+                .aerodynamics(RigidBodyAerodynamics::new().lift_surface(surface))
+        };
+        let lifting = world.insert_rigid_body(glider(1.0));
+        let not_lifting = world.insert_rigid_body(glider(0.0));
+
+        world.step(&(), &());
+        assert!(world.bodies[lifting].linvel().y > world.bodies[not_lifting].linvel().y);
+
+        for _ in 0..600 {
+            world.step(&(), &());
+        }
+
+        let terminal_velocity = (2.0 * 10.0 / (1.2 * 0.5 as Real)).sqrt();
+        let velocity = world.bodies[falling].linvel().y;
+        assert!(
+            (velocity + terminal_velocity).abs() < 1.0e-2,
+            "{}",
+            velocity
+        );
+    }
+
+    #[test]
+    fn drag_of_light_and_fast_body_is_stable() {
+        let mut world = PhysicsWorld::new();
+
+        // A 10cm × 10cm × 1mm plate, much lighter than the air it pushes at 150m/s.
+        #[cfg(feature = "dim2")]
+        let (collider, angvel) = (ColliderBuilder::cuboid(0.05, 0.0005), 150.0);
+        #[cfg(feature = "dim3")]
+        let (collider, angvel) = (
+            ColliderBuilder::cuboid(0.05, 0.0005, 0.05),
+            Vector::x() * 150.0,
+        );
+        let handle = world.insert_rigid_body(
+            RigidBodyBuilder::dynamic()
+                .linvel(Vector::y() * 150.0)
+                .angvel(angvel)
+                .aerodynamics(
+                    RigidBodyAerodynamics::new()
+                        .drag_coefficient(1.0)
+                        .angular_drag_coefficient(1.0),
+                ),
+        );
+        world.insert_collider_with_parent(collider.density(500.0), handle);
+
+        let initial_angvel: AngVector<Real> = angvel;
+        let (mut speed, mut angular_speed) = (150.0, 150.0);
+        for _ in 0..60 {
+            world.step(&(), &());
+            let rb = &world.bodies[handle];
+            let new_speed = rb.linvel().y;
+            #[cfg(feature = "dim2")]
+            let new_angular_speed = rb.angvel() * initial_angvel / 150.0;
+            #[cfg(feature = "dim3")]
+            let new_angular_speed = rb.angvel().dot(&initial_angvel) / 150.0;
+
+            assert!(new_speed >= 0.0 && new_speed <= speed, "{}", new_speed);
+            assert!(
+                new_angular_speed >= 0.0 && new_angular_speed <= angular_speed,
+                "{}",
+                new_angular_speed
+            );
+            speed = new_speed;
+            angular_speed = new_angular_speed;
+        }
+    }
+}
@@ -1,11 +1,15 @@
 use super::TOIEntry;
-use crate::dynamics::{IslandManager, RigidBodyHandle, RigidBodySet};
-use crate::geometry::{ColliderParent, ColliderSet, CollisionEvent, NarrowPhase};
-use crate::math::Real;
+use crate::dynamics::{IslandManager, RigidBody, RigidBodyHandle, RigidBodySet, RigidBodyType};
+use crate::geometry::{
+    BroadPhase, Collider, ColliderHandle, ColliderParent, ColliderSet, NarrowPhase,
+    SensorPassThroughEvent,
+};
+use crate::math::{Isometry, Real};
 use crate::parry::utils::SortedPair;
 use crate::pipeline::{EventHandler, QueryPipeline, QueryPipelineMode};
-use crate::prelude::{ActiveEvents, CollisionEventFlags};
-use parry::query::{DefaultQueryDispatcher, QueryDispatcher};
+use crate::prelude::ActiveEvents;
+use parry::bounding_volume::BoundingVolume;
+use parry::query::{DefaultQueryDispatcher, NonlinearRigidMotion, QueryDispatcher};
 use parry::utils::hashmap::HashMap;
 use std::collections::BinaryHeap;
 
@@ -21,6 +25,10 @@ pub enum PredictedImpacts {
 pub struct CCDSolver {
     #[cfg_attr(feature = "serde-serialize", serde(skip))]
     query_pipeline: QueryPipeline,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    moving_sensors: Vec<ColliderHandle>, // Workspace.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    sensors: Vec<ColliderHandle>, // Workspace.
 }
 
 impl Default for CCDSolver {
@@ -45,6 +53,8 @@ impl CCDSolver {
     {
         CCDSolver {
             query_pipeline: QueryPipeline::with_query_dispatcher(d),
+            moving_sensors: vec![],
+            sensors: vec![],
         }
     }
 
@@ -103,7 +113,9 @@ impl CCDSolver {
                 } else {
                     None
                 };
-                let moving_fast = rb.ccd.is_moving_fast(dt, &rb.integrated_vels, forces);
+                // The velocity limits may prevent the rigid-body from ever moving fast.
+                let moving_fast = rb.ccd.can_move_fast(dt, &rb.vel_limits)
+                    && rb.ccd.is_moving_fast(dt, &rb.integrated_vels, forces);
                 rb.ccd.ccd_active = moving_fast;
                 ccd_active = ccd_active || moving_fast;
             }
@@ -228,8 +240,12 @@ impl CCDSolver {
     }
 
     /// Outputs the set of bodies as well as their first time-of-impact event.
+    ///
+    /// The `elapsed_time` since the beginning of the timestep is added to the time of entry of
+    /// the emitted sensor pass-through events, since `dt` may be the length of a CCD substep.
     pub fn predict_impacts_at_next_positions(
         &mut self,
+        elapsed_time: Real,
         dt: Real,
         islands: &IslandManager,
         bodies: &RigidBodySet,
@@ -541,21 +557,265 @@ impl CCDSolver {
                     .contains(ActiveEvents::COLLISION_EVENTS)
             {
                 // Emit one intersection-started and one intersection-stopped event.
-                events.handle_collision_event(
+                events.handle_sensor_pass_through_event(
                     bodies,
                     colliders,
-                    CollisionEvent::Started(toi.c1, toi.c2, CollisionEventFlags::SENSOR),
-                    None,
-                );
-                events.handle_collision_event(
-                    bodies,
-                    colliders,
-                    CollisionEvent::Stopped(toi.c1, toi.c2, CollisionEventFlags::SENSOR),
-                    None,
+                    SensorPassThroughEvent {
+                        collider1: toi.c1,
+                        collider2: toi.c2,
+                        time_of_entry: elapsed_time + toi.toi,
+                    },
                 );
             }
         }
 
         PredictedImpacts::Impacts(frozen)
     }
+
+    /// Detects the fast rigid-bodies passing through sensors with swept detection enabled.
+    ///
+    /// Only the rigid-bodies without active CCD are tested here, since the sensors crossed by
+    /// the others are detected by `Self::predict_impacts_at_next_positions`. This must be called
+    /// before the colliders are moved to the final positions of their rigid-bodies.
+    ///
+    /// The `elapsed_time` since the beginning of the timestep is added to the time of entry of
+    /// the emitted events, since `dt` may be the length of a CCD substep.
+    pub fn detect_sensor_pass_throughs(
+        &mut self,
+        elapsed_time: Real,
+        dt: Real,
+        islands: &IslandManager,
+        broad_phase: &BroadPhase,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        events: &dyn EventHandler,
+    ) {
+        let mut fast_bodies = islands
+            .active_dynamic_bodies()
+            .iter()
+            .map(|handle| &bodies[*handle])
+            .filter(|rb| !rb.ccd.ccd_active && rb.ccd.is_moving_fast(dt, &rb.integrated_vels, None))
+            .peekable();
+
+        if fast_bodies.peek().is_none() {
+            return;
+        }
+
+        // The broad-phase Aabbs are computed at the current positions of the colliders, so they
+        // don’t cover the motion of the sensors attached to moving rigid-bodies.
+        let moving_sensors = &mut self.moving_sensors;
+        moving_sensors.clear();
+        moving_sensors.extend(
+            islands
+                .active_kinematic_bodies()
+                .iter()
+                .chain(islands.active_dynamic_bodies())
+                .flat_map(|handle| bodies[*handle].colliders.0.iter().copied())
+                .filter(|handle| colliders[*handle].is_sensor()),
+        );
+        let sensors = &mut self.sensors;
+        let query_dispatcher = self.query_pipeline.query_dispatcher();
+
+        for rb1 in fast_bodies {
+            for ch1 in &rb1.colliders.0 {
+                let co1 = &colliders[*ch1];
+
+                if co1.is_sensor() || !co1.is_enabled() {
+                    continue;
+                }
+
+                let co_next_pos1 = next_collider_position(co1, bodies);
+                let aabb1 = co1.compute_swept_aabb(&co_next_pos1);
+
+                sensors.clear();
+                sensors.extend_from_slice(moving_sensors);
+                broad_phase.for_each_collider_intersecting_aabb(&aabb1, |handle| {
+                    if colliders[handle].is_sensor() {
+                        sensors.push(handle);
+                    }
+                });
+                // The same sensor can be reported more than once.
+                sensors.sort_unstable_by_key(|handle| handle.into_raw_parts());
+                sensors.dedup();
+
+                for ch2 in sensors.iter() {
+                    let co2 = &colliders[*ch2];
+
+                    if !co2.is_sensor_ccd_enabled() || !co2.is_enabled() {
+                        continue;
+                    }
+
+                    let co_next_pos2 = next_collider_position(co2, bodies);
+                    let rb2 = co2.parent.map(|p| &bodies[p.handle]);
+                    let rb_type2 = rb2.map(|rb| rb.body_type).unwrap_or(RigidBodyType::Fixed);
+
+                    // Ignore self-intersections and apply the filters of the narrow-phase.
+                    if co1.parent == co2.parent
+                        || (!co1
+                            .flags
+                            .active_collision_types
+                            .test(rb1.body_type, rb_type2)
+                            && !co2
+                                .flags
+                                .active_collision_types
+                                .test(rb1.body_type, rb_type2))
+                        || !co1.flags.collision_groups.test(co2.flags.collision_groups)
+                        || !(co1.flags.active_events | co2.flags.active_events)
+                            .contains(ActiveEvents::COLLISION_EVENTS)
+                        || !aabb1.intersects(&co2.compute_swept_aabb(&co_next_pos2))
+                    {
+                        continue;
+                    }
+
+                    let intersect_before = query_dispatcher
+                        .intersection_test(&co1.pos.inv_mul(&co2.pos), &*co1.shape, &*co2.shape)
+                        .unwrap_or(false);
+                    let intersect_after = query_dispatcher
+                        .intersection_test(
+                            &co_next_pos1.inv_mul(&co_next_pos2),
+                            &*co1.shape,
+                            &*co2.shape,
+                        )
+                        .unwrap_or(false);
+
+                    if intersect_before || intersect_after {
+                        // This intersection is detected by the narrow-phase.
+                        continue;
+                    }
+
+                    let motion1 =
+                        swept_body_motion(rb1).prepend(co1.parent.unwrap().pos_wrt_parent);
+                    let motion2 = match (rb2, co2.parent) {
+                        (Some(rb2), Some(parent2)) => {
+                            swept_body_motion(rb2).prepend(parent2.pos_wrt_parent)
+                        }
+                        _ => NonlinearRigidMotion::constant_position(co2.pos.0),
+                    };
+
+                    let toi = query_dispatcher
+                        .nonlinear_time_of_impact(
+                            &motion1,
+                            &*co1.shape,
+                            &motion2,
+                            &*co2.shape,
+                            0.0,
+                            dt,
+                            true,
+                        )
+                        .ok()
+                        .flatten();
+
+                    if let Some(toi) = toi {
+                        events.handle_sensor_pass_through_event(
+                            bodies,
+                            colliders,
+                            SensorPassThroughEvent {
+                                collider1: *ch1,
+                                collider2: *ch2,
+                                time_of_entry: elapsed_time + toi.toi,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The position of the given collider once its rigid-body reaches its next position.
+fn next_collider_position(co: &Collider, bodies: &RigidBodySet) -> Isometry<Real> {
+    match co.parent {
+        Some(parent) => bodies[parent.handle].pos.next_position * parent.pos_wrt_parent,
+        None => co.pos.0,
+    }
+}
+
+/// The motion of the given rigid-body from its current position to its next position.
+fn swept_body_motion(rb: &RigidBody) -> NonlinearRigidMotion {
+    let vels = match rb.body_type {
+        RigidBodyType::Dynamic if !rb.is_sleeping() => &rb.integrated_vels,
+        RigidBodyType::KinematicPositionBased | RigidBodyType::KinematicVelocityBased => &rb.vels,
+        _ => return NonlinearRigidMotion::constant_position(rb.pos.position),
+    };
+
+    NonlinearRigidMotion::new(
+        rb.pos.position,
+        rb.mprops.local_mprops.local_com,
+        vels.linvel,
+        vels.angvel,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_utils;
+    use crate::pipeline::{ActiveEvents, PhysicsWorld};
+
+    #[test]
+    fn fast_body_passing_through_swept_sensor() {
+        let mut world = PhysicsWorld::with_gravity(Vector::zeros());
+
+        // Two thin sensors, only one of them with swept detection.
+        let sensor = test_utils::cuboid(1.0, 0.05)
+            .sensor(true)
+            .active_events(ActiveEvents::COLLISION_EVENTS);
+        let swept_sensor = world.insert_collider(sensor.clone().sensor_ccd_enabled(true));
+        world.insert_collider(sensor.translation(Vector::x() * 5.0));
+
+        // Two fast balls without CCD, crossing each sensor during the first timestep.
+        for x in [0.0, 5.0] {
+            let rb = world.insert_rigid_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * x + Vector::y() * 2.0)
+                    .linvel(Vector::y() * -200.0),
+            );
+            world.insert_collider_with_parent(ColliderBuilder::ball(0.1), rb);
+        }
+
+        let events = test_utils::EventChannels::new();
+        world.step(&(), &events.collector);
+
+        let pass_throughs: Vec<_> = events.pass_throughs.try_iter().collect();
+        assert_eq!(pass_throughs.len(), 1);
+        let pass_through = pass_throughs[0];
+        assert!(pass_through.collider1 == swept_sensor || pass_through.collider2 == swept_sensor);
+        let expected_time_of_entry = (2.0 - 0.1 - 0.05) / 200.0 as Real;
+        assert!((pass_through.time_of_entry - expected_time_of_entry).abs() < 1.0e-3);
+
+        let collisions: Vec<_> = events.collisions.try_iter().collect();
+        assert_eq!(collisions.len(), 2);
+        assert!(collisions[0].started() && collisions[1].stopped());
+        assert!(collisions.iter().all(|event| event.sensor()));
+
+        // With CCD substeps, the time of entry is still measured from the beginning of the
+        // timestep. The bullet hits the wall during the second timestep, ending its first
+        // substep before the ball reaches the sensor.
+        world.integration_parameters.max_ccd_substeps = 4;
+        let wall = test_utils::cuboid(0.1, 10.0);
+        world.insert_collider(wall.translation(Vector::y() * 50.0 - Vector::x() * 9.0));
+        let bullet = world.insert_rigid_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 50.0 - Vector::x() * 30.0)
+                .linvel(Vector::x() * 1000.0)
+                .ccd_enabled(true),
+        );
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.1), bullet);
+        world.step(&(), &events.collector);
+
+        let rb = world.insert_rigid_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 2.65)
+                .linvel(Vector::y() * -200.0),
+        );
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.1), rb);
+        world.step(&(), &events.collector);
+
+        let pass_throughs: Vec<_> = events.pass_throughs.try_iter().collect();
+        assert_eq!(pass_throughs.len(), 1);
+        let expected_time_of_entry = (2.65 - 0.1 - 0.05) / 200.0 as Real;
+        assert!((pass_throughs[0].time_of_entry - expected_time_of_entry).abs() < 1.0e-3);
+    }
 }
@@ -0,0 +1,94 @@
+use crate::dynamics::RigidBody;
+use crate::geometry::{Aabb, InteractionGroups};
+use crate::math::{AngVector, Real, Vector};
+
+/// A force field applied by the physics pipeline to the active dynamic rigid-bodies.
+///
+/// Force generators are registered into a [`ForceGeneratorSet`](crate::dynamics::ForceGeneratorSet)
+/// given to the physics pipeline. At each timestep, they are evaluated on the active dynamic
+/// rigid-bodies within their scope, after gravity and user-defined forces are accumulated.
+/// The forces they compute only last for the current timestep, and never wake up sleeping
+/// rigid-bodies.
+pub trait ForceGenerator: Send + Sync {
+    /// Clones this force generator into a boxed trait-object.
+    fn clone_dyn(&self) -> Box<dyn ForceGenerator>;
+
+    /// Advances the internal state of this generator (turbulences for example) by `dt`.
+    ///
+    /// This is called once per CCD substep, before the forces are computed.
+    fn update(&mut self, _dt: Real) {}
+
+    /// Appends the internal state advanced by [`Self::update`] to `state`.
+    ///
+    /// This is used by world snapshots, and only needs to be implemented by generators
+    /// with an internal state.
+    fn save_state(&self, _state: &mut Vec<Real>) {}
+
+    /// Restores the internal state saved by [`Self::save_state`] from the beginning of `state`.
+    ///
+    /// Returns the number of values read from `state`.
+    fn restore_state(&mut self, _state: &[Real]) -> usize {
+        0
+    }
+
+    /// The world-space region outside of which this generator has no effect.
+    ///
+    /// If this returns `Some`, the rigid-bodies outside of this region are culled using
+    /// the broad-phase, and [`Self::force`] is not called for them.
+    fn region(&self) -> Option<Aabb> {
+        None
+    }
+
+    /// The force applied by this generator at the center-of-mass of the given rigid-body.
+    fn force(&self, rb: &RigidBody) -> Vector<Real>;
+
+    /// The torque applied by this generator to the given rigid-body.
+    fn torque(&self, _rb: &RigidBody) -> AngVector<Real> {
+        na::zero()
+    }
+}
+
+/// The set of rigid-bodies a force generator is applied to.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ForceGeneratorScope {
+    /// The world-space region the generator is restricted to.
+    ///
+    /// Only the rigid-bodies with at least one collider intersecting this region
+    /// are affected. If `None`, the generator is not restricted to a region.
+    pub region: Option<Aabb>,
+    /// The interaction groups the generator is restricted to.
+    ///
+    /// Only the rigid-bodies with at least one collider with collision groups compatible
+    /// with these groups are affected. Rigid-bodies without colliders are only affected
+    /// if these groups are `InteractionGroups::all()`.
+    pub groups: InteractionGroups,
+}
+
+impl Default for ForceGeneratorScope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ForceGeneratorScope {
+    /// A scope including every active dynamic rigid-body.
+    pub fn new() -> Self {
+        Self {
+            region: None,
+            groups: InteractionGroups::all(),
+        }
+    }
+
+    /// Restricts this scope to the given world-space region.
+    pub fn region(mut self, region: Aabb) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Restricts this scope to the given interaction groups.
+    pub fn groups(mut self, groups: InteractionGroups) -> Self {
+        self.groups = groups;
+        self
+    }
+}
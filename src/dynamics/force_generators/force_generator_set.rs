@@ -0,0 +1,315 @@
+use super::{ForceGenerator, ForceGeneratorScope};
+use crate::data::arena::Arena;
+use crate::dynamics::{IslandManager, RigidBodyHandle, RigidBodySet};
+use crate::geometry::{BroadPhase, ColliderSet, InteractionGroups};
+use crate::math::Real;
+use parry::bounding_volume::BoundingVolume;
+
+/// The unique identifier of a force generator added to a force generator set.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[repr(transparent)]
+pub struct ForceGeneratorHandle(pub crate::data::arena::Index);
+
+impl ForceGeneratorHandle {
+    /// Converts this handle into its (index, generation) components.
+    pub fn into_raw_parts(self) -> (u32, u32) {
+        self.0.into_raw_parts()
+    }
+
+    /// Reconstructs an handle from its (index, generation) components.
+    pub fn from_raw_parts(id: u32, generation: u32) -> Self {
+        Self(crate::data::arena::Index::from_raw_parts(id, generation))
+    }
+
+    /// An always-invalid force generator handle.
+    pub fn invalid() -> Self {
+        Self(crate::data::arena::Index::from_raw_parts(
+            crate::INVALID_U32,
+            crate::INVALID_U32,
+        ))
+    }
+}
+
+struct ForceGeneratorEntry {
+    generator: Box<dyn ForceGenerator>,
+    scope: ForceGeneratorScope,
+}
+
+impl Clone for ForceGeneratorEntry {
+    fn clone(&self) -> Self {
+        Self {
+            generator: self.generator.clone_dyn(),
+            scope: self.scope,
+        }
+    }
+}
+
+/// The internal state of the force generators of a set, as saved by world snapshots.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+pub(crate) struct ForceGeneratorSetState {
+    handles: Vec<ForceGeneratorHandle>,
+    values: Vec<Real>,
+}
+
+impl ForceGeneratorSetState {
+    /// Checks if this state has been saved from a set with the same force generators as `set`.
+    pub fn is_compatible_with(&self, set: &ForceGeneratorSet) -> bool {
+        self.handles.len() == set.len() && self.handles.iter().all(|h| set.contains(*h))
+    }
+}
+
+/// A set of force generators applied by the physics pipeline at each timestep.
+///
+/// This is given to `PhysicsPipeline::step_with_force_generators`, and owned by the `PhysicsWorld`.
+#[derive(Clone, Default)]
+pub struct ForceGeneratorSet {
+    generators: Arena<ForceGeneratorEntry>,
+    candidates: Vec<RigidBodyHandle>, // Workspace.
+}
+
+impl ForceGeneratorSet {
+    /// Creates a new empty set of force generators.
+    pub fn new() -> Self {
+        Self {
+            generators: Arena::new(),
+            candidates: vec![],
+        }
+    }
+
+    /// The number of force generators on this set.
+    pub fn len(&self) -> usize {
+        self.generators.len()
+    }
+
+    /// `true` if there are no force generators in this set.
+    pub fn is_empty(&self) -> bool {
+        self.generators.is_empty()
+    }
+
+    /// Is the given force generator handle valid?
+    pub fn contains(&self, handle: ForceGeneratorHandle) -> bool {
+        self.generators.contains(handle.0)
+    }
+
+    /// Inserts a new force generator, applied to the rigid-bodies within the given scope.
+    pub fn insert(
+        &mut self,
+        generator: impl ForceGenerator + 'static,
+        scope: ForceGeneratorScope,
+    ) -> ForceGeneratorHandle {
+        ForceGeneratorHandle(self.generators.insert(ForceGeneratorEntry {
+            generator: Box::new(generator),
+            scope,
+        }))
+    }
+
+    /// Removes a force generator from this set.
+    pub fn remove(&mut self, handle: ForceGeneratorHandle) -> Option<Box<dyn ForceGenerator>> {
+        self.generators
+            .remove(handle.0)
+            .map(|entry| entry.generator)
+    }
+
+    /// Gets the force generator with the given handle.
+    pub fn get(&self, handle: ForceGeneratorHandle) -> Option<&dyn ForceGenerator> {
+        self.generators.get(handle.0).map(|entry| &*entry.generator)
+    }
+
+    /// Gets a mutable reference to the force generator with the given handle.
+    pub fn get_mut(&mut self, handle: ForceGeneratorHandle) -> Option<&mut dyn ForceGenerator> {
+        match self.generators.get_mut(handle.0) {
+            Some(entry) => Some(&mut *entry.generator),
+            None => None,
+        }
+    }
+
+    /// Gets the scope of the force generator with the given handle.
+    pub fn scope(&self, handle: ForceGeneratorHandle) -> Option<&ForceGeneratorScope> {
+        self.generators.get(handle.0).map(|entry| &entry.scope)
+    }
+
+    /// Gets a mutable reference to the scope of the force generator with the given handle.
+    pub fn scope_mut(&mut self, handle: ForceGeneratorHandle) -> Option<&mut ForceGeneratorScope> {
+        self.generators
+            .get_mut(handle.0)
+            .map(|entry| &mut entry.scope)
+    }
+
+    /// Iterates through all the force generators of this set.
+    pub fn iter(&self) -> impl Iterator<Item = (ForceGeneratorHandle, &dyn ForceGenerator)> {
+        self.generators
+            .iter()
+            .map(|(h, entry)| (ForceGeneratorHandle(h), &*entry.generator))
+    }
+
+    /// Copies the internal state of the force generators into `state`.
+    ///
+    /// This reuses the buffers already allocated by `state`.
+    pub(crate) fn save_state(&self, state: &mut ForceGeneratorSetState) {
+        state.handles.clear();
+        state.values.clear();
+
+        for (handle, entry) in self.generators.iter() {
+            state.handles.push(ForceGeneratorHandle(handle));
+            entry.generator.save_state(&mut state.values);
+        }
+    }
+
+    /// Restores, in-place, the state saved by [`Self::save_state`].
+    ///
+    /// The state must be compatible with this set, see [`ForceGeneratorSetState::is_compatible_with`].
+    pub(crate) fn restore_state(&mut self, state: &ForceGeneratorSetState) {
+        let mut values = state.values.as_slice();
+
+        for handle in &state.handles {
+            if let Some(entry) = self.generators.get_mut(handle.0) {
+                let num_read = entry.generator.restore_state(values);
+                values = &values[num_read..];
+            }
+        }
+    }
+
+    /// Updates every force generator and adds their forces to the active dynamic rigid-bodies
+    /// within their scope.
+    ///
+    /// This must be called after the forces of the rigid-bodies were reset to the gravity and
+    /// user-defined forces for this timestep.
+    pub(crate) fn apply(
+        &mut self,
+        dt: Real,
+        islands: &IslandManager,
+        broad_phase: &BroadPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &ColliderSet,
+    ) {
+        for (_, entry) in self.generators.iter_mut() {
+            entry.generator.update(dt);
+
+            let region = match (entry.scope.region, entry.generator.region()) {
+                (Some(region1), Some(region2)) => {
+                    if !region1.intersects(&region2) {
+                        continue;
+                    }
+                    Some(region1.intersection(&region2).unwrap_or(region1))
+                }
+                (region1, region2) => region1.or(region2),
+            };
+
+            self.candidates.clear();
+
+            if let Some(region) = region {
+                let groups = entry.scope.groups;
+                let candidates = &mut self.candidates;
+                broad_phase.for_each_collider_intersecting_aabb(&region, |handle| {
+                    if let Some(co) = colliders.get(handle) {
+                        if co.is_enabled() && co.collision_groups().test(groups) {
+                            candidates.extend(co.parent());
+                        }
+                    }
+                });
+
+                // A rigid-body can be reported once per collider and per region.
+                self.candidates
+                    .sort_unstable_by_key(|handle| handle.into_raw_parts());
+                self.candidates.dedup();
+            } else {
+                let groups = entry.scope.groups;
+                self.candidates
+                    .extend(
+                        islands
+                            .active_dynamic_bodies()
+                            .iter()
+                            .copied()
+                            .filter(|handle| {
+                                groups == InteractionGroups::all()
+                                    || bodies[*handle].colliders().iter().any(|co| {
+                                        colliders
+                                            .get(*co)
+                                            .map(|co| co.collision_groups().test(groups))
+                                            .unwrap_or(false)
+                                    })
+                            }),
+                    );
+            }
+
+            for handle in &self.candidates {
+                let is_active = bodies
+                    .get(*handle)
+                    .and_then(|rb| islands.active_dynamic_bodies().get(rb.ids.active_set_id))
+                    == Some(handle);
+
+                if !is_active {
+                    continue;
+                }
+
+                let rb = &bodies[*handle];
+                let force = entry.generator.force(rb);
+                let torque = entry.generator.torque(rb);
+                let rb = bodies.index_mut_internal(*handle);
+                rb.forces.force += force;
+                rb.forces.torque += torque;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{
+        ForceGeneratorScope, PointGravity, RadialField, RigidBodyBuilder, RigidBodyHandle,
+    };
+    use crate::geometry::{ColliderBuilder, Group, InteractionGroups};
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::PhysicsWorld;
+
+    #[test]
+    fn force_generators_scope() {
+        let mut world = PhysicsWorld::new();
+        let mut insert_ball = |pos: Vector<Real>, groups: InteractionGroups, sleeping: bool| {
+            let rb = RigidBodyBuilder::dynamic()
+                .translation(pos)
+                .sleeping(sleeping);
+            let handle = world.insert_rigid_body(rb);
+            let co = ColliderBuilder::ball(0.5).collision_groups(groups);
+            world.insert_collider_with_parent(co, handle);
+            handle
+        };
+
+        let all = InteractionGroups::all();
+        let other = InteractionGroups::new(Group::GROUP_2, Group::GROUP_2);
+        let inside = insert_ball(Vector::x() * 2.0, all, false);
+        let filtered_out = insert_ball(Vector::x() * -2.0, other, false);
+        let sleeping = insert_ball(Vector::y() * 3.0, all, true);
+        let outside = insert_ball(Vector::x() * 20.0, all, false);
+
+        let field = RadialField::new(Point::origin(), 5.0, 10.0);
+        let groups = InteractionGroups::new(Group::GROUP_1, Group::GROUP_1);
+        world
+            .force_generators
+            .insert(field, ForceGeneratorScope::new().groups(groups));
+
+        for _ in 0..10 {
+            world.step(&(), &());
+        }
+
+        let x = |world: &PhysicsWorld, h: RigidBodyHandle| world.bodies[h].translation().x;
+        assert!(x(&world, inside) > 2.0);
+        assert_eq!(x(&world, filtered_out), -2.0);
+        assert_eq!(world.bodies[sleeping].translation().y, 3.0);
+        assert!(world.bodies[sleeping].is_sleeping());
+        assert_eq!(x(&world, outside), 20.0);
+
+        // The gravity well attracts the bodies outside of the radial field too.
+        world.force_generators.insert(
+            PointGravity::new(Point::origin(), 100.0),
+            Default::default(),
+        );
+        world.step(&(), &());
+        assert!(x(&world, outside) < 20.0);
+
+        // The force generators are cloned along with the world.
+        assert_eq!(world.clone().force_generators.len(), 2);
+    }
+}
@@ -0,0 +1,12 @@
+pub use self::force_generator::{ForceGenerator, ForceGeneratorScope};
+pub(crate) use self::force_generator_set::ForceGeneratorSetState;
+pub use self::force_generator_set::{ForceGeneratorHandle, ForceGeneratorSet};
+pub use self::point_gravity::PointGravity;
+pub use self::radial_field::{RadialFalloff, RadialField};
+pub use self::wind::Wind;
+
+mod force_generator;
+mod force_generator_set;
+mod point_gravity;
+mod radial_field;
+mod wind;
@@ -0,0 +1,57 @@
+use super::ForceGenerator;
+use crate::dynamics::RigidBody;
+use crate::math::{Point, Real, Vector};
+
+/// A gravity well attracting the rigid-bodies towards a point.
+///
+/// The attraction is proportional to the mass of the rigid-body and inversely proportional
+/// to the squared distance between its center-of-mass and the center of the well.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PointGravity {
+    /// The world-space center of the gravity well.
+    pub center: Point<Real>,
+    /// The gravitational parameter of the well, i.e., the gravitational constant multiplied
+    /// by the mass of the attracting body.
+    ///
+    /// This is the magnitude of the gravitational acceleration at a distance of `1.0`.
+    pub strength: Real,
+    /// The distance under which the attraction stops increasing (default: `0.1`).
+    ///
+    /// This avoids infinite forces when a rigid-body gets very close to the center.
+    pub min_distance: Real,
+}
+
+impl PointGravity {
+    /// Creates a new gravity well with the given center and gravitational parameter.
+    pub fn new(center: Point<Real>, strength: Real) -> Self {
+        Self {
+            center,
+            strength,
+            min_distance: 0.1,
+        }
+    }
+
+    /// Sets the distance under which the attraction stops increasing.
+    pub fn min_distance(mut self, min_distance: Real) -> Self {
+        self.min_distance = min_distance;
+        self
+    }
+}
+
+impl ForceGenerator for PointGravity {
+    fn clone_dyn(&self) -> Box<dyn ForceGenerator> {
+        Box::new(*self)
+    }
+
+    fn force(&self, rb: &RigidBody) -> Vector<Real> {
+        let dir = self.center - rb.center_of_mass();
+        let dist = dir.norm().max(self.min_distance);
+
+        if dist == 0.0 {
+            return Vector::zeros();
+        }
+
+        dir * (rb.mass() * self.strength / (dist * dist * dist))
+    }
+}
@@ -0,0 +1,94 @@
+use super::ForceGenerator;
+use crate::dynamics::RigidBody;
+use crate::geometry::Aabb;
+use crate::math::{Point, Real, Vector};
+
+/// How the magnitude of a [`RadialField`] decreases with the distance to its center.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RadialFalloff {
+    /// The magnitude is the same everywhere within the radius.
+    Constant,
+    /// The magnitude decreases linearly down to zero at the radius.
+    Linear,
+    /// The magnitude decreases quadratically down to zero at the radius.
+    Quadratic,
+}
+
+impl RadialFalloff {
+    /// The falloff factor at the given distance, divided by the radius of the field.
+    pub fn factor(self, normalized_distance: Real) -> Real {
+        if normalized_distance >= 1.0 {
+            return 0.0;
+        }
+
+        match self {
+            RadialFalloff::Constant => 1.0,
+            RadialFalloff::Linear => 1.0 - normalized_distance,
+            RadialFalloff::Quadratic => (1.0 - normalized_distance) * (1.0 - normalized_distance),
+        }
+    }
+}
+
+/// A field pushing the rigid-bodies away from, or pulling them towards, a point.
+///
+/// This can be used for explosions, repulsors, or attractors. Contrary to [`PointGravity`](super::PointGravity),
+/// the force doesn’t depend on the mass of the rigid-bodies, and only applies within a radius.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RadialField {
+    /// The world-space center of the field.
+    pub center: Point<Real>,
+    /// The radius of the field, beyond which it has no effect.
+    pub radius: Real,
+    /// The magnitude of the force at the center of the field.
+    ///
+    /// The force pushes the rigid-bodies away from the center if this is positive, and
+    /// pulls them towards the center if this is negative.
+    pub strength: Real,
+    /// How the magnitude of the force decreases with the distance to the center (default: `Linear`).
+    pub falloff: RadialFalloff,
+}
+
+impl RadialField {
+    /// Creates a new radial field with a linear falloff.
+    pub fn new(center: Point<Real>, radius: Real, strength: Real) -> Self {
+        Self {
+            center,
+            radius,
+            strength,
+            falloff: RadialFalloff::Linear,
+        }
+    }
+
+    /// Sets how the magnitude of the force decreases with the distance to the center.
+    pub fn falloff(mut self, falloff: RadialFalloff) -> Self {
+        self.falloff = falloff;
+        self
+    }
+}
+
+impl ForceGenerator for RadialField {
+    fn clone_dyn(&self) -> Box<dyn ForceGenerator> {
+        Box::new(*self)
+    }
+
+    fn region(&self) -> Option<Aabb> {
+        let half_extents = Vector::repeat(self.radius);
+        Some(Aabb::new(
+            self.center - half_extents,
+            self.center + half_extents,
+        ))
+    }
+
+    fn force(&self, rb: &RigidBody) -> Vector<Real> {
+        let dir = rb.center_of_mass() - self.center;
+        let dist = dir.norm();
+
+        if dist == 0.0 || self.radius <= 0.0 {
+            return Vector::zeros();
+        }
+
+        dir * (self.strength * self.falloff.factor(dist / self.radius) / dist)
+    }
+}
@@ -0,0 +1,109 @@
+use super::ForceGenerator;
+use crate::dynamics::RigidBody;
+use crate::math::{Point, Real, Vector, DIM};
+use na::RealField;
+
+/// A directional wind, with optional turbulences.
+///
+/// The wind applies a drag force proportional to the velocity of the wind relative to the
+/// rigid-body. The turbulences are a deterministic variation of the wind velocity over time
+/// and space, so simulations with the same initial state remain identical.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Wind {
+    /// The average velocity of the wind.
+    pub velocity: Vector<Real>,
+    /// The drag coefficient relating the relative wind velocity to the applied force.
+    pub drag: Real,
+    /// The amplitude of the turbulences, relative to the wind speed (default: `0.0`).
+    ///
+    /// With a value of `0.5`, each component of the wind velocity varies by up to half the
+    /// average wind speed.
+    pub turbulence: Real,
+    /// The number of gusts per second (default: `0.5`).
+    pub turbulence_frequency: Real,
+    /// The distance over which the turbulences vary across space (default: `10.0`).
+    pub turbulence_scale: Real,
+    time: Real,
+}
+
+impl Wind {
+    /// Creates a new wind with the given average velocity and drag coefficient, without turbulences.
+    pub fn new(velocity: Vector<Real>, drag: Real) -> Self {
+        Self {
+            velocity,
+            drag,
+            turbulence: 0.0,
+            turbulence_frequency: 0.5,
+            turbulence_scale: 10.0,
+            time: 0.0,
+        }
+    }
+
+    /// Sets the amplitude of the turbulences, relative to the wind speed.
+    pub fn turbulence(mut self, turbulence: Real) -> Self {
+        self.turbulence = turbulence;
+        self
+    }
+
+    /// Sets the number of gusts per second.
+    pub fn turbulence_frequency(mut self, frequency: Real) -> Self {
+        self.turbulence_frequency = frequency;
+        self
+    }
+
+    /// Sets the distance over which the turbulences vary across space.
+    pub fn turbulence_scale(mut self, scale: Real) -> Self {
+        self.turbulence_scale = scale;
+        self
+    }
+
+    /// The velocity of the wind at the given world-space point.
+    pub fn velocity_at_point(&self, point: &Point<Real>) -> Vector<Real> {
+        if self.turbulence == 0.0 {
+            return self.velocity;
+        }
+
+        // Arbitrary incommensurable factors, so the gusts along each axis don’t line up.
+        const AXIS_PHASES: [Real; 3] = [0.0, 2.17, 4.41];
+        const AXIS_FREQUENCIES: [Real; 3] = [1.0, 1.31, 0.77];
+
+        let amplitude = self.turbulence * self.velocity.norm();
+        let inv_scale = crate::utils::inv(self.turbulence_scale);
+        let mut result = self.velocity;
+
+        for i in 0..DIM {
+            let phase = self.time * self.turbulence_frequency * AXIS_FREQUENCIES[i]
+                + point.coords.iter().sum::<Real>() * inv_scale
+                + point[i] * inv_scale * AXIS_FREQUENCIES[i]
+                + AXIS_PHASES[i];
+            result[i] += amplitude * (phase * Real::two_pi()).sin();
+        }
+
+        result
+    }
+}
+
+impl ForceGenerator for Wind {
+    fn clone_dyn(&self) -> Box<dyn ForceGenerator> {
+        Box::new(*self)
+    }
+
+    fn update(&mut self, dt: Real) {
+        self.time += dt;
+    }
+
+    fn save_state(&self, state: &mut Vec<Real>) {
+        state.push(self.time);
+    }
+
+    fn restore_state(&mut self, state: &[Real]) -> usize {
+        self.time = state[0];
+        1
+    }
+
+    fn force(&self, rb: &RigidBody) -> Vector<Real> {
+        let com = rb.center_of_mass();
+        (self.velocity_at_point(com) - rb.velocity_at_point(com)) * self.drag
+    }
+}
@@ -1,3 +1,4 @@
+use crate::geometry::ContactSoftness;
 use crate::math::Real;
 
 /// Parameters for a time-step of the physics engine.
@@ -44,10 +45,16 @@ pub struct IntegrationParameters {
     /// The maximal distance separating two objects that will generate predictive contacts (default: `0.002`).
     pub prediction_distance: Real,
     /// Maximum number of iterations performed to solve non-penetration and joint constraints (default: `4`).
+    ///
+    /// Ignored if `num_solver_substeps` is greater than `1`: each substep runs a single iteration.
     pub max_velocity_iterations: usize,
     /// Maximum number of iterations performed to solve friction constraints (default: `8`).
+    ///
+    /// Ignored if `num_solver_substeps` is greater than `1`: each substep runs a single iteration.
     pub max_velocity_friction_iterations: usize,
     /// Maximum number of iterations performed to remove the energy introduced by penetration corrections  (default: `1`).
+    ///
+    /// Ignored if `num_solver_substeps` is greater than `1`: each substep runs a single iteration.
     pub max_stabilization_iterations: usize,
     /// If `false`, friction and non-penetration constraints will be solved in the same loop. Otherwise,
     /// non-penetration constraints are solved first, and friction constraints are solved after (default: `true`).
@@ -56,6 +63,20 @@ pub struct IntegrationParameters {
     pub min_island_size: usize,
     /// Maximum number of substeps performed by the  solver (default: `1`).
     pub max_ccd_substeps: usize,
+    /// Number of substeps performed by the constraints solver at each timestep (default: `1`).
+    ///
+    /// If this is `1`, the constraints are solved once per timestep, with the number of iterations
+    /// given by `max_velocity_iterations`, `max_velocity_friction_iterations`, and
+    /// `max_stabilization_iterations`.
+    ///
+    /// If this is greater than `1`, the solver switches to a substepping (TGS-soft) mode: the timestep
+    /// is split into `num_solver_substeps` substeps of length `dt / num_solver_substeps`. Each substep
+    /// re-computes the contact and joint constraints from the current rigid-body poses, then runs a
+    /// single velocity iteration, integrates the positions, and runs a single relaxation iteration
+    /// without the stabilization bias. The iteration counts above are ignored in this mode. This is
+    /// generally more stable than adding iterations for long joint chains or stacks with high mass
+    /// ratios.
+    pub num_solver_substeps: usize,
 }
 
 impl IntegrationParameters {
@@ -134,6 +155,20 @@ impl IntegrationParameters {
         1.0 / (1.0 + cfm_coeff)
     }
 
+    /// The ERP coefficient multiplied by the inverse timestep length, and the CFM factor,
+    /// applied to the contacts with the given softness.
+    ///
+    /// If `softness` is `None`, these are given by [`Self::erp_inv_dt`] and [`Self::cfm_factor`].
+    pub(crate) fn contact_erp_inv_dt_and_cfm_factor(
+        &self,
+        softness: Option<&ContactSoftness>,
+    ) -> (Real, Real) {
+        match softness {
+            Some(softness) => softness.erp_inv_dt_and_cfm_factor(self.dt),
+            None => (self.erp_inv_dt(), self.cfm_factor()),
+        }
+    }
+
     /// The CFM (constraints force mixing) coefficient applied to all joints for constraints regularization
     pub fn joint_cfm_coeff(&self) -> Real {
         // Compute CFM assuming a critically damped spring multiplied by the damping ratio.
@@ -144,6 +179,26 @@ impl IntegrationParameters {
                 * self.joint_damping_ratio
                 * self.joint_damping_ratio)
     }
+
+    /// The parameters used for each substep when `num_solver_substeps` is greater than `1`.
+    ///
+    /// The iteration counts are overridden to `1` on purpose: substepping replaces the
+    /// iterations, and running several iterations per substep would mostly multiply the cost.
+    pub(crate) fn solver_substep_parameters(&self) -> Self {
+        let num_substeps = self.num_solver_substeps.max(1);
+
+        if num_substeps == 1 {
+            return *self;
+        }
+
+        Self {
+            dt: self.dt / num_substeps as Real,
+            max_velocity_iterations: 1,
+            max_velocity_friction_iterations: 1,
+            max_stabilization_iterations: 1,
+            ..*self
+        }
+    }
 }
 
 impl Default for IntegrationParameters {
@@ -169,6 +224,7 @@ impl Default for IntegrationParameters {
             // tons of islands, reducing SIMD parallelism opportunities.
             min_island_size: 128,
             max_ccd_substeps: 1,
+            num_solver_substeps: 1,
         }
     }
 }
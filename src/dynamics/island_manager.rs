@@ -1,3 +1,4 @@
+use crate::data::arena::Arena;
 use crate::dynamics::{
     ImpulseJointSet, MultibodyJointSet, RigidBodyActivation, RigidBodyChanges, RigidBodyColliders,
     RigidBodyHandle, RigidBodyIds, RigidBodySet, RigidBodyType, RigidBodyVelocity,
@@ -5,6 +6,28 @@ use crate::dynamics::{
 use crate::geometry::{ColliderSet, NarrowPhase};
 use crate::math::Real;
 use crate::utils::WDot;
+use parry::utils::hashmap::HashMap;
+
+/// The unique handle of an island of rigid-bodies tracked by the `IslandManager`.
+///
+/// An island is a set of dynamic rigid-bodies connected by contacts or joints. Its handle stays
+/// the same as long as the set of rigid-bodies it contains doesn’t change.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[repr(transparent)]
+pub struct IslandHandle(pub crate::data::arena::Index);
+
+impl IslandHandle {
+    /// Converts this handle into its (index, generation) components.
+    pub fn into_raw_parts(self) -> (u32, u32) {
+        self.0.into_raw_parts()
+    }
+
+    /// Reconstructs an handle from its (index, generation) components.
+    pub fn from_raw_parts(id: u32, generation: u32) -> Self {
+        Self(crate::data::arena::Index::from_raw_parts(id, generation))
+    }
+}
 
 /// Structure responsible for maintaining the set of active rigid-bodies, and
 /// putting non-moving rigid-bodies to sleep to save computation times.
@@ -15,12 +38,40 @@ pub struct IslandManager {
     pub(crate) active_kinematic_set: Vec<RigidBodyHandle>,
     pub(crate) active_islands: Vec<usize>,
     active_set_timestamp: u32,
+    woken_up_bodies: Vec<RigidBodyHandle>,
+    fallen_asleep_bodies: Vec<RigidBodyHandle>,
+    woken_up_islands: Vec<IslandHandle>,
+    fallen_asleep_islands: Vec<IslandHandle>,
+    islands: Arena<Vec<RigidBodyHandle>>,
+    // Number of active set updates since the last call to `clear_sleep_transitions`.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    num_transition_updates: usize,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    components: Vec<usize>, // Workspace.
     #[cfg_attr(feature = "serde-serialize", serde(skip))]
     can_sleep: Vec<RigidBodyHandle>, // Workspace.
     #[cfg_attr(feature = "serde-serialize", serde(skip))]
     stack: Vec<RigidBodyHandle>, // Workspace.
 }
 
+/// The part of the island manager state modified by a simulation step.
+///
+/// This contains the active sets, the islands, and the bodies that woke up or fell asleep
+/// during the last step. The workspaces are not captured.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+pub(crate) struct IslandManagerState {
+    active_dynamic_set: Vec<RigidBodyHandle>,
+    active_kinematic_set: Vec<RigidBodyHandle>,
+    active_islands: Vec<usize>,
+    active_set_timestamp: u32,
+    woken_up_bodies: Vec<RigidBodyHandle>,
+    fallen_asleep_bodies: Vec<RigidBodyHandle>,
+    woken_up_islands: Vec<IslandHandle>,
+    fallen_asleep_islands: Vec<IslandHandle>,
+    islands: Arena<Vec<RigidBodyHandle>>,
+}
+
 impl IslandManager {
     /// Creates a new empty island manager.
     pub fn new() -> Self {
@@ -29,11 +80,58 @@ impl IslandManager {
             active_kinematic_set: vec![],
             active_islands: vec![],
             active_set_timestamp: 0,
+            woken_up_bodies: vec![],
+            fallen_asleep_bodies: vec![],
+            woken_up_islands: vec![],
+            fallen_asleep_islands: vec![],
+            islands: Arena::new(),
+            num_transition_updates: 0,
+            components: vec![],
             can_sleep: vec![],
             stack: vec![],
         }
     }
 
+    /// Copies the part of this island manager modified by a simulation step into `state`.
+    ///
+    /// This reuses the buffers already allocated by `state`.
+    pub(crate) fn save_state(&self, state: &mut IslandManagerState) {
+        state
+            .active_dynamic_set
+            .clone_from(&self.active_dynamic_set);
+        state
+            .active_kinematic_set
+            .clone_from(&self.active_kinematic_set);
+        state.active_islands.clone_from(&self.active_islands);
+        state.active_set_timestamp = self.active_set_timestamp;
+        state.woken_up_bodies.clone_from(&self.woken_up_bodies);
+        state
+            .fallen_asleep_bodies
+            .clone_from(&self.fallen_asleep_bodies);
+        state.woken_up_islands.clone_from(&self.woken_up_islands);
+        state
+            .fallen_asleep_islands
+            .clone_from(&self.fallen_asleep_islands);
+        state.islands.clone_from(&self.islands);
+    }
+
+    /// Restores, in-place, the state saved by [`Self::save_state`].
+    pub(crate) fn restore_state(&mut self, state: &IslandManagerState) {
+        self.active_dynamic_set
+            .clone_from(&state.active_dynamic_set);
+        self.active_kinematic_set
+            .clone_from(&state.active_kinematic_set);
+        self.active_islands.clone_from(&state.active_islands);
+        self.active_set_timestamp = state.active_set_timestamp;
+        self.woken_up_bodies.clone_from(&state.woken_up_bodies);
+        self.fallen_asleep_bodies
+            .clone_from(&state.fallen_asleep_bodies);
+        self.woken_up_islands.clone_from(&state.woken_up_islands);
+        self.fallen_asleep_islands
+            .clone_from(&state.fallen_asleep_islands);
+        self.islands.clone_from(&state.islands);
+    }
+
     pub(crate) fn num_islands(&self) -> usize {
         self.active_islands.len() - 1
     }
@@ -62,6 +160,11 @@ impl IslandManager {
                 }
             }
         }
+
+        self.islands.retain(|_, island| {
+            island.retain(|handle| bodies.get(*handle).is_some());
+            !island.is_empty()
+        });
     }
 
     pub(crate) fn rigid_body_removed(
@@ -84,6 +187,21 @@ impl IslandManager {
                 }
             }
         }
+
+        if let Some(island_handle) = removed_ids.island {
+            if let Some(island) = self.islands.get_mut(island_handle.0) {
+                island.retain(|handle| *handle != removed_handle);
+
+                if island.is_empty() {
+                    self.islands.remove(island_handle.0);
+                }
+            }
+
+            // The rigid-body still exists if it was only disabled.
+            if let Some(rb) = bodies.get_mut_internal(removed_handle) {
+                rb.ids.island = None;
+            }
+        }
     }
 
     /// Forces the specified rigid-body to wake up if it is dynamic.
@@ -122,6 +240,97 @@ impl IslandManager {
         &self.active_dynamic_set[..]
     }
 
+    /// The dynamic rigid-bodies that woke up during the last timestep.
+    ///
+    /// This includes the rigid-bodies simulated for the first time. A rigid-body that woke up and
+    /// fell asleep again during the CCD substeps of the same timestep isn’t listed.
+    pub fn woken_up_bodies(&self) -> &[RigidBodyHandle] {
+        &self.woken_up_bodies[..]
+    }
+
+    /// The dynamic rigid-bodies that fell asleep during the last timestep.
+    ///
+    /// A rigid-body that fell asleep and woke up again during the CCD substeps of the same
+    /// timestep isn’t listed.
+    pub fn fallen_asleep_bodies(&self) -> &[RigidBodyHandle] {
+        &self.fallen_asleep_bodies[..]
+    }
+
+    /// The islands of the rigid-bodies listed by [`Self::woken_up_bodies`], sorted by handle.
+    pub fn woken_up_islands(&self) -> &[IslandHandle] {
+        &self.woken_up_islands[..]
+    }
+
+    /// The islands of the rigid-bodies listed by [`Self::fallen_asleep_bodies`], sorted by handle.
+    pub fn fallen_asleep_islands(&self) -> &[IslandHandle] {
+        &self.fallen_asleep_islands[..]
+    }
+
+    /// Iterates through all the islands of rigid-bodies, including the sleeping ones.
+    ///
+    /// The islands are only computed from the rigid-bodies that were awake during at least one
+    /// timestep: a rigid-body created asleep isn’t part of any island until it wakes up.
+    pub fn islands(&self) -> impl Iterator<Item = (IslandHandle, &[RigidBodyHandle])> {
+        self.islands
+            .iter()
+            .map(|(handle, island)| (IslandHandle(handle), &island[..]))
+    }
+
+    /// The rigid-bodies that are part of the given island.
+    pub fn island_bodies(&self, island: IslandHandle) -> Option<&[RigidBodyHandle]> {
+        self.islands.get(island.0).map(|island| &island[..])
+    }
+
+    /// The island the given rigid-body is part of.
+    pub fn island_of(
+        &self,
+        bodies: &RigidBodySet,
+        handle: RigidBodyHandle,
+    ) -> Option<IslandHandle> {
+        bodies
+            .get(handle)?
+            .ids
+            .island
+            .filter(|island| self.islands.contains(island.0))
+    }
+
+    /// Forces all the rigid-bodies of the given island to fall asleep.
+    ///
+    /// The island is removed from the active set at the beginning of the next timestep, unless
+    /// something wakes it up in the meantime, e.g., a moving kinematic body, a rigid-body that
+    /// can’t sleep, or a contact with another awake island.
+    pub fn sleep_island(&mut self, bodies: &mut RigidBodySet, island: IslandHandle) {
+        if let Some(island) = self.islands.get(island.0) {
+            for handle in island {
+                if let Some(rb) = bodies.get_mut_internal(*handle) {
+                    if rb.is_dynamic() {
+                        rb.sleep();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Forces all the rigid-bodies of the given island to wake up.
+    ///
+    /// If `strong` is `true` then it is assured that these rigid-bodies will
+    /// remain awake during multiple subsequent timesteps.
+    pub fn wake_up_island(
+        &mut self,
+        bodies: &mut RigidBodySet,
+        island_handle: IslandHandle,
+        strong: bool,
+    ) {
+        if let Some(island) = self.islands.get_mut(island_handle.0) {
+            // Take the island temporarily to let `Self::wake_up` borrow `self` mutably.
+            let island = std::mem::take(island);
+            for handle in &island {
+                self.wake_up(bodies, *handle, strong);
+            }
+            self.islands[island_handle.0] = island;
+        }
+    }
+
     pub(crate) fn active_island(&self, island_id: usize) -> &[RigidBodyHandle] {
         let island_range = self.active_islands[island_id]..self.active_islands[island_id + 1];
         &self.active_dynamic_set[island_range]
@@ -140,6 +349,68 @@ impl IslandManager {
         self.active_islands[island_id]..self.active_islands[island_id + 1]
     }
 
+    /// Clears the lists of rigid-bodies and islands that woke up or fell asleep.
+    ///
+    /// This must be called once at the beginning of each timestep: the lists are filled by all
+    /// the CCD substeps of the timestep.
+    pub(crate) fn clear_sleep_transitions(&mut self) {
+        self.woken_up_bodies.clear();
+        self.fallen_asleep_bodies.clear();
+        self.woken_up_islands.clear();
+        self.fallen_asleep_islands.clear();
+        self.num_transition_updates = 0;
+    }
+
+    /// Removes the opposite transitions of a rigid-body happening during the same timestep, and
+    /// recomputes the islands of the rigid-bodies that changed state.
+    fn finalize_sleep_transitions(&mut self, bodies: &RigidBodySet) {
+        self.num_transition_updates += 1;
+
+        // A single update can’t both wake up and put to sleep a rigid-body. Across multiple
+        // CCD substeps the transitions of a rigid-body alternate, so only the net one is kept.
+        if self.num_transition_updates > 1 {
+            let mut net_transitions = HashMap::default();
+            for handle in &self.woken_up_bodies {
+                *net_transitions.entry(*handle).or_insert(0) += 1;
+            }
+            for handle in &self.fallen_asleep_bodies {
+                *net_transitions.entry(*handle).or_insert(0) -= 1;
+            }
+
+            // Resetting the counter keeps only the first occurrence of each rigid-body.
+            self.woken_up_bodies
+                .retain(|handle| match net_transitions.get_mut(handle) {
+                    Some(net) if *net > 0 => {
+                        *net = 0;
+                        true
+                    }
+                    _ => false,
+                });
+            self.fallen_asleep_bodies
+                .retain(|handle| match net_transitions.get_mut(handle) {
+                    Some(net) if *net < 0 => {
+                        *net = 0;
+                        true
+                    }
+                    _ => false,
+                });
+        }
+
+        for (transitioned_bodies, transitioned_islands) in [
+            (&self.woken_up_bodies, &mut self.woken_up_islands),
+            (&self.fallen_asleep_bodies, &mut self.fallen_asleep_islands),
+        ] {
+            transitioned_islands.clear();
+            transitioned_islands.extend(
+                transitioned_bodies
+                    .iter()
+                    .filter_map(|handle| bodies[*handle].ids.island),
+            );
+            transitioned_islands.sort_unstable_by_key(|island| island.into_raw_parts());
+            transitioned_islands.dedup();
+        }
+    }
+
     pub(crate) fn update_active_set_with_contacts(
         &mut self,
         dt: Real,
@@ -158,6 +429,8 @@ impl IslandManager {
         // Update the energy of every rigid body and
         // keep only those that may not sleep.
         //        let t = instant::now();
+        // The bodies with this timestamp were awake at the end of the last update.
+        let awake_timestamp = self.active_set_timestamp;
         self.active_set_timestamp += 1;
         self.stack.clear();
         self.can_sleep.clear();
@@ -234,6 +507,8 @@ impl IslandManager {
         // traversal of the interaction graph.
         self.active_islands.clear();
         self.active_islands.push(0);
+        self.components.clear();
+        self.components.push(0);
 
         // The max avoid underflow when the stack is empty.
         let mut island_marker = self.stack.len().max(1) - 1;
@@ -248,6 +523,11 @@ impl IslandManager {
             }
 
             if self.stack.len() < island_marker {
+                // We are starting a new connected component.
+                if self.active_dynamic_set.len() > *self.components.last().unwrap() {
+                    self.components.push(self.active_dynamic_set.len());
+                }
+
                 if self.active_dynamic_set.len() - *self.active_islands.last().unwrap()
                     >= min_island_size
                 {
@@ -271,6 +551,10 @@ impl IslandManager {
                 self.stack.push(other);
             }
 
+            if awake_timestamp == 0 || rb.ids.active_set_timestamp != awake_timestamp {
+                self.woken_up_bodies.push(handle);
+            }
+
             rb.activation.wake_up(false);
             rb.ids.active_island_id = self.active_islands.len() - 1;
             rb.ids.active_set_id = self.active_dynamic_set.len();
@@ -282,6 +566,9 @@ impl IslandManager {
         }
 
         self.active_islands.push(self.active_dynamic_set.len());
+        self.components.push(self.active_dynamic_set.len());
+        self.update_islands(bodies);
+
         //        println!(
         //            "Extraction: {}, num islands: {}",
         //            instant::now() - t,
@@ -294,6 +581,80 @@ impl IslandManager {
             if rb.activation.sleeping {
                 rb.vels = RigidBodyVelocity::zero();
                 rb.activation.sleep();
+
+                if awake_timestamp != 0 && rb.ids.active_set_timestamp == awake_timestamp {
+                    self.fallen_asleep_bodies.push(*handle);
+                }
+            }
+        }
+
+        self.finalize_sleep_transitions(bodies);
+    }
+
+    /// Updates the islands from the connected components of the active set.
+    ///
+    /// The islands of the sleeping rigid-bodies are left untouched. The island of a connected
+    /// component keeps its handle if it still contains exactly the same rigid-bodies.
+    fn update_islands(&mut self, bodies: &mut RigidBodySet) {
+        let mut dismantled = vec![];
+
+        for bounds in self.components.windows(2) {
+            let component = &self.active_dynamic_set[bounds[0]..bounds[1]];
+
+            if component.is_empty() {
+                continue;
+            }
+
+            let prev_island = bodies[component[0]].ids.island;
+            let unchanged = prev_island
+                .and_then(|island| self.islands.get(island.0))
+                .map(|island| island.len())
+                == Some(component.len())
+                && component
+                    .iter()
+                    .all(|handle| bodies[*handle].ids.island == prev_island);
+
+            if unchanged {
+                continue;
+            }
+
+            // Remove the previous islands of these rigid-bodies, and create a new one.
+            for handle in component {
+                if let Some(island) = bodies[*handle].ids.island {
+                    if let Some(members) = self.islands.remove(island.0) {
+                        dismantled.push(members);
+                    }
+                }
+            }
+
+            let island = IslandHandle(self.islands.insert(component.to_vec()));
+            for handle in component {
+                bodies.index_mut_internal(*handle).ids.island = Some(island);
+            }
+        }
+
+        // The remaining sleeping rigid-bodies of the removed islands are regrouped into one
+        // island per removed island.
+        for mut members in dismantled {
+            members.retain(|handle| match bodies.get_mut_internal(*handle) {
+                Some(rb)
+                    if matches!(rb.ids.island, Some(island) if !self.islands.contains(island.0)) =>
+                {
+                    if rb.is_dynamic() && rb.is_enabled() {
+                        true
+                    } else {
+                        rb.ids.island = None;
+                        false
+                    }
+                }
+                _ => false,
+            });
+
+            if !members.is_empty() {
+                let island = IslandHandle(self.islands.insert(members));
+                for handle in &self.islands[island.0] {
+                    bodies.index_mut_internal(*handle).ids.island = Some(island);
+                }
             }
         }
     }
@@ -308,3 +669,137 @@ fn update_energy(activation: &mut RigidBodyActivation, sq_linvel: Real, sq_angve
         activation.time_since_can_sleep = 0.0;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_utils;
+
+    #[test]
+    fn sleep_and_wake_up_notifications() {
+        let mut world = test_utils::world();
+        world.insert_collider(ColliderBuilder::ball(10.0).translation(Vector::y() * -10.0));
+        let rb = world.insert_rigid_body(RigidBodyBuilder::dynamic().translation(Vector::y()));
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.5), rb);
+
+        world.step(&(), &());
+        assert_eq!(world.islands.woken_up_bodies(), &[rb]);
+        let island = world.islands.island_of(&world.bodies, rb).unwrap();
+        assert_eq!(world.islands.woken_up_islands(), &[island]);
+
+        // The ball falls asleep once, after resting on the ground.
+        let mut num_sleeps = 0;
+        for _ in 0..500 {
+            world.step(&(), &());
+            assert!(world.islands.woken_up_bodies().is_empty());
+            assert!(world.islands.woken_up_islands().is_empty());
+            num_sleeps += world.islands.fallen_asleep_bodies().len();
+            if !world.islands.fallen_asleep_bodies().is_empty() {
+                assert_eq!(world.islands.fallen_asleep_islands(), &[island]);
+            }
+        }
+        assert_eq!(num_sleeps, 1);
+        assert!(world.bodies[rb].is_sleeping());
+
+        // Opposite transitions of the same substepped timestep cancel each other.
+        world.islands.woken_up_bodies.extend([rb, rb]);
+        world.islands.fallen_asleep_bodies.push(rb);
+        world.islands.num_transition_updates = 1;
+        world.islands.finalize_sleep_transitions(&world.bodies);
+        assert_eq!(world.islands.woken_up_bodies(), &[rb]);
+        assert!(world.islands.fallen_asleep_bodies().is_empty());
+        world.islands.fallen_asleep_bodies.push(rb);
+        world.islands.finalize_sleep_transitions(&world.bodies);
+        assert!(world.islands.woken_up_bodies().is_empty());
+        assert!(world.islands.fallen_asleep_bodies().is_empty());
+        assert!(world.islands.woken_up_islands().is_empty());
+
+        world.bodies[rb].wake_up(true);
+        world.step(&(), &());
+        assert_eq!(world.islands.woken_up_bodies(), &[rb]);
+        assert!(world.islands.fallen_asleep_bodies().is_empty());
+
+        world.step(&(), &());
+        assert!(world.islands.woken_up_bodies().is_empty());
+
+        // The notifications are kept for the whole timestep, even with several CCD substeps.
+        world.integration_parameters.max_ccd_substeps = 4;
+        let wall = test_utils::cuboid(0.1, 10.0);
+        world.insert_collider(wall.translation(Vector::x() * 20.0));
+        let bullet = world.insert_rigid_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 5.0 - Vector::x() * 10.0)
+                .linvel(Vector::x() * 1000.0)
+                .ccd_enabled(true),
+        );
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.1), bullet);
+        world.bodies[rb].sleep();
+        world.step(&(), &());
+        assert_eq!(world.islands.woken_up_bodies(), &[bullet]);
+        assert_eq!(world.islands.fallen_asleep_bodies(), &[rb]);
+
+        // The bullet hits the wall during this timestep.
+        world.bodies[rb].wake_up(true);
+        world.step(&(), &());
+        assert_eq!(world.islands.woken_up_bodies(), &[rb]);
+    }
+
+    #[test]
+    fn island_queries() {
+        let mut world = test_utils::world();
+        world.insert_collider(ColliderBuilder::halfspace(Vector::y_axis()));
+        let mut ball = |x: Real, y: Real| {
+            let rb = world.insert_rigid_body(
+                RigidBodyBuilder::dynamic().translation(Vector::x() * x + Vector::y() * y),
+            );
+            world.insert_collider_with_parent(ColliderBuilder::ball(0.5), rb);
+            rb
+        };
+
+        // Two stacked balls, and a lone ball far away from them.
+        let bottom = ball(0.0, 0.5);
+        let top = ball(0.0, 1.5);
+        let lone = ball(10.0, 0.5);
+
+        world.step(&(), &());
+        let stack = world.islands.island_of(&world.bodies, bottom).unwrap();
+        let lone_island = world.islands.island_of(&world.bodies, lone).unwrap();
+        assert_eq!(world.islands.island_of(&world.bodies, top), Some(stack));
+        assert_ne!(stack, lone_island);
+        assert_eq!(world.islands.islands().count(), 2);
+        assert_eq!(world.islands.island_bodies(lone_island), Some(&[lone][..]));
+
+        // The handles are stable while the islands don’t change.
+        for _ in 0..10 {
+            world.step(&(), &());
+            assert_eq!(world.islands.island_of(&world.bodies, bottom), Some(stack));
+            assert_eq!(
+                world.islands.island_of(&world.bodies, lone),
+                Some(lone_island)
+            );
+        }
+
+        // Sleeping islands are still enumerated.
+        world.islands.sleep_island(&mut world.bodies, stack);
+        world.step(&(), &());
+        assert!(world.bodies[bottom].is_sleeping() && world.bodies[top].is_sleeping());
+        assert!(!world.bodies[lone].is_sleeping());
+        assert!(!world.islands.active_dynamic_bodies().contains(&bottom));
+        assert_eq!(world.islands.island_of(&world.bodies, top), Some(stack));
+        assert_eq!(world.islands.islands().count(), 2);
+
+        world.islands.wake_up_island(&mut world.bodies, stack, true);
+        world.step(&(), &());
+        assert!(!world.bodies[bottom].is_sleeping() && !world.bodies[top].is_sleeping());
+        assert_eq!(world.islands.island_of(&world.bodies, top), Some(stack));
+
+        // Removing a rigid-body removes it from its island.
+        world.remove_rigid_body(top, true);
+        world.step(&(), &());
+        let island = world.islands.island_of(&world.bodies, bottom).unwrap();
+        assert_eq!(world.islands.island_bodies(island), Some(&[bottom][..]));
+        assert_eq!(world.islands.islands().count(), 2);
+    }
+}
@@ -108,6 +108,20 @@ impl FixedJointBuilder {
         self
     }
 
+    /// Sets the magnitude of the force applied by the joint beyond which it breaks.
+    #[must_use]
+    pub fn break_force(mut self, break_force: Real) -> Self {
+        self.0.data.set_break_force(break_force);
+        self
+    }
+
+    /// Sets the magnitude of the torque applied by the joint beyond which it breaks.
+    #[must_use]
+    pub fn break_torque(mut self, break_torque: Real) -> Self {
+        self.0.data.set_break_torque(break_torque);
+        self
+    }
+
     /// Sets the joint’s frame, expressed in the first rigid-body’s local-space.
     #[must_use]
     pub fn local_frame1(mut self, local_frame: Isometry<Real>) -> Self {
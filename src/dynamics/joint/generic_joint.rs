@@ -223,6 +223,10 @@ pub struct GenericJoint {
     pub contacts_enabled: bool,
     /// Whether or not the joint is enabled.
     pub enabled: JointEnabled,
+    /// The magnitude of the force applied by this joint beyond which it breaks (default: `Real::MAX`).
+    pub break_force: Real,
+    /// The magnitude of the torque applied by this joint beyond which it breaks (default: `Real::MAX`).
+    pub break_torque: Real,
 }
 
 impl Default for GenericJoint {
@@ -238,6 +242,8 @@ impl Default for GenericJoint {
             motors: [JointMotor::default(); SPATIAL_DIM],
             contacts_enabled: true,
             enabled: JointEnabled::Enabled,
+            break_force: Real::MAX,
+            break_torque: Real::MAX,
         }
     }
 }
@@ -374,6 +380,29 @@ impl GenericJoint {
         self
     }
 
+    /// Is this joint breakable?
+    pub fn is_breakable(&self) -> bool {
+        self.break_force < Real::MAX || self.break_torque < Real::MAX
+    }
+
+    /// Sets the magnitude of the force applied by this joint beyond which it breaks.
+    ///
+    /// A broken impulse joint is disabled, and a broken multibody joint is removed from its
+    /// set. In both cases, a joint broken event is emitted.
+    pub fn set_break_force(&mut self, break_force: Real) -> &mut Self {
+        self.break_force = break_force;
+        self
+    }
+
+    /// Sets the magnitude of the torque applied by this joint beyond which it breaks.
+    ///
+    /// A broken impulse joint is disabled, and a broken multibody joint is removed from its
+    /// set. In both cases, a joint broken event is emitted.
+    pub fn set_break_torque(&mut self, break_torque: Real) -> &mut Self {
+        self.break_torque = break_torque;
+        self
+    }
+
     /// The joint limits along the specified axis.
     #[must_use]
     pub fn limits(&self, axis: JointAxis) -> Option<&JointLimits<Real>> {
@@ -563,6 +592,20 @@ impl GenericJointBuilder {
         self
     }
 
+    /// Sets the magnitude of the force applied by the joint beyond which it breaks.
+    #[must_use]
+    pub fn break_force(mut self, break_force: Real) -> Self {
+        self.0.set_break_force(break_force);
+        self
+    }
+
+    /// Sets the magnitude of the torque applied by the joint beyond which it breaks.
+    #[must_use]
+    pub fn break_torque(mut self, break_torque: Real) -> Self {
+        self.0.set_break_torque(break_torque);
+        self
+    }
+
     /// Sets the joint’s frame, expressed in the first rigid-body’s local-space.
     #[must_use]
     pub fn local_frame1(mut self, local_frame: Isometry<Real>) -> Self {
@@ -0,0 +1,276 @@
+use crate::dynamics::{
+    GenericJoint, ImpulseJointHandle, ImpulseJointSet, IslandManager, JointIndex, MultibodyIndex,
+    MultibodyJointHandle, MultibodyJointSet, RigidBodySet, RigidBodyVelocity,
+};
+use crate::geometry::ColliderSet;
+use crate::math::{AngVector, Point, Real, Vector, DIM, SPATIAL_DIM};
+use crate::pipeline::EventHandler;
+use crate::utils::{WCross, WDot};
+
+/// The handle of a joint that broke.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BrokenJoint {
+    /// A broken impulse joint. It is disabled, but still part of its set.
+    Impulse(ImpulseJointHandle),
+    /// A broken multibody joint. It was removed from its set.
+    Multibody(MultibodyJointHandle),
+}
+
+/// Event occurring when the force or torque applied by a joint exceeds its break threshold.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JointBrokenEvent {
+    /// The joint that broke.
+    pub joint: BrokenJoint,
+    /// The world-space linear impulse applied by the joint to its second rigid-body during
+    /// the timestep it broke.
+    pub linear_impulse: Vector<Real>,
+    /// The world-space angular impulse applied by the joint to its second rigid-body during
+    /// the timestep it broke.
+    pub angular_impulse: AngVector<Real>,
+}
+
+/// Detects and breaks the joints applying forces or torques exceeding their thresholds.
+///
+/// The impulses applied by impulse joints are read from the constraints solver. Multibody
+/// joints don’t have constraints, so the impulse they apply is deduced from the change of
+/// momentum of the links they support, minus the external forces applied to these links.
+/// Contacts involving these links are not accounted for.
+pub(crate) struct JointBreaker {
+    /// The multibodies with breakable joints, and the index of their root in `velocities`.
+    multibodies: Vec<(MultibodyIndex, usize)>,
+    /// The velocities of the multibody links at the beginning of the timestep.
+    velocities: Vec<RigidBodyVelocity>,
+    /// The linear and angular impulses applied to the subtree of each multibody link.
+    subtree_impulses: Vec<(Vector<Real>, AngVector<Real>)>,
+    broken_joints: Vec<JointBrokenEvent>,
+}
+
+impl JointBreaker {
+    pub fn new() -> Self {
+        Self {
+            multibodies: vec![],
+            velocities: vec![],
+            subtree_impulses: vec![],
+            broken_joints: vec![],
+        }
+    }
+
+    /// Records the velocities of the awake multibodies with breakable joints.
+    ///
+    /// This must be called before the constraints solver modifies the velocities.
+    pub fn init(&mut self, bodies: &RigidBodySet, multibody_joints: &MultibodyJointSet) {
+        self.multibodies.clear();
+        self.velocities.clear();
+
+        for (id, multibody) in multibody_joints.multibodies.iter() {
+            let breakable = multibody
+                .links()
+                .skip(1)
+                .any(|link| link.joint.data.is_breakable());
+
+            // NOTE: a multibody with a breakable joint has at least two links.
+            if breakable && !bodies[multibody.link(1).unwrap().rigid_body].is_sleeping() {
+                self.multibodies
+                    .push((MultibodyIndex(id), self.velocities.len()));
+                self.velocities
+                    .extend(multibody.links().map(|link| bodies[link.rigid_body].vels));
+            }
+        }
+    }
+
+    /// Breaks the joints applying forces or torques exceeding their thresholds, and emits the
+    /// corresponding events.
+    ///
+    /// This must be called after the constraints solver wrote back the joint impulses.
+    pub fn break_joints(
+        &mut self,
+        dt: Real,
+        islands: &IslandManager,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        joint_constraint_indices: &[Vec<JointIndex>],
+        multibody_joints: &mut MultibodyJointSet,
+        events: &dyn EventHandler,
+    ) {
+        let joints = impulse_joints.joints_mut();
+
+        for island in &joint_constraint_indices[..islands.num_islands()] {
+            for joint_id in island {
+                let joint = &mut joints[*joint_id].weight;
+
+                if !joint.data.is_breakable() {
+                    continue;
+                }
+
+                let mut impulses = joint.impulses;
+                for i in 0..SPATIAL_DIM {
+                    impulses[i] += joint.data.limits[i].impulse + joint.data.motors[i].impulse;
+                }
+
+                // The solver applies positive impulses to the first body, along the axes of
+                // the first local frame.
+                let frame1 = bodies[joint.body1].position() * joint.data.local_frame1;
+                let linear_impulse = -(frame1.rotation * impulses.fixed_rows::<DIM>(0));
+                #[cfg(feature = "dim2")]
+                let angular_impulse = -impulses[DIM];
+                #[cfg(feature = "dim3")]
+                let angular_impulse = -(frame1.rotation * impulses.fixed_rows::<3>(DIM));
+
+                if exceeds_thresholds(&joint.data, dt, &linear_impulse, &angular_impulse) {
+                    joint.data.set_enabled(false);
+                    self.broken_joints.push(JointBrokenEvent {
+                        joint: BrokenJoint::Impulse(joint.handle),
+                        linear_impulse,
+                        angular_impulse,
+                    });
+                }
+            }
+        }
+
+        for (id, first_velocity) in &self.multibodies {
+            let multibody = match multibody_joints.get_multibody(*id) {
+                Some(multibody) => multibody,
+                None => continue,
+            };
+
+            // The impulse applied to each link by the rest of the multibody.
+            self.subtree_impulses.clear();
+            for link in multibody.links() {
+                let rb = &bodies[link.rigid_body];
+                let init_vels = &self.velocities[first_velocity + link.internal_id];
+                let vels = multibody.link_velocity(link.internal_id);
+                let linear_impulse = (vels.linvel - init_vels.linvel)
+                    .component_mul(&rb.mprops.effective_mass())
+                    - rb.forces.force * dt;
+                let angular_impulse = rb.mprops.effective_angular_inertia()
+                    * (vels.angvel - init_vels.angvel)
+                    - rb.forces.torque * dt
+                    + rb.mprops.world_com.coords.gcross(linear_impulse);
+                self.subtree_impulses
+                    .push((linear_impulse, angular_impulse));
+            }
+
+            // Accumulate these impulses through the subtrees. The parent of a link
+            // always has a smaller id.
+            for i in (1..self.subtree_impulses.len()).rev() {
+                let parent = multibody.link(i).unwrap().parent_internal_id;
+                let (linear_impulse, angular_impulse) = self.subtree_impulses[i];
+                self.subtree_impulses[parent].0 += linear_impulse;
+                self.subtree_impulses[parent].1 += angular_impulse;
+            }
+
+            for link in multibody.links().skip(1) {
+                if !link.joint.data.is_breakable() {
+                    continue;
+                }
+
+                // The subtree impulses are expressed at the origin, move them to the anchor.
+                let (linear_impulse, angular_impulse) = self.subtree_impulses[link.internal_id];
+                let anchor = bodies[link.rigid_body].position()
+                    * Point::from(link.joint.data.local_frame2.translation.vector);
+                let angular_impulse = angular_impulse - anchor.coords.gcross(linear_impulse);
+
+                if exceeds_thresholds(&link.joint.data, dt, &linear_impulse, &angular_impulse) {
+                    self.broken_joints.push(JointBrokenEvent {
+                        joint: BrokenJoint::Multibody(MultibodyJointHandle(link.rigid_body.0)),
+                        linear_impulse,
+                        angular_impulse,
+                    });
+                }
+            }
+        }
+
+        for event in self.broken_joints.drain(..) {
+            if let BrokenJoint::Multibody(handle) = event.joint {
+                multibody_joints.remove(handle, true);
+            }
+
+            events.handle_joint_broken_event(bodies, colliders, event);
+        }
+    }
+}
+
+fn exceeds_thresholds(
+    data: &GenericJoint,
+    dt: Real,
+    linear_impulse: &Vector<Real>,
+    angular_impulse: &AngVector<Real>,
+) -> bool {
+    // NOTE: the strict inequalities are important here, so we don’t
+    //       break a joint applying no force if its thresholds are 0.0.
+    linear_impulse.norm() > data.break_force * dt
+        || angular_impulse.gdot(*angular_impulse).sqrt() > data.break_torque * dt
+}
+
+#[cfg(test)]
+mod test {
+    use super::BrokenJoint;
+    use crate::dynamics::{FixedJointBuilder, RigidBodyBuilder};
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_utils;
+
+    #[test]
+    fn joints_break_under_heavy_loads() {
+        let mut world = test_utils::world();
+        let ground = world.insert_rigid_body(RigidBodyBuilder::fixed());
+
+        // Bodies with a unit mass hanging below the ground, with joints supporting
+        // a force of 5 or 20.
+        let mut hang = |x: Real, break_force: Real, multibody: bool| {
+            let body = world.insert_rigid_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * x - Vector::y())
+                    .additional_mass(1.0),
+            );
+            let joint = FixedJointBuilder::new()
+                .local_anchor1(Point::from(Vector::x() * x))
+                .local_anchor2(Point::from(Vector::y()))
+                .break_force(break_force);
+
+            if multibody {
+                let handle = world.insert_multibody_joint(ground, body, joint, true);
+                BrokenJoint::Multibody(handle.unwrap())
+            } else {
+                BrokenJoint::Impulse(world.insert_impulse_joint(ground, body, joint, true))
+            }
+        };
+
+        let weak_impulse_joint = hang(0.0, 5.0, false);
+        let strong_impulse_joint = hang(2.0, 20.0, false);
+        let weak_multibody_joint = hang(4.0, 5.0, true);
+        let strong_multibody_joint = hang(6.0, 20.0, true);
+
+        let events = test_utils::EventChannels::new();
+        for _ in 0..10 {
+            world.step(&(), &events.collector);
+        }
+
+        // The weak joints break at the first timestep, while supporting the weight of their body.
+        let broken: Vec<_> = events.broken_joints.try_iter().collect();
+        assert_eq!(broken.len(), 2);
+        assert!(broken.iter().any(|event| event.joint == weak_impulse_joint));
+        assert!(broken
+            .iter()
+            .any(|event| event.joint == weak_multibody_joint));
+        assert!(!broken
+            .iter()
+            .any(|event| event.joint == strong_impulse_joint));
+        assert!(!broken
+            .iter()
+            .any(|event| event.joint == strong_multibody_joint));
+
+        let expected = Vector::y() * 10.0 * world.integration_parameters.dt;
+        for event in &broken {
+            assert!((event.linear_impulse - expected).norm() < 1.0e-2 * expected.norm());
+        }
+
+        if let BrokenJoint::Impulse(handle) = weak_impulse_joint {
+            assert!(!world.impulse_joints.get(handle).unwrap().data.is_enabled());
+        }
+        if let BrokenJoint::Multibody(handle) = weak_multibody_joint {
+            assert!(world.multibody_joints.get(handle).is_none());
+        }
+    }
+}
@@ -1,6 +1,8 @@
 pub use self::fixed_joint::*;
 pub use self::generic_joint::*;
 pub use self::impulse_joint::*;
+pub(crate) use self::joint_breaker::JointBreaker;
+pub use self::joint_breaker::{BrokenJoint, JointBrokenEvent};
 pub use self::motor_model::MotorModel;
 pub use self::multibody_joint::*;
 pub use self::prismatic_joint::*;
@@ -13,6 +15,7 @@ pub use self::spherical_joint::*;
 mod fixed_joint;
 mod generic_joint;
 mod impulse_joint;
+mod joint_breaker;
 mod motor_model;
 mod multibody_joint;
 mod prismatic_joint;
@@ -754,6 +754,13 @@ impl Multibody {
         self.velocities.rows_mut(0, self.ndofs)
     }
 
+    /// The world-space velocity of the given link, computed from the current generalized
+    /// velocities and body jacobians.
+    pub(crate) fn link_velocity(&self, link_id: usize) -> RigidBodyVelocity {
+        let velocity = &self.body_jacobians[link_id] * self.generalized_velocity();
+        RigidBodyVelocity::from_slice(velocity.as_slice())
+    }
+
     #[inline]
     pub(crate) fn integrate(&mut self, dt: Real) {
         for rb in self.links.iter_mut() {
@@ -1,7 +1,10 @@
 use crate::data::{Arena, Coarena, Index};
 use crate::dynamics::joint::MultibodyLink;
-use crate::dynamics::{GenericJoint, Multibody, MultibodyJoint, RigidBodyHandle};
+use crate::dynamics::{
+    GenericJoint, Multibody, MultibodyJoint, RigidBodyHandle, RigidBodySet, RigidBodyVelocity,
+};
 use crate::geometry::{InteractionGraph, RigidBodyGraphIndex};
+use crate::math::{Isometry, Real, Rotation, SpacialVector, Vector};
 use crate::parry::partitioning::IndexedData;
 
 /// The unique handle of an multibody_joint added to a `MultibodyJointSet`.
@@ -72,6 +75,69 @@ impl Default for MultibodyJointLink {
     }
 }
 
+/// The part of the state of a multibody link modified by a simulation step.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone)]
+struct MultibodyLinkState {
+    rigid_body: RigidBodyHandle,
+    coords: SpacialVector<Real>,
+    joint_rot: Rotation<Real>,
+    local_to_world: Isometry<Real>,
+    local_to_parent: Isometry<Real>,
+    shift02: Vector<Real>,
+    shift23: Vector<Real>,
+    joint_velocity: RigidBodyVelocity,
+}
+
+/// The layout of the saved state of a multibody.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone)]
+struct MultibodyStateLayout {
+    root_is_dynamic: bool,
+    num_links: usize,
+    num_root_dofs: usize,
+    num_dofs: usize,
+}
+
+/// The part of the multibody joint set state modified by a simulation step.
+///
+/// This contains the joint coordinates and link poses of every multibody, as well as their
+/// generalized velocities and accelerations.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+pub(crate) struct MultibodyJointSetState {
+    multibodies: Vec<MultibodyStateLayout>,
+    links: Vec<MultibodyLinkState>,
+    // The velocities then accelerations of each multibody, one after the other.
+    dofs: Vec<Real>,
+}
+
+impl MultibodyJointSetState {
+    /// Checks if this state has been saved from a multibody joint set with the same links
+    /// (attached to the same rigid-bodies) and degrees of freedom as `set`.
+    ///
+    /// The degrees of freedom of the multibody roots are ignored since they depend on the
+    /// type of their rigid-body, and are only updated at the beginning of each timestep.
+    pub fn is_compatible_with(&self, set: &MultibodyJointSet) -> bool {
+        let mut links = self.links.iter();
+
+        self.multibodies.len() == set.multibodies.len()
+            && self
+                .multibodies
+                .iter()
+                .zip(set.multibodies())
+                .all(|(layout, mb)| {
+                    layout.num_links == mb.num_links()
+                        && layout.num_dofs - layout.num_root_dofs
+                            == mb.velocities.len() - mb.root().joint().ndofs()
+                        && mb
+                            .links()
+                            .zip(&mut links)
+                            .all(|(link, saved)| link.rigid_body == saved.rigid_body)
+                })
+    }
+}
+
 #[derive(Default)]
 /// A set of rigid bodies that can be handled by a physics pipeline.
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -194,8 +260,14 @@ impl MultibodyJointSet {
                 for multibody in multibodies {
                     if multibody.num_links() == 1 {
                         // We don’t have any multibody_joint attached to this body, remove it.
-                        if let Some(other) = self.connectivity_graph.remove_node(removed.graph_id) {
-                            self.rb2mb.get_mut(other.0).unwrap().graph_id = removed.graph_id;
+                        let isolated_rb = multibody.root().rigid_body;
+                        let isolated = self
+                            .rb2mb
+                            .remove(isolated_rb.0, Default::default())
+                            .unwrap();
+                        if let Some(other) = self.connectivity_graph.remove_node(isolated.graph_id)
+                        {
+                            self.rb2mb.get_mut(other.0).unwrap().graph_id = isolated.graph_id;
                         }
                     } else {
                         let mb_id = self.multibodies.insert(multibody);
@@ -403,6 +475,91 @@ impl MultibodyJointSet {
     pub fn multibodies(&self) -> impl Iterator<Item = &Multibody> {
         self.multibodies.iter().map(|e| e.1)
     }
+
+    /// Copies the part of this set modified by a simulation step into `state`.
+    ///
+    /// This reuses the buffers already allocated by `state`.
+    pub(crate) fn save_state(&self, state: &mut MultibodyJointSetState) {
+        state.multibodies.clear();
+        state.links.clear();
+        state.dofs.clear();
+
+        for mb in self.multibodies() {
+            state.multibodies.push(MultibodyStateLayout {
+                root_is_dynamic: mb.root_is_dynamic,
+                num_links: mb.num_links(),
+                num_root_dofs: mb.root().joint().ndofs(),
+                num_dofs: mb.velocities.len(),
+            });
+            state
+                .links
+                .extend(mb.links().map(|link| MultibodyLinkState {
+                    rigid_body: link.rigid_body,
+                    coords: link.joint.coords,
+                    joint_rot: link.joint.joint_rot,
+                    local_to_world: link.local_to_world,
+                    local_to_parent: link.local_to_parent,
+                    shift02: link.shift02,
+                    shift23: link.shift23,
+                    joint_velocity: link.joint_velocity,
+                }));
+            state.dofs.extend_from_slice(mb.velocities.as_slice());
+            state.dofs.extend_from_slice(mb.accelerations.as_slice());
+        }
+    }
+
+    /// Restores, in-place, the state saved by [`Self::save_state`].
+    ///
+    /// The state must be compatible with this set, see [`MultibodyJointSetState::is_compatible_with`].
+    pub(crate) fn restore_state(
+        &mut self,
+        state: &MultibodyJointSetState,
+        bodies: &mut RigidBodySet,
+    ) {
+        let mut links = state.links.iter();
+        let mut dofs = state.dofs.as_slice();
+
+        for ((_, mb), layout) in self.multibodies.iter_mut().zip(&state.multibodies) {
+            // Give the root the degrees of freedom matching its rigid-body type, like the
+            // beginning of the next timestep would.
+            mb.update_root_type(bodies);
+            // If the root type changed since the state was saved, the root degrees of freedom
+            // are reset like `Multibody::update_root_type` does.
+            let reset_root = mb.root_is_dynamic != layout.root_is_dynamic;
+
+            for (i, (link, saved)) in mb.links_mut().zip(&mut links).enumerate() {
+                if i != 0 || !reset_root {
+                    link.joint.coords = saved.coords;
+                    link.joint.joint_rot = saved.joint_rot;
+                }
+                link.local_to_world = saved.local_to_world;
+                link.local_to_parent = saved.local_to_parent;
+                link.shift02 = saved.shift02;
+                link.shift23 = saved.shift23;
+                link.joint_velocity = saved.joint_velocity;
+            }
+
+            let (velocities, rest) = dofs.split_at(layout.num_dofs);
+            let (accelerations, rest) = rest.split_at(layout.num_dofs);
+            dofs = rest;
+
+            if reset_root {
+                let num_root_dofs = mb.root().joint().ndofs();
+                let num_other_dofs = layout.num_dofs - layout.num_root_dofs;
+                mb.velocities.rows_mut(0, num_root_dofs).fill(0.0);
+                mb.velocities
+                    .rows_mut(num_root_dofs, num_other_dofs)
+                    .copy_from_slice(&velocities[layout.num_root_dofs..]);
+                mb.accelerations.rows_mut(0, num_root_dofs).fill(0.0);
+                mb.accelerations
+                    .rows_mut(num_root_dofs, num_other_dofs)
+                    .copy_from_slice(&accelerations[layout.num_root_dofs..]);
+            } else {
+                mb.velocities.copy_from_slice(velocities);
+                mb.accelerations.copy_from_slice(accelerations);
+            }
+        }
+    }
 }
 
 impl std::ops::Index<MultibodyIndex> for MultibodyJointSet {
@@ -201,6 +201,20 @@ impl RopeJointBuilder {
         self
     }
 
+    /// Sets the magnitude of the force applied by the joint beyond which it breaks.
+    #[must_use]
+    pub fn break_force(mut self, break_force: Real) -> Self {
+        self.0.data.set_break_force(break_force);
+        self
+    }
+
+    /// Sets the magnitude of the torque applied by the joint beyond which it breaks.
+    #[must_use]
+    pub fn break_torque(mut self, break_torque: Real) -> Self {
+        self.0.data.set_break_torque(break_torque);
+        self
+    }
+
     /// Sets the joint’s anchor, expressed in the local-space of the first rigid-body.
     #[must_use]
     pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
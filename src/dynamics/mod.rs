@@ -1,9 +1,14 @@
 //! Structures related to dynamics: bodies, impulse_joints, etc.
 
+pub(crate) use self::aerodynamics::apply_aerodynamic_forces;
+pub use self::aerodynamics::{LiftSurface, RigidBodyAerodynamics};
 pub use self::ccd::CCDSolver;
 pub use self::coefficient_combine_rule::CoefficientCombineRule;
+pub use self::force_generators::*;
 pub use self::integration_parameters::IntegrationParameters;
-pub use self::island_manager::IslandManager;
+pub(crate) use self::island_manager::IslandManagerState;
+pub use self::island_manager::{IslandHandle, IslandManager};
+pub(crate) use self::joint::JointBreaker;
 pub(crate) use self::joint::JointGraphEdge;
 pub(crate) use self::joint::JointIndex;
 pub use self::joint::*;
@@ -12,13 +17,16 @@ pub use self::rigid_body_components::*;
 pub(crate) use self::solver::IslandSolver;
 #[cfg(feature = "parallel")]
 pub(crate) use self::solver::ParallelIslandSolver;
+pub(crate) use self::solver::SubstepSolver;
 pub use parry::mass_properties::MassProperties;
 
 pub use self::rigid_body::{RigidBody, RigidBodyBuilder};
 pub use self::rigid_body_set::{BodyPair, RigidBodySet};
 
+mod aerodynamics;
 mod ccd;
 mod coefficient_combine_rule;
+mod force_generators;
 mod integration_parameters;
 mod island_manager;
 mod joint;
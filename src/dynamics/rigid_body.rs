@@ -1,7 +1,8 @@
 use crate::dynamics::{
-    LockedAxes, MassProperties, RigidBodyActivation, RigidBodyAdditionalMassProps, RigidBodyCcd,
-    RigidBodyChanges, RigidBodyColliders, RigidBodyDamping, RigidBodyDominance, RigidBodyForces,
-    RigidBodyIds, RigidBodyMassProps, RigidBodyPosition, RigidBodyType, RigidBodyVelocity,
+    LockedAxes, MassProperties, RigidBodyActivation, RigidBodyAdditionalMassProps,
+    RigidBodyAerodynamics, RigidBodyCcd, RigidBodyChanges, RigidBodyColliders, RigidBodyDamping,
+    RigidBodyDominance, RigidBodyForces, RigidBodyGravity, RigidBodyIds, RigidBodyMassProps,
+    RigidBodyPosition, RigidBodyType, RigidBodyVelocity, RigidBodyVelocityLimits,
 };
 use crate::geometry::{
     ColliderHandle, ColliderMassProps, ColliderParent, ColliderPosition, ColliderSet, ColliderShape,
@@ -24,6 +25,7 @@ pub struct RigidBody {
     pub(crate) integrated_vels: RigidBodyVelocity,
     pub(crate) vels: RigidBodyVelocity,
     pub(crate) damping: RigidBodyDamping,
+    pub(crate) vel_limits: RigidBodyVelocityLimits,
     pub(crate) forces: RigidBodyForces,
     pub(crate) ccd: RigidBodyCcd,
     pub(crate) ids: RigidBodyIds,
@@ -36,6 +38,7 @@ pub struct RigidBody {
     /// The dominance group this rigid-body is part of.
     pub(crate) dominance: RigidBodyDominance,
     pub(crate) enabled: bool,
+    pub(crate) aerodynamics: Option<Box<RigidBodyAerodynamics>>,
     /// User-defined data associated to this rigid-body.
     pub user_data: u128,
 }
@@ -54,6 +57,7 @@ impl RigidBody {
             integrated_vels: RigidBodyVelocity::default(),
             vels: RigidBodyVelocity::default(),
             damping: RigidBodyDamping::default(),
+            vel_limits: RigidBodyVelocityLimits::default(),
             forces: RigidBodyForces::default(),
             ccd: RigidBodyCcd::default(),
             ids: RigidBodyIds::default(),
@@ -63,6 +67,7 @@ impl RigidBody {
             body_type: RigidBodyType::Dynamic,
             dominance: RigidBodyDominance::default(),
             enabled: true,
+            aerodynamics: None,
             user_data: 0,
         }
     }
@@ -129,6 +134,38 @@ impl RigidBody {
         self.damping.angular_damping = damping
     }
 
+    /// The maximum magnitude of the linear velocity of this rigid-body.
+    #[inline]
+    pub fn max_linear_velocity(&self) -> Real {
+        self.vel_limits.max_linear_velocity
+    }
+
+    /// Sets the maximum magnitude of the linear velocity of this rigid-body.
+    ///
+    /// The linear velocity of this rigid-body is clamped to this value after each velocity solve,
+    /// unless it is a multibody link. Should be `>= 0`: negative or NaN values are replaced by
+    /// zero.
+    #[inline]
+    pub fn set_max_linear_velocity(&mut self, max_linvel: Real) {
+        self.vel_limits.max_linear_velocity = max_linvel.max(0.0);
+    }
+
+    /// The maximum magnitude of the angular velocity of this rigid-body.
+    #[inline]
+    pub fn max_angular_velocity(&self) -> Real {
+        self.vel_limits.max_angular_velocity
+    }
+
+    /// Sets the maximum magnitude of the angular velocity of this rigid-body.
+    ///
+    /// The angular velocity of this rigid-body is clamped to this value after each velocity solve,
+    /// unless it is a multibody link. Should be `>= 0`: negative or NaN values are replaced by
+    /// zero.
+    #[inline]
+    pub fn set_max_angular_velocity(&mut self, max_angvel: Real) {
+        self.vel_limits.max_angular_velocity = max_angvel.max(0.0);
+    }
+
     /// The type of this rigid-body.
     pub fn body_type(&self) -> RigidBodyType {
         self.body_type
@@ -370,6 +407,21 @@ impl RigidBody {
         self.ccd.ccd_enabled
     }
 
+    /// Enables or disables the gyroscopic forces applied to this rigid-body.
+    ///
+    /// Gyroscopic forces make the rigid-body conserve its angular momentum instead of its angular
+    /// velocity, resulting in the precession of spinning tops and the Dzhanibekov effect.
+    #[cfg(feature = "dim3")]
+    pub fn enable_gyroscopic_forces(&mut self, enabled: bool) {
+        self.forces.gyroscopic_forces_enabled = enabled;
+    }
+
+    /// Are gyroscopic forces applied to this rigid-body?
+    #[cfg(feature = "dim3")]
+    pub fn is_gyroscopic_forces_enabled(&self) -> bool {
+        self.forces.gyroscopic_forces_enabled
+    }
+
     // This is different from `is_ccd_enabled`. This checks that CCD
     // is active for this rigid-body, i.e., if it was seen to move fast
     // enough to justify a CCD run.
@@ -522,6 +574,36 @@ impl RigidBody {
         }
     }
 
+    /// The aerodynamic model of this rigid-body, if any.
+    pub fn aerodynamics(&self) -> Option<&RigidBodyAerodynamics> {
+        self.aerodynamics.as_deref()
+    }
+
+    /// Sets the aerodynamic model of this rigid-body.
+    ///
+    /// The aerodynamic drag and lift are applied in addition to the linear and angular damping.
+    pub fn set_aerodynamics(&mut self, aerodynamics: Option<RigidBodyAerodynamics>) {
+        self.aerodynamics = aerodynamics.map(Box::new);
+    }
+
+    /// The gravity affecting this rigid-body.
+    pub fn gravity(&self) -> &RigidBodyGravity {
+        &self.forces.gravity
+    }
+
+    /// Sets the gravity affecting this rigid-body, overriding the global gravity if it isn’t
+    /// [`RigidBodyGravity::Global`].
+    ///
+    /// The gravity scale of this rigid-body is still applied to the resulting gravity.
+    pub fn set_gravity(&mut self, gravity: RigidBodyGravity, wake_up: bool) {
+        if wake_up && self.activation.sleeping {
+            self.changes.insert(RigidBodyChanges::SLEEP);
+            self.activation.sleeping = false;
+        }
+
+        self.forces.gravity = gravity;
+    }
+
     /// The dominance group of this rigid-body.
     pub fn dominance_group(&self) -> i8 {
         self.dominance.0
@@ -789,6 +871,8 @@ impl RigidBody {
 
     /// Predicts the next position of this rigid-body, by integrating its velocity and forces
     /// by a time of `dt`.
+    ///
+    /// The forces include the gravity affecting this rigid-body, as computed by the last timestep.
     pub fn predict_position_using_velocity_and_forces(&self, dt: Real) -> Isometry<Real> {
         self.pos
             .integrate_forces_and_velocities(dt, &self.forces, &self.vels, &self.mprops)
@@ -981,6 +1065,9 @@ impl RigidBody {
     }
 
     /// The potential energy of this body in a gravity field.
+    ///
+    /// The global `gravity` is ignored if this rigid-body has its own gravity, see
+    /// [`RigidBody::set_gravity`].
     pub fn gravitational_potential_energy(&self, dt: Real, gravity: Vector<Real>) -> Real {
         let world_com = self
             .mprops
@@ -992,7 +1079,12 @@ impl RigidBody {
         // to sync up the potential energy with the kinetic energy:
         let world_com = world_com - self.vels.linvel * (dt / 2.0);
 
-        -self.mass() * self.forces.gravity_scale * gravity.dot(&world_com)
+        self.mass()
+            * self.forces.gravity_scale
+            * self
+                .forces
+                .gravity
+                .potential(&gravity, &Point::from(world_com))
     }
 }
 
@@ -1008,10 +1100,18 @@ pub struct RigidBodyBuilder {
     pub angvel: AngVector<Real>,
     /// The scale factor applied to the gravity affecting the rigid-body to be built, `1.0` by default.
     pub gravity_scale: Real,
+    /// The gravity affecting the rigid-body to be built, the global gravity by default.
+    pub gravity: RigidBodyGravity,
+    /// The aerodynamic model of the rigid-body to be built, none by default.
+    pub aerodynamics: Option<RigidBodyAerodynamics>,
     /// Damping factor for gradually slowing down the translational motion of the rigid-body, `0.0` by default.
     pub linear_damping: Real,
     /// Damping factor for gradually slowing down the angular motion of the rigid-body, `0.0` by default.
     pub angular_damping: Real,
+    /// The maximum linear speed of the rigid-body to be built, unbounded by default.
+    pub max_linear_velocity: Real,
+    /// The maximum angular speed of the rigid-body to be built, unbounded by default.
+    pub max_angular_velocity: Real,
     body_type: RigidBodyType,
     mprops_flags: LockedAxes,
     /// The additional mass-properties of the rigid-body being built. See [`RigidBodyBuilder::additional_mass_properties`] for more information.
@@ -1024,6 +1124,9 @@ pub struct RigidBodyBuilder {
     ///
     /// CCD prevents tunneling, but may still allow limited interpenetration of colliders.
     pub ccd_enabled: bool,
+    /// Whether gyroscopic forces are applied to the rigid-body to be built, `false` by default.
+    #[cfg(feature = "dim3")]
+    pub gyroscopic_forces_enabled: bool,
     /// The dominance group of the rigid-body to be built.
     pub dominance_group: i8,
     /// Will the rigid-body being built be enabled?
@@ -1040,14 +1143,20 @@ impl RigidBodyBuilder {
             linvel: Vector::zeros(),
             angvel: na::zero(),
             gravity_scale: 1.0,
+            gravity: RigidBodyGravity::Global,
+            aerodynamics: None,
             linear_damping: 0.0,
             angular_damping: 0.0,
+            max_linear_velocity: Real::MAX,
+            max_angular_velocity: Real::MAX,
             body_type,
             mprops_flags: LockedAxes::empty(),
             additional_mass_properties: RigidBodyAdditionalMassProps::default(),
             can_sleep: true,
             sleeping: false,
             ccd_enabled: false,
+            #[cfg(feature = "dim3")]
+            gyroscopic_forces_enabled: false,
             dominance_group: 0,
             enabled: true,
             user_data: 0,
@@ -1096,6 +1205,18 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets the aerodynamic model of the rigid-body to be created.
+    pub fn aerodynamics(mut self, aerodynamics: RigidBodyAerodynamics) -> Self {
+        self.aerodynamics = Some(aerodynamics);
+        self
+    }
+
+    /// Sets the gravity affecting the rigid-body to be created, instead of the global gravity.
+    pub fn gravity(mut self, gravity: RigidBodyGravity) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
     /// Sets the dominance group of this rigid-body.
     pub fn dominance_group(mut self, group: i8) -> Self {
         self.dominance_group = group;
@@ -1264,6 +1385,26 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets the maximum magnitude of the linear velocity of the rigid-body to be created.
+    ///
+    /// The linear velocity is clamped to this value after each velocity solve, unless the
+    /// rigid-body is a multibody link. Should be `>= 0`: negative or NaN values are replaced by
+    /// zero.
+    pub fn max_linear_velocity(mut self, max_linvel: Real) -> Self {
+        self.max_linear_velocity = max_linvel.max(0.0);
+        self
+    }
+
+    /// Sets the maximum magnitude of the angular velocity of the rigid-body to be created.
+    ///
+    /// The angular velocity is clamped to this value after each velocity solve, unless the
+    /// rigid-body is a multibody link. Should be `>= 0`: negative or NaN values are replaced by
+    /// zero.
+    pub fn max_angular_velocity(mut self, max_angvel: Real) -> Self {
+        self.max_angular_velocity = max_angvel.max(0.0);
+        self
+    }
+
     /// Sets the initial linear velocity of the rigid-body to be created.
     pub fn linvel(mut self, linvel: Vector<Real>) -> Self {
         self.linvel = linvel;
@@ -1290,6 +1431,16 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets whether or not gyroscopic forces are applied to the rigid-body to be created.
+    ///
+    /// Gyroscopic forces make the rigid-body conserve its angular momentum instead of its angular
+    /// velocity.
+    #[cfg(feature = "dim3")]
+    pub fn gyroscopic_forces_enabled(mut self, enabled: bool) -> Self {
+        self.gyroscopic_forces_enabled = enabled;
+        self
+    }
+
     /// Sets whether or not the rigid-body is to be created asleep.
     pub fn sleeping(mut self, sleeping: bool) -> Self {
         self.sleeping = sleeping;
@@ -1322,7 +1473,15 @@ impl RigidBodyBuilder {
         rb.mprops.flags = self.mprops_flags;
         rb.damping.linear_damping = self.linear_damping;
         rb.damping.angular_damping = self.angular_damping;
+        rb.vel_limits.max_linear_velocity = self.max_linear_velocity;
+        rb.vel_limits.max_angular_velocity = self.max_angular_velocity;
         rb.forces.gravity_scale = self.gravity_scale;
+        rb.forces.gravity = self.gravity;
+        #[cfg(feature = "dim3")]
+        {
+            rb.forces.gyroscopic_forces_enabled = self.gyroscopic_forces_enabled;
+        }
+        rb.aerodynamics = self.aerodynamics.clone().map(Box::new);
         rb.dominance = RigidBodyDominance(self.dominance_group);
         rb.enabled = self.enabled;
         rb.enable_ccd(self.ccd_enabled);
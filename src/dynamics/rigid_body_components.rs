@@ -1,4 +1,4 @@
-use crate::dynamics::MassProperties;
+use crate::dynamics::{IslandHandle, MassProperties};
 use crate::geometry::{
     ColliderChanges, ColliderHandle, ColliderMassProps, ColliderParent, ColliderPosition,
     ColliderSet, ColliderShape,
@@ -173,9 +173,23 @@ impl RigidBodyPosition {
         RigidBodyVelocity { linvel, angvel }
     }
 
+    /// Interpolates between `self.position` and `self.next_position`.
+    ///
+    /// The translation is interpolated linearly, and the rotation is interpolated spherically.
+    /// The returned position is equal to `self.position` if `t == 0.0`, and to
+    /// `self.next_position` if `t == 1.0`.
+    #[must_use]
+    pub fn interpolate(&self, t: Real) -> Isometry<Real> {
+        self.position.lerp_slerp(&self.next_position, t)
+    }
+
     /// Compute new positions after integrating the given forces and velocities.
     ///
-    /// This uses a symplectic Euler integration scheme.
+    /// This uses a symplectic Euler integration scheme. The gravity isn’t applied separately:
+    /// at the beginning of each timestep, the pipeline folds the gravity of each rigid-body,
+    /// i.e., its [`RigidBodyGravity`] evaluated at its center-of-mass and multiplied by its
+    /// gravity scale, into `forces.force`. Since the solver integrates the same force, the
+    /// predicted positions match the simulated ones, even for a position-dependent gravity.
     #[must_use]
     pub fn integrate_forces_and_velocities(
         &self,
@@ -184,7 +198,15 @@ impl RigidBodyPosition {
         vels: &RigidBodyVelocity,
         mprops: &RigidBodyMassProps,
     ) -> Isometry<Real> {
-        let new_vels = forces.integrate(dt, vels, mprops);
+        #[allow(unused_mut)] // mut is needed for 3D but not for 2D.
+        let mut new_vels = forces.integrate(dt, vels, mprops);
+
+        #[cfg(feature = "dim3")]
+        if forces.gyroscopic_forces_enabled {
+            let impulse = vels.gyroscopic_impulse(dt, &self.position.rotation, mprops);
+            new_vels.apply_torque_impulse(mprops, impulse);
+        }
+
         new_vels.integrate(dt, &self.position, &mprops.local_mprops.local_com)
     }
 }
@@ -545,6 +567,70 @@ impl RigidBodyVelocity {
         }
     }
 
+    /// Returns the updated velocities after clamping their magnitude to the given limits.
+    #[must_use]
+    pub fn apply_limits(&self, limits: &RigidBodyVelocityLimits) -> Self {
+        let mut result = *self;
+        // NOTE: the limits are public fields, so guard against negative or NaN values.
+        let max_linvel = limits.max_linear_velocity.max(0.0);
+        let max_angvel = limits.max_angular_velocity.max(0.0);
+
+        let linvel_norm = self.linvel.norm();
+        if linvel_norm > max_linvel {
+            result.linvel *= max_linvel / linvel_norm;
+        }
+
+        #[cfg(feature = "dim2")]
+        {
+            result.angvel = self.angvel.max(-max_angvel).min(max_angvel);
+        }
+        #[cfg(feature = "dim3")]
+        {
+            let angvel_norm = self.angvel.norm();
+            if angvel_norm > max_angvel {
+                result.angvel *= max_angvel / angvel_norm;
+            }
+        }
+
+        result
+    }
+
+    /// The angular impulse applied by the gyroscopic torque `-ω × Iω` during `dt`, on a
+    /// rigid-body with the given orientation rotating with these velocities.
+    ///
+    /// This performs one step of an implicit Euler integration, which remains stable even for
+    /// fast rotations. Applying this impulse makes the rigid-body conserve its angular momentum
+    /// instead of its angular velocity, which results in precession and the Dzhanibekov effect.
+    #[cfg(feature = "dim3")]
+    #[must_use]
+    pub fn gyroscopic_impulse(
+        &self,
+        dt: Real,
+        rotation: &Rotation<Real>,
+        rb_mprops: &RigidBodyMassProps,
+    ) -> AngVector<Real> {
+        let inertia_frame = rotation * rb_mprops.local_mprops.principal_inertia_local_frame;
+        let inertia = rb_mprops.local_mprops.principal_inertia();
+        let inertia_matrix = na::Matrix3::from_diagonal(&inertia);
+        // The angular velocity and momentum, in the principal inertia frame.
+        let angvel = inertia_frame.inverse_transform_vector(&self.angvel);
+        let momentum = inertia.component_mul(&angvel);
+
+        // One Newton iteration for solving `I (ω' - ω) + dt ω' × Iω' = 0`, starting from `ω`.
+        let residual = angvel.cross(&momentum) * dt;
+        let jacobian = inertia_matrix
+            + (angvel.cross_matrix() * inertia_matrix - momentum.cross_matrix()) * dt;
+
+        match jacobian.try_inverse() {
+            Some(inv_jacobian) => {
+                let delta_angvel = -(inv_jacobian * residual);
+                inertia_frame * inertia.component_mul(&delta_angvel)
+            }
+            // This happens if the rigid-body doesn’t have any angular inertia.
+            None => AngVector::zeros(),
+        }
+    }
+
     /// The velocity of the given world-space point on this rigid-body.
     #[must_use]
     pub fn velocity_at_point(&self, point: &Point<Real>, world_com: &Point<Real>) -> Vector<Real> {
@@ -692,21 +778,172 @@ impl Default for RigidBodyDamping {
     }
 }
 
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, PartialEq)]
+/// The maximum speeds of a rigid-body.
+///
+/// The velocities of a dynamic rigid-body are clamped to these limits after the velocity solve.
+/// The multibody links aren’t clamped: their velocities are derived from the velocities of
+/// their multibody joints, which would no longer match the clamped velocities.
+pub struct RigidBodyVelocityLimits {
+    /// The maximum magnitude of the linear velocity of the rigid-body. Should be `>= 0`.
+    pub max_linear_velocity: Real,
+    /// The maximum magnitude of the angular velocity of the rigid-body. Should be `>= 0`.
+    pub max_angular_velocity: Real,
+}
+
+impl Default for RigidBodyVelocityLimits {
+    fn default() -> Self {
+        Self {
+            max_linear_velocity: Real::MAX,
+            max_angular_velocity: Real::MAX,
+        }
+    }
+}
+
+impl RigidBodyVelocityLimits {
+    /// Are the velocities of the rigid-body unbounded?
+    pub fn is_unbounded(&self) -> bool {
+        self.max_linear_velocity == Real::MAX && self.max_angular_velocity == Real::MAX
+    }
+}
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, Default)]
+/// The gravity affecting a rigid-body.
+pub enum RigidBodyGravity {
+    /// The rigid-body is affected by the global gravity passed to `PhysicsPipeline::step`.
+    #[default]
+    Global,
+    /// The rigid-body is affected by this gravitational acceleration instead of the global gravity.
+    ///
+    /// Set it to zero to disable gravity for this rigid-body only.
+    Constant(Vector<Real>),
+    /// The rigid-body is attracted by a planet, irrespective of the global gravity.
+    ///
+    /// The gravitational acceleration is directed toward `center` and its magnitude is
+    /// `surface_gravity * (radius / distance)²`. Within `radius` from the center, its
+    /// magnitude remains equal to `surface_gravity`.
+    Planet {
+        /// The center of the planet.
+        center: Point<Real>,
+        /// The radius of the planet.
+        radius: Real,
+        /// The magnitude of the gravitational acceleration at the planet’s surface.
+        surface_gravity: Real,
+    },
+    /// The gravitational acceleration is given by this function of the world-space center-of-mass
+    /// of the rigid-body, instead of the global gravity.
+    ///
+    /// This variant can’t be serialized.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    Function(fn(&Point<Real>) -> Vector<Real>),
+}
+
+impl PartialEq for RigidBodyGravity {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (RigidBodyGravity::Global, RigidBodyGravity::Global) => true,
+            (RigidBodyGravity::Constant(g1), RigidBodyGravity::Constant(g2)) => g1 == g2,
+            (
+                RigidBodyGravity::Planet {
+                    center: c1,
+                    radius: r1,
+                    surface_gravity: g1,
+                },
+                RigidBodyGravity::Planet {
+                    center: c2,
+                    radius: r2,
+                    surface_gravity: g2,
+                },
+            ) => c1 == c2 && r1 == r2 && g1 == g2,
+            // Function pointers are compared by address.
+            (RigidBodyGravity::Function(f1), RigidBodyGravity::Function(f2)) => {
+                *f1 as usize == *f2 as usize
+            }
+            _ => false,
+        }
+    }
+}
+
+impl RigidBodyGravity {
+    /// The gravitational acceleration at the center-of-mass `world_com`, before applying the gravity
+    /// scale of the rigid-body.
+    pub fn acceleration(
+        &self,
+        global_gravity: &Vector<Real>,
+        world_com: &Point<Real>,
+    ) -> Vector<Real> {
+        match self {
+            RigidBodyGravity::Global => *global_gravity,
+            RigidBodyGravity::Constant(gravity) => *gravity,
+            RigidBodyGravity::Planet {
+                center,
+                radius,
+                surface_gravity,
+            } => {
+                let dir = center - world_com;
+                let dist = dir.norm();
+
+                if dist == 0.0 {
+                    return Vector::zeros();
+                }
+
+                let ratio = radius / dist.max(*radius);
+                dir * (surface_gravity * ratio * ratio / dist)
+            }
+            RigidBodyGravity::Function(f) => f(world_com),
+        }
+    }
+
+    /// The gravitational potential, per unit of mass, at the center-of-mass `world_com`, before
+    /// applying the gravity scale of the rigid-body.
+    ///
+    /// The potential of a gravity function is approximated by the potential of a uniform field
+    /// equal to the gravity at `world_com`.
+    pub fn potential(&self, global_gravity: &Vector<Real>, world_com: &Point<Real>) -> Real {
+        match self {
+            RigidBodyGravity::Planet {
+                center,
+                radius,
+                surface_gravity,
+            } => {
+                let dist = na::distance(center, world_com);
+
+                if dist >= *radius {
+                    -surface_gravity * radius * radius / dist
+                } else {
+                    surface_gravity * (dist - 2.0 * radius)
+                }
+            }
+            _ => -self
+                .acceleration(global_gravity, world_com)
+                .dot(&world_com.coords),
+        }
+    }
+}
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Copy, PartialEq)]
 /// The user-defined external forces applied to this rigid-body.
 pub struct RigidBodyForces {
-    /// Accumulation of external forces (only for dynamic bodies).
+    /// Accumulation of external forces (only for dynamic bodies), including the gravity
+    /// computed at the beginning of the last timestep.
     pub force: Vector<Real>,
     /// Accumulation of external torques (only for dynamic bodies).
     pub torque: AngVector<Real>,
     /// Gravity is multiplied by this scaling factor before it's
     /// applied to this rigid-body.
     pub gravity_scale: Real,
+    /// The gravity affecting this rigid-body.
+    pub gravity: RigidBodyGravity,
     /// Forces applied by the user.
     pub user_force: Vector<Real>,
     /// Torque applied by the user.
     pub user_torque: AngVector<Real>,
+    /// Are gyroscopic forces applied to this rigid-body?
+    #[cfg(feature = "dim3")]
+    pub gyroscopic_forces_enabled: bool,
 }
 
 impl Default for RigidBodyForces {
@@ -715,8 +952,11 @@ impl Default for RigidBodyForces {
             force: na::zero(),
             torque: na::zero(),
             gravity_scale: 1.0,
+            gravity: RigidBodyGravity::Global,
             user_force: na::zero(),
             user_torque: na::zero(),
+            #[cfg(feature = "dim3")]
+            gyroscopic_forces_enabled: false,
         }
     }
 }
@@ -804,6 +1044,20 @@ impl RigidBodyCcd {
         return vels.linvel.norm() + vels.angvel.norm() * self.ccd_max_dist;
     }
 
+    /// Can a rigid-body with the given velocity limits move fast enough to cause a tunneling
+    /// problem?
+    pub fn can_move_fast(&self, dt: Real, limits: &RigidBodyVelocityLimits) -> bool {
+        if limits.is_unbounded() {
+            return true;
+        }
+
+        // NOTE: this uses the same threshold as `Self::is_moving_fast`.
+        let threshold = self.ccd_thickness / 10.0;
+        let max_point_velocity =
+            limits.max_linear_velocity + limits.max_angular_velocity * self.ccd_max_dist;
+        max_point_velocity * dt > threshold
+    }
+
     /// Is this rigid-body moving fast enough so that it may cause a tunneling problem?
     pub fn is_moving_fast(
         &self,
@@ -843,6 +1097,7 @@ pub struct RigidBodyIds {
     pub(crate) active_set_id: usize,
     pub(crate) active_set_offset: usize,
     pub(crate) active_set_timestamp: u32,
+    pub(crate) island: Option<IslandHandle>,
 }
 
 impl Default for RigidBodyIds {
@@ -852,6 +1107,7 @@ impl Default for RigidBodyIds {
             active_set_id: 0,
             active_set_offset: 0,
             active_set_timestamp: 0,
+            island: None,
         }
     }
 }
@@ -3,6 +3,7 @@ use crate::dynamics::{
     ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBody, RigidBodyChanges, RigidBodyHandle,
 };
 use crate::geometry::ColliderSet;
+use crate::math::{Real, Vector};
 use std::ops::{Index, IndexMut};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -206,6 +207,19 @@ impl RigidBodySet {
         })
     }
 
+    /// Translates every rigid-body of this set by `translation`.
+    ///
+    /// The rigid-bodies are moved without being woken up nor flagged as modified. This is
+    /// meant to be used for shifting the origin of the whole simulation, see
+    /// `PhysicsWorld::shift_origin`.
+    pub fn shift_origin(&mut self, translation: &Vector<Real>) {
+        for (_, rb) in self.bodies.iter_mut() {
+            rb.pos.position.translation.vector += translation;
+            rb.pos.next_position.translation.vector += translation;
+            rb.mprops.update_world_mass_properties(&rb.pos.position);
+        }
+    }
+
     /// Update colliders positions after rigid-bodies moved.
     ///
     /// When a rigid-body moves, the positions of the colliders attached to it need to be updated.
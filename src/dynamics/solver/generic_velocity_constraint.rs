@@ -5,7 +5,8 @@ use crate::math::{Real, DIM, MAX_MANIFOLD_POINTS};
 use crate::utils::{WAngularInertia, WCross, WDot};
 
 use super::{
-    AnyVelocityConstraint, DeltaVel, VelocityConstraintElement, VelocityConstraintNormalPart,
+    AnyVelocityConstraint, DeltaVel, VelocityConstraintAngularFrictionPart,
+    VelocityConstraintElement, VelocityConstraintNormalPart,
 };
 #[cfg(feature = "dim2")]
 use crate::utils::WBasis;
@@ -34,9 +35,9 @@ impl GenericVelocityConstraint {
         jacobian_id: &mut usize,
         insert_at: Option<usize>,
     ) {
-        let cfm_factor = params.cfm_factor();
         let inv_dt = params.inv_dt();
-        let erp_inv_dt = params.erp_inv_dt();
+        let (erp_inv_dt, cfm_factor) =
+            params.contact_erp_inv_dt_and_cfm_factor(manifold.data.softness.as_ref());
 
         let handle1 = manifold.data.rigid_body1.unwrap();
         let handle2 = manifold.data.rigid_body2.unwrap();
@@ -73,15 +74,20 @@ impl GenericVelocityConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let tangents1 = super::compute_tangent_contact_directions(
+            &force_dir1,
+            &manifold.data.friction_direction.unwrap_or_else(na::zero),
+            &vels1.linvel,
+            &vels2.linvel,
+        );
 
         let multibodies_ndof = multibody1.map(|m| m.0.ndofs()).unwrap_or(0)
             + multibody2.map(|m| m.0.ndofs()).unwrap_or(0);
-        // For each solver contact we generate DIM constraints, and each constraints appends
-        // the multibodies jacobian and weighted jacobians
-        let required_jacobian_len =
-            *jacobian_id + manifold.data.solver_contacts.len() * multibodies_ndof * 2 * DIM;
+        // For each solver contact we generate DIM constraints (plus the angular friction
+        // constraints), and each constraints appends the multibodies jacobian and weighted jacobians
+        let num_jacobian_lines =
+            VelocityConstraint::num_active_constraints_and_jacobian_lines(manifold).1;
+        let required_jacobian_len = *jacobian_id + num_jacobian_lines * multibodies_ndof * 2;
 
         if jacobians.nrows() < required_jacobian_len && !cfg!(feature = "parallel") {
             jacobians.resize_vertically_mut(required_jacobian_len, 0.0);
@@ -111,7 +117,8 @@ impl GenericVelocityConstraint {
                     na::zero()
                 },
                 cfm_factor,
-                limit: 0.0,
+                limits: [0.0; DIM - 1],
+                angular_friction: None,
                 mj_lambda1,
                 mj_lambda2,
                 manifold_id,
@@ -127,7 +134,7 @@ impl GenericVelocityConstraint {
                 let vel1 = vels1.linvel + vels1.angvel.gcross(dp1);
                 let vel2 = vels2.linvel + vels2.angvel.gcross(dp2);
 
-                constraint.limit = manifold_point.friction;
+                constraint.limits = manifold.data.friction_limits(manifold_point);
                 constraint.manifold_contact_id[k] = manifold_point.contact_id;
 
                 // Normal part.
@@ -290,6 +297,64 @@ impl GenericVelocityConstraint {
                 }
             }
 
+            // Angular friction part, with its jacobians after the ones of all the contacts.
+            if manifold.data.has_angular_friction() {
+                let axes = super::angular_friction_axes(&force_dir1, &tangents1);
+                let mut part = VelocityConstraintAngularFrictionPart::new(
+                    axes,
+                    &mprops1.effective_world_inv_inertia_sqrt,
+                    &mprops2.effective_world_inv_inertia_sqrt,
+                    vels1.angvel,
+                    vels2.angvel,
+                    manifold.data.rolling_friction,
+                    manifold.data.spinning_friction,
+                );
+
+                for (i, axis) in axes.iter().enumerate() {
+                    let inv_r1 = if let Some((mb1, link_id1)) = multibody1.as_ref() {
+                        mb1.fill_jacobians(
+                            *link_id1,
+                            na::zero(),
+                            #[cfg(feature = "dim2")]
+                            na::vector![*axis],
+                            #[cfg(feature = "dim3")]
+                            *axis,
+                            jacobian_id,
+                            jacobians,
+                        )
+                        .0
+                    } else if type1.is_dynamic() {
+                        part.gcross1[i].gdot(part.gcross1[i])
+                    } else {
+                        part.gcross1[i] = na::zero();
+                        0.0
+                    };
+
+                    let inv_r2 = if let Some((mb2, link_id2)) = multibody2.as_ref() {
+                        mb2.fill_jacobians(
+                            *link_id2,
+                            na::zero(),
+                            #[cfg(feature = "dim2")]
+                            na::vector![-*axis],
+                            #[cfg(feature = "dim3")]
+                            -*axis,
+                            jacobian_id,
+                            jacobians,
+                        )
+                        .0
+                    } else if type2.is_dynamic() {
+                        part.gcross2[i].gdot(part.gcross2[i])
+                    } else {
+                        part.gcross2[i] = na::zero();
+                        0.0
+                    };
+
+                    part.r[i] = crate::utils::inv(inv_r1 + inv_r2);
+                }
+
+                constraint.angular_friction = Some(part);
+            }
+
             constraint.cfm_factor = if is_fast_contact { 1.0 } else { cfm_factor };
 
             let ndofs1 = multibody1.map(|mb| mb.0.ndofs()).unwrap_or(0);
@@ -348,7 +413,8 @@ impl GenericVelocityConstraint {
             &self.velocity_constraint.tangent1,
             &self.velocity_constraint.im1,
             &self.velocity_constraint.im2,
-            self.velocity_constraint.limit,
+            self.velocity_constraint.limits,
+            self.velocity_constraint.angular_friction.as_mut(),
             self.ndofs1,
             self.ndofs2,
             self.j_id,
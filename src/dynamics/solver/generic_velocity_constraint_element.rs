@@ -1,6 +1,7 @@
 use super::DeltaVel;
 use crate::dynamics::solver::{
-    VelocityConstraintElement, VelocityConstraintNormalPart, VelocityConstraintTangentPart,
+    VelocityConstraintAngularFrictionPart, VelocityConstraintElement, VelocityConstraintNormalPart,
+    VelocityConstraintTangentPart,
 };
 use crate::math::{AngVector, Real, Vector, DIM};
 use crate::utils::WDot;
@@ -98,7 +99,7 @@ impl VelocityConstraintTangentPart<Real> {
         im2: &Vector<Real>,
         ndofs1: usize,
         ndofs2: usize,
-        limit: Real,
+        limits: [Real; DIM - 1],
         mj_lambda1: &mut GenericRhs,
         mj_lambda2: &mut GenericRhs,
         mj_lambdas: &mut DVector<Real>,
@@ -126,7 +127,8 @@ impl VelocityConstraintTangentPart<Real> {
                 mj_lambdas,
             ) + self.rhs[0];
 
-            let new_impulse = (self.impulse[0] - self.r[0] * dvel_0).simd_clamp(-limit, limit);
+            let new_impulse =
+                (self.impulse[0] - self.r[0] * dvel_0).simd_clamp(-limits[0], limits[0]);
             let dlambda = new_impulse - self.impulse[0];
             self.impulse[0] = new_impulse;
 
@@ -189,7 +191,7 @@ impl VelocityConstraintTangentPart<Real> {
                 self.impulse[0] - self.r[0] * dvel_0,
                 self.impulse[1] - self.r[1] * dvel_1,
             );
-            let new_impulse = new_impulse.cap_magnitude(limit);
+            let new_impulse = super::cap_friction_impulse(new_impulse, limits);
 
             let dlambda = new_impulse - self.impulse;
             self.impulse = new_impulse;
@@ -289,6 +291,85 @@ impl VelocityConstraintNormalPart<Real> {
     }
 }
 
+impl VelocityConstraintAngularFrictionPart<Real> {
+    #[inline]
+    pub fn generic_solve(
+        &mut self,
+        j_id: usize,
+        jacobians: &DVector<Real>,
+        im1: &Vector<Real>,
+        im2: &Vector<Real>,
+        ndofs1: usize,
+        ndofs2: usize,
+        normal_impulse: Real,
+        mj_lambda1: &mut GenericRhs,
+        mj_lambda2: &mut GenericRhs,
+        mj_lambdas: &mut DVector<Real>,
+    ) {
+        let j_id1 = j_id1(j_id, ndofs1, ndofs2);
+        let j_id2 = j_id2(j_id, ndofs1, ndofs2);
+        let j_step = j_step(ndofs1, ndofs2);
+        // The angular friction doesn’t have any linear part.
+        let no_dir = Vector::zeros();
+        let mut new_impulse = self.impulse;
+
+        for (i, new_impulse) in new_impulse.iter_mut().enumerate() {
+            let dvel = mj_lambda1.dvel(
+                j_id1 + i * j_step,
+                ndofs1,
+                jacobians,
+                &no_dir,
+                &self.gcross1[i],
+                mj_lambdas,
+            ) + mj_lambda2.dvel(
+                j_id2 + i * j_step,
+                ndofs2,
+                jacobians,
+                &no_dir,
+                &self.gcross2[i],
+                mj_lambdas,
+            ) + self.rhs[i];
+            *new_impulse = self.impulse[i] - self.r[i] * dvel;
+        }
+
+        let rolling_limit = self.rolling_friction * normal_impulse;
+        #[cfg(feature = "dim3")]
+        let spinning_limit = self.spinning_friction * normal_impulse;
+        let new_impulse = super::cap_angular_friction_impulse(
+            new_impulse,
+            rolling_limit,
+            #[cfg(feature = "dim3")]
+            spinning_limit,
+        );
+
+        for (i, new_impulse) in new_impulse.iter().enumerate() {
+            let dlambda = *new_impulse - self.impulse[i];
+            mj_lambda1.apply_impulse(
+                j_id1 + i * j_step,
+                ndofs1,
+                dlambda,
+                jacobians,
+                &no_dir,
+                &self.gcross1[i],
+                mj_lambdas,
+                im1,
+            );
+            mj_lambda2.apply_impulse(
+                j_id2 + i * j_step,
+                ndofs2,
+                dlambda,
+                jacobians,
+                &no_dir,
+                &self.gcross2[i],
+                mj_lambdas,
+                im2,
+            );
+        }
+
+        self.impulse = new_impulse;
+    }
+}
+
 impl VelocityConstraintElement<Real> {
     #[inline]
     pub fn generic_solve_group(
@@ -299,7 +380,8 @@ impl VelocityConstraintElement<Real> {
         #[cfg(feature = "dim3")] tangent1: &Vector<Real>,
         im1: &Vector<Real>,
         im2: &Vector<Real>,
-        limit: Real,
+        limits: [Real; DIM - 1],
+        angular_friction: Option<&mut VelocityConstraintAngularFrictionPart<Real>>,
         // ndofs is 0 for a non-multibody body, or a multibody with zero
         // degrees of freedom.
         ndofs1: usize,
@@ -336,14 +418,36 @@ impl VelocityConstraintElement<Real> {
             let mut tng_j_id = tangent_j_id(j_id, ndofs1, ndofs2);
 
             for element in elements.iter_mut() {
-                let limit = limit * element.normal_part.impulse;
+                let normal_impulse = element.normal_part.impulse;
+                let limits = limits.map(|limit| limit * normal_impulse);
                 let part = &mut element.tangent_part;
                 part.generic_solve(
-                    tng_j_id, jacobians, tangents1, im1, im2, ndofs1, ndofs2, limit, mj_lambda1,
+                    tng_j_id, jacobians, tangents1, im1, im2, ndofs1, ndofs2, limits, mj_lambda1,
                     mj_lambda2, mj_lambdas,
                 );
                 tng_j_id += j_step;
             }
+
+            if let Some(part) = angular_friction {
+                // The angular friction jacobians follow the ones of all the contacts.
+                let ang_j_id = j_id + elements.len() * j_step;
+                let mut normal_impulse = 0.0;
+                for element in elements.iter() {
+                    normal_impulse += element.normal_part.impulse;
+                }
+                part.generic_solve(
+                    ang_j_id,
+                    jacobians,
+                    im1,
+                    im2,
+                    ndofs1,
+                    ndofs2,
+                    normal_impulse,
+                    mj_lambda1,
+                    mj_lambda2,
+                    mj_lambdas,
+                );
+            }
         }
     }
 }
@@ -5,7 +5,8 @@ use crate::math::{Point, Real, DIM, MAX_MANIFOLD_POINTS};
 use crate::utils::WCross;
 
 use super::{
-    AnyVelocityConstraint, VelocityGroundConstraintElement, VelocityGroundConstraintNormalPart,
+    AnyVelocityConstraint, VelocityConstraint, VelocityGroundConstraintAngularFrictionPart,
+    VelocityGroundConstraintElement, VelocityGroundConstraintNormalPart,
 };
 #[cfg(feature = "dim2")]
 use crate::utils::WBasis;
@@ -32,9 +33,9 @@ impl GenericVelocityGroundConstraint {
         jacobian_id: &mut usize,
         insert_at: Option<usize>,
     ) {
-        let cfm_factor = params.cfm_factor();
         let inv_dt = params.inv_dt();
-        let erp_inv_dt = params.erp_inv_dt();
+        let (erp_inv_dt, cfm_factor) =
+            params.contact_erp_inv_dt_and_cfm_factor(manifold.data.softness.as_ref());
 
         let mut handle1 = manifold.data.rigid_body1;
         let mut handle2 = manifold.data.rigid_body2;
@@ -66,14 +67,19 @@ impl GenericVelocityGroundConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let tangents1 = super::compute_tangent_contact_directions(
+            &force_dir1,
+            &manifold.data.friction_direction.unwrap_or_else(na::zero),
+            &vels1.linvel,
+            &vels2.linvel,
+        );
 
         let multibodies_ndof = mb2.ndofs();
-        // For each solver contact we generate DIM constraints, and each constraints appends
-        // the multibodies jacobian and weighted jacobians
-        let required_jacobian_len =
-            *jacobian_id + manifold.data.solver_contacts.len() * multibodies_ndof * 2 * DIM;
+        // For each solver contact we generate DIM constraints (plus the angular friction
+        // constraints), and each constraints appends the multibodies jacobian and weighted jacobians
+        let num_jacobian_lines =
+            VelocityConstraint::num_active_constraints_and_jacobian_lines(manifold).1;
+        let required_jacobian_len = *jacobian_id + num_jacobian_lines * multibodies_ndof * 2;
 
         if jacobians.nrows() < required_jacobian_len && !cfg!(feature = "parallel") {
             jacobians.resize_vertically_mut(required_jacobian_len, 0.0);
@@ -94,7 +100,8 @@ impl GenericVelocityGroundConstraint {
                 elements: [VelocityGroundConstraintElement::zero(); MAX_MANIFOLD_POINTS],
                 im2: mprops2.effective_inv_mass,
                 cfm_factor,
-                limit: 0.0,
+                limits: [0.0; DIM - 1],
+                angular_friction: None,
                 mj_lambda2,
                 manifold_id,
                 manifold_contact_id: [0; MAX_MANIFOLD_POINTS],
@@ -109,7 +116,7 @@ impl GenericVelocityGroundConstraint {
                 let vel1 = vels1.linvel + vels1.angvel.gcross(dp1);
                 let vel2 = vels2.linvel + vels2.angvel.gcross(dp2);
 
-                constraint.limit = manifold_point.friction;
+                constraint.limits = manifold.data.friction_limits(manifold_point);
                 constraint.manifold_contact_id[k] = manifold_point.contact_id;
 
                 // Normal part.
@@ -188,6 +195,38 @@ impl GenericVelocityGroundConstraint {
                 }
             }
 
+            // Angular friction part, with its jacobians after the ones of all the contacts.
+            if manifold.data.has_angular_friction() {
+                let axes = super::angular_friction_axes(&force_dir1, &tangents1);
+                let mut part = VelocityGroundConstraintAngularFrictionPart::new(
+                    axes,
+                    &mprops2.effective_world_inv_inertia_sqrt,
+                    vels1.angvel,
+                    vels2.angvel,
+                    manifold.data.rolling_friction,
+                    manifold.data.spinning_friction,
+                );
+
+                for (i, axis) in axes.iter().enumerate() {
+                    let inv_r2 = mb2
+                        .fill_jacobians(
+                            link_id2,
+                            na::zero(),
+                            #[cfg(feature = "dim2")]
+                            na::vector![-*axis],
+                            #[cfg(feature = "dim3")]
+                            -*axis,
+                            jacobian_id,
+                            jacobians,
+                        )
+                        .0;
+                    part.gcross2[i] = na::zero(); // Unused for generic constraints.
+                    part.r[i] = crate::utils::inv(inv_r2);
+                }
+
+                constraint.angular_friction = Some(part);
+            }
+
             constraint.cfm_factor = if is_fast_contact { 1.0 } else { cfm_factor };
 
             let constraint = GenericVelocityGroundConstraint {
@@ -220,7 +259,8 @@ impl GenericVelocityGroundConstraint {
             self.velocity_constraint.cfm_factor,
             elements,
             jacobians,
-            self.velocity_constraint.limit,
+            self.velocity_constraint.limits,
+            self.velocity_constraint.angular_friction.as_mut(),
             self.ndofs2,
             self.j_id,
             mj_lambda2,
@@ -1,6 +1,6 @@
 use crate::dynamics::solver::{
-    VelocityGroundConstraintElement, VelocityGroundConstraintNormalPart,
-    VelocityGroundConstraintTangentPart,
+    VelocityGroundConstraintAngularFrictionPart, VelocityGroundConstraintElement,
+    VelocityGroundConstraintNormalPart, VelocityGroundConstraintTangentPart,
 };
 use crate::math::{Real, DIM};
 use na::DVector;
@@ -14,7 +14,7 @@ impl VelocityGroundConstraintTangentPart<Real> {
         j_id2: usize,
         jacobians: &DVector<Real>,
         ndofs2: usize,
-        limit: Real,
+        limits: [Real; DIM - 1],
         mj_lambda2: usize,
         mj_lambdas: &mut DVector<Real>,
     ) {
@@ -25,7 +25,8 @@ impl VelocityGroundConstraintTangentPart<Real> {
                 .dot(&mj_lambdas.rows(mj_lambda2, ndofs2))
                 + self.rhs[0];
 
-            let new_impulse = (self.impulse[0] - self.r[0] * dvel_0).simd_clamp(-limit, limit);
+            let new_impulse =
+                (self.impulse[0] - self.r[0] * dvel_0).simd_clamp(-limits[0], limits[0]);
             let dlambda = new_impulse - self.impulse[0];
             self.impulse[0] = new_impulse;
 
@@ -52,7 +53,7 @@ impl VelocityGroundConstraintTangentPart<Real> {
                 self.impulse[0] - self.r[0] * dvel_0,
                 self.impulse[1] - self.r[1] * dvel_1,
             );
-            let new_impulse = new_impulse.cap_magnitude(limit);
+            let new_impulse = super::cap_friction_impulse(new_impulse, limits);
 
             let dlambda = new_impulse - self.impulse;
             self.impulse = new_impulse;
@@ -99,13 +100,58 @@ impl VelocityGroundConstraintNormalPart<Real> {
     }
 }
 
+impl VelocityGroundConstraintAngularFrictionPart<Real> {
+    #[inline]
+    pub fn generic_solve(
+        &mut self,
+        j_id2: usize,
+        jacobians: &DVector<Real>,
+        ndofs2: usize,
+        normal_impulse: Real,
+        mj_lambda2: usize,
+        mj_lambdas: &mut DVector<Real>,
+    ) {
+        let j_step = ndofs2 * 2;
+        let mut new_impulse = self.impulse;
+
+        for (i, new_impulse) in new_impulse.iter_mut().enumerate() {
+            let dvel = jacobians
+                .rows(j_id2 + i * j_step, ndofs2)
+                .dot(&mj_lambdas.rows(mj_lambda2, ndofs2))
+                + self.rhs[i];
+            *new_impulse = self.impulse[i] - self.r[i] * dvel;
+        }
+
+        let rolling_limit = self.rolling_friction * normal_impulse;
+        #[cfg(feature = "dim3")]
+        let spinning_limit = self.spinning_friction * normal_impulse;
+        let new_impulse = super::cap_angular_friction_impulse(
+            new_impulse,
+            rolling_limit,
+            #[cfg(feature = "dim3")]
+            spinning_limit,
+        );
+
+        for (i, new_impulse) in new_impulse.iter().enumerate() {
+            mj_lambdas.rows_mut(mj_lambda2, ndofs2).axpy(
+                *new_impulse - self.impulse[i],
+                &jacobians.rows(j_id2 + i * j_step + ndofs2, ndofs2),
+                1.0,
+            );
+        }
+
+        self.impulse = new_impulse;
+    }
+}
+
 impl VelocityGroundConstraintElement<Real> {
     #[inline]
     pub fn generic_solve_group(
         cfm_factor: Real,
         elements: &mut [Self],
         jacobians: &DVector<Real>,
-        limit: Real,
+        limits: [Real; DIM - 1],
+        angular_friction: Option<&mut VelocityGroundConstraintAngularFrictionPart<Real>>,
         ndofs2: usize,
         // Jacobian index of the first constraint.
         j_id: usize,
@@ -133,11 +179,29 @@ impl VelocityGroundConstraintElement<Real> {
             let mut tng_j_id = j_id + ndofs2 * 2;
 
             for element in elements.iter_mut() {
-                let limit = limit * element.normal_part.impulse;
+                let normal_impulse = element.normal_part.impulse;
+                let limits = limits.map(|limit| limit * normal_impulse);
                 let part = &mut element.tangent_part;
-                part.generic_solve(tng_j_id, jacobians, ndofs2, limit, mj_lambda2, mj_lambdas);
+                part.generic_solve(tng_j_id, jacobians, ndofs2, limits, mj_lambda2, mj_lambdas);
                 tng_j_id += j_step;
             }
+
+            if let Some(part) = angular_friction {
+                // The angular friction jacobians follow the ones of all the contacts.
+                let ang_j_id = j_id + elements.len() * j_step;
+                let mut normal_impulse = 0.0;
+                for element in elements.iter() {
+                    normal_impulse += element.normal_part.impulse;
+                }
+                part.generic_solve(
+                    ang_j_id,
+                    jacobians,
+                    ndofs2,
+                    normal_impulse,
+                    mj_lambda2,
+                    mj_lambdas,
+                );
+            }
         }
     }
 }
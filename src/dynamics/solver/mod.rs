@@ -8,6 +8,7 @@ pub(self) use self::parallel_solver_constraints::ParallelSolverConstraints;
 pub(self) use self::parallel_velocity_solver::ParallelVelocitySolver;
 #[cfg(not(feature = "parallel"))]
 pub(self) use self::solver_constraints::SolverConstraints;
+pub(crate) use self::substep_solver::SubstepSolver;
 #[cfg(not(feature = "parallel"))]
 pub(self) use self::velocity_solver::VelocitySolver;
 pub(self) use delta_vel::DeltaVel;
@@ -44,6 +45,7 @@ mod parallel_solver_constraints;
 mod parallel_velocity_solver;
 #[cfg(not(feature = "parallel"))]
 mod solver_constraints;
+mod substep_solver;
 mod velocity_constraint;
 mod velocity_constraint_element;
 #[cfg(feature = "simd-is-enabled")]
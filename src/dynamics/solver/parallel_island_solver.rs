@@ -322,6 +322,12 @@ impl ParallelIslandSolver {
                                 //       by the square root of the inertia tensor:
                                 dvel.angular += rb.mprops.effective_world_inv_inertia_sqrt * rb.forces.torque * params.dt;
                                 dvel.linear += rb.forces.force.component_mul(&rb.mprops.effective_inv_mass) * params.dt;
+
+                                #[cfg(feature = "dim3")]
+                                if rb.forces.gyroscopic_forces_enabled {
+                                    let impulse = rb.vels.gyroscopic_impulse(params.dt, &rb.pos.position.rotation, &rb.mprops);
+                                    dvel.angular += rb.mprops.effective_world_inv_inertia_sqrt * impulse;
+                                }
                             }
                         }
                     }
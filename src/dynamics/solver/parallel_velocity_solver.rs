@@ -210,16 +210,13 @@ impl ParallelVelocitySolver {
                             .effective_world_inv_inertia_sqrt
                             .transform_vector(dvel.angular);
 
-                        // Update positions.
+                        // The positions are integrated from these velocities by the physics pipeline.
                         let mut new_vels = rb.vels;
                         new_vels.linvel += dvel.linear;
                         new_vels.angvel += dangvel;
-                        new_vels = new_vels.apply_damping(params.dt, &rb.damping);
-                        rb.pos.next_position = new_vels.integrate(
-                            params.dt,
-                            &rb.pos.position,
-                            &rb.mprops.local_mprops.local_com,
-                        );
+                        rb.integrated_vels = new_vels
+                            .apply_damping(params.dt, &rb.damping)
+                            .apply_limits(&rb.vel_limits);
                     }
                 }
             }
@@ -309,7 +306,9 @@ impl ParallelVelocitySolver {
                             .transform_vector(dvel.angular);
                         rb.vels.linvel += dvel.linear;
                         rb.vels.angvel += dangvel;
-                        rb.vels = rb.vels.apply_damping(params.dt, &rb.damping);
+                        rb.vels = rb.vels
+                            .apply_damping(params.dt, &rb.damping)
+                            .apply_limits(&rb.vel_limits);
                     }
                 }
             }
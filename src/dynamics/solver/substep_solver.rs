@@ -0,0 +1,179 @@
+use crate::dynamics::{IslandManager, JointGraphEdge, RigidBody, RigidBodySet};
+use crate::geometry::{ContactData, ContactManifold};
+use crate::math::{Isometry, Point, Real, SpacialVector, Vector, SPATIAL_DIM};
+use crate::utils::WCross;
+
+#[derive(Copy, Clone)]
+struct JointImpulses {
+    impulses: SpacialVector<Real>,
+    limits: [Real; SPATIAL_DIM],
+    motors: [Real; SPATIAL_DIM],
+}
+
+impl JointImpulses {
+    fn zero() -> Self {
+        Self {
+            impulses: na::zero(),
+            limits: [0.0; SPATIAL_DIM],
+            motors: [0.0; SPATIAL_DIM],
+        }
+    }
+}
+
+/// Workspace used by the substepping (TGS-soft) solver mode.
+///
+/// The island solvers are run once per substep. In-between, this moves the active dynamic
+/// bodies to their new poses and updates the contact distances from the relative motion of
+/// the bodies, so the constraints of the next substep can be re-computed without running the
+/// narrow-phase again. The impulses computed by each substep are summed so the contact and joint
+/// impulses reported at the end of the timestep cover the whole timestep.
+pub(crate) struct SubstepSolver {
+    start_positions: Vec<Isometry<Real>>,
+    contact_impulses: Vec<ContactData>,
+    joint_impulses: Vec<JointImpulses>,
+}
+
+impl SubstepSolver {
+    pub fn new() -> Self {
+        Self {
+            start_positions: vec![],
+            contact_impulses: vec![],
+            joint_impulses: vec![],
+        }
+    }
+
+    /// Saves the poses of the active dynamic bodies at the beginning of the timestep.
+    pub fn init(
+        &mut self,
+        islands: &IslandManager,
+        bodies: &RigidBodySet,
+        manifolds: &[&mut ContactManifold],
+        joints: &[JointGraphEdge],
+    ) {
+        self.start_positions.clear();
+        self.start_positions.extend(
+            islands
+                .active_dynamic_bodies()
+                .iter()
+                .map(|handle| bodies[*handle].pos.position),
+        );
+
+        let num_contacts = manifolds.iter().map(|m| m.points.len()).sum();
+        self.contact_impulses.clear();
+        self.contact_impulses
+            .resize(num_contacts, ContactData::default());
+        self.joint_impulses.clear();
+        self.joint_impulses
+            .resize(joints.len(), JointImpulses::zero());
+    }
+
+    /// Accumulates the impulses computed by the last substep, and resets them to zero.
+    pub fn accumulate_impulses(
+        &mut self,
+        manifolds: &mut [&mut ContactManifold],
+        joints: &mut [JointGraphEdge],
+    ) {
+        let points = manifolds.iter_mut().flat_map(|m| m.points.iter_mut());
+        for (acc, pt) in self.contact_impulses.iter_mut().zip(points) {
+            acc.impulse += pt.data.impulse;
+            acc.tangent_impulse += pt.data.tangent_impulse;
+            pt.data.impulse = 0.0;
+            pt.data.tangent_impulse = na::zero();
+        }
+
+        for (acc, joint) in self.joint_impulses.iter_mut().zip(joints.iter_mut()) {
+            let joint = &mut joint.weight;
+            acc.impulses += joint.impulses;
+            joint.impulses.fill(0.0);
+
+            for i in 0..SPATIAL_DIM {
+                acc.limits[i] += joint.data.limits[i].impulse;
+                acc.motors[i] += joint.data.motors[i].impulse;
+                joint.data.limits[i].impulse = 0.0;
+                joint.data.motors[i].impulse = 0.0;
+            }
+        }
+    }
+
+    /// Moves the active dynamic bodies to the poses reached by the last substep.
+    ///
+    /// The solver contacts are moved with the bodies, and their distances are updated
+    /// from the relative motion of the bodies along the contact normal.
+    pub fn prepare_next_substep(
+        &mut self,
+        substep_dt: Real,
+        islands: &IslandManager,
+        bodies: &mut RigidBodySet,
+        manifolds: &mut [&mut ContactManifold],
+    ) {
+        for manifold in manifolds.iter_mut() {
+            let normal = manifold.data.normal;
+            let rb1 = manifold.data.rigid_body1.map(|h| &bodies[h]);
+            let rb2 = manifold.data.rigid_body2.map(|h| &bodies[h]);
+
+            for contact in &mut manifold.data.solver_contacts {
+                let disp1 = rb1
+                    .map(|rb| point_displacement(rb, &contact.point, substep_dt))
+                    .unwrap_or_else(Vector::zeros);
+                let disp2 = rb2
+                    .map(|rb| point_displacement(rb, &contact.point, substep_dt))
+                    .unwrap_or_else(Vector::zeros);
+
+                contact.dist += (disp2 - disp1).dot(&normal);
+                contact.point += (disp1 + disp2) * 0.5;
+            }
+        }
+
+        for handle in islands.active_dynamic_bodies() {
+            let rb = bodies.index_mut_internal(*handle);
+            rb.pos.position = rb.pos.next_position;
+            rb.mprops.update_world_mass_properties(&rb.pos.position);
+        }
+    }
+
+    /// Restores the poses of the active dynamic bodies at the beginning of the timestep, and
+    /// writes back the impulses accumulated over all the substeps.
+    pub fn finalize(
+        &mut self,
+        islands: &IslandManager,
+        bodies: &mut RigidBodySet,
+        manifolds: &mut [&mut ContactManifold],
+        joints: &mut [JointGraphEdge],
+    ) {
+        for (handle, position) in islands
+            .active_dynamic_bodies()
+            .iter()
+            .zip(self.start_positions.iter())
+        {
+            let rb = bodies.index_mut_internal(*handle);
+            rb.pos.position = *position;
+            rb.mprops.update_world_mass_properties(position);
+        }
+
+        let points = manifolds.iter_mut().flat_map(|m| m.points.iter_mut());
+        for (acc, pt) in self.contact_impulses.iter().zip(points) {
+            pt.data.impulse = acc.impulse;
+            pt.data.tangent_impulse = acc.tangent_impulse;
+        }
+
+        for (acc, joint) in self.joint_impulses.iter().zip(joints.iter_mut()) {
+            let joint = &mut joint.weight;
+            joint.impulses = acc.impulses;
+
+            for i in 0..SPATIAL_DIM {
+                joint.data.limits[i].impulse = acc.limits[i];
+                joint.data.motors[i].impulse = acc.motors[i];
+            }
+        }
+    }
+}
+
+/// The displacement, during the last substep, of a point attached to the given rigid-body.
+fn point_displacement(rb: &RigidBody, point: &Point<Real>, substep_dt: Real) -> Vector<Real> {
+    if rb.is_dynamic() {
+        rb.pos.next_position * rb.pos.position.inverse_transform_point(point) - point
+    } else {
+        // Non-dynamic bodies aren’t moved between substeps, so we rely on their velocities.
+        (rb.vels.linvel + rb.vels.angvel.gcross(point - rb.mprops.world_com)) * substep_dt
+    }
+}
@@ -5,11 +5,14 @@ use crate::dynamics::solver::{
 use crate::dynamics::solver::{WVelocityConstraint, WVelocityGroundConstraint};
 use crate::dynamics::{IntegrationParameters, RigidBodySet};
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
-use crate::math::{Real, Vector, DIM, MAX_MANIFOLD_POINTS};
+use crate::math::{Real, Vector, ANG_DIM, DIM, MAX_MANIFOLD_POINTS};
 use crate::utils::{self, WAngularInertia, WBasis, WCross, WDot};
 use na::DVector;
 
-use super::{DeltaVel, VelocityConstraintElement, VelocityConstraintNormalPart};
+use super::{
+    DeltaVel, VelocityConstraintAngularFrictionPart, VelocityConstraintElement,
+    VelocityConstraintNormalPart,
+};
 
 //#[repr(align(64))]
 #[derive(Copy, Clone, Debug)]
@@ -122,7 +125,8 @@ pub(crate) struct VelocityConstraint {
     pub im1: Vector<Real>,
     pub im2: Vector<Real>,
     pub cfm_factor: Real,
-    pub limit: Real,
+    pub limits: [Real; DIM - 1],
+    pub angular_friction: Option<VelocityConstraintAngularFrictionPart<Real>>,
     pub mj_lambda1: usize,
     pub mj_lambda2: usize,
     pub manifold_id: ContactManifoldIndex,
@@ -132,12 +136,19 @@ pub(crate) struct VelocityConstraint {
 }
 
 impl VelocityConstraint {
-    #[cfg(feature = "parallel")]
     pub fn num_active_constraints_and_jacobian_lines(manifold: &ContactManifold) -> (usize, usize) {
         let rest = manifold.data.solver_contacts.len() % MAX_MANIFOLD_POINTS != 0;
+        let num_constraints =
+            manifold.data.solver_contacts.len() / MAX_MANIFOLD_POINTS + rest as usize;
+        // Each constraint with angular friction has one more jacobian line per angular axis.
+        let num_angular_friction_lines = if manifold.data.has_angular_friction() {
+            num_constraints * ANG_DIM
+        } else {
+            0
+        };
         (
-            manifold.data.solver_contacts.len() / MAX_MANIFOLD_POINTS + rest as usize,
-            manifold.data.solver_contacts.len() * DIM,
+            num_constraints,
+            manifold.data.solver_contacts.len() * DIM + num_angular_friction_lines,
         )
     }
 
@@ -151,9 +162,9 @@ impl VelocityConstraint {
     ) {
         assert_eq!(manifold.data.relative_dominance, 0);
 
-        let cfm_factor = params.cfm_factor();
         let inv_dt = params.inv_dt();
-        let erp_inv_dt = params.erp_inv_dt();
+        let (erp_inv_dt, cfm_factor) =
+            params.contact_erp_inv_dt_and_cfm_factor(manifold.data.softness.as_ref());
 
         let handle1 = manifold.data.rigid_body1.unwrap();
         let handle2 = manifold.data.rigid_body2.unwrap();
@@ -171,8 +182,29 @@ impl VelocityConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let tangents1 = super::compute_tangent_contact_directions(
+            &force_dir1,
+            &manifold
+                .data
+                .friction_direction
+                .unwrap_or_else(Vector::zeros),
+            &vels1.linvel,
+            &vels2.linvel,
+        );
+
+        let angular_friction = if manifold.data.has_angular_friction() {
+            Some(VelocityConstraintAngularFrictionPart::new(
+                super::angular_friction_axes(&force_dir1, &tangents1),
+                &mprops1.effective_world_inv_inertia_sqrt,
+                &mprops2.effective_world_inv_inertia_sqrt,
+                vels1.angvel,
+                vels2.angvel,
+                manifold.data.rolling_friction,
+                manifold.data.spinning_friction,
+            ))
+        } else {
+            None
+        };
 
         for (_l, manifold_points) in manifold
             .data
@@ -191,7 +223,8 @@ impl VelocityConstraint {
                 im1: mprops1.effective_inv_mass,
                 im2: mprops2.effective_inv_mass,
                 cfm_factor,
-                limit: 0.0,
+                limits: [0.0; DIM - 1],
+                angular_friction,
                 mj_lambda1,
                 mj_lambda2,
                 manifold_id,
@@ -240,7 +273,8 @@ impl VelocityConstraint {
                 constraint.im1 = mprops1.effective_inv_mass;
                 constraint.im2 = mprops2.effective_inv_mass;
                 constraint.cfm_factor = cfm_factor;
-                constraint.limit = 0.0;
+                constraint.limits = [0.0; DIM - 1];
+                constraint.angular_friction = angular_friction;
                 constraint.mj_lambda1 = mj_lambda1;
                 constraint.mj_lambda2 = mj_lambda2;
                 constraint.manifold_id = manifold_id;
@@ -256,7 +290,7 @@ impl VelocityConstraint {
                 let vel1 = vels1.linvel + vels1.angvel.gcross(dp1);
                 let vel2 = vels2.linvel + vels2.angvel.gcross(dp2);
 
-                constraint.limit = manifold_point.friction;
+                constraint.limits = manifold.data.friction_limits(manifold_point);
                 constraint.manifold_contact_id[k] = manifold_point.contact_id;
 
                 // Normal part.
@@ -366,7 +400,8 @@ impl VelocityConstraint {
             &self.tangent1,
             &self.im1,
             &self.im2,
-            self.limit,
+            self.limits,
+            self.angular_friction.as_mut(),
             &mut mj_lambda1,
             &mut mj_lambda2,
             solve_normal,
@@ -408,6 +443,7 @@ impl VelocityConstraint {
 #[cfg(feature = "dim3")]
 pub(crate) fn compute_tangent_contact_directions<N>(
     force_dir1: &Vector<N>,
+    friction_direction: &Vector<N>,
     linvel1: &Vector<N>,
     linvel2: &Vector<N>,
 ) -> [Vector<N>; DIM - 1]
@@ -417,7 +453,8 @@ where
 {
     use na::SimdValue;
 
-    // Compute the tangent direction. Pick the direction of
+    // Compute the tangent direction. Pick the friction direction of
+    // the contact, if it is not zero. Otherwise, pick the direction of
     // the linear relative velocity, if it is not too small.
     // Otherwise use a fallback direction.
     let relative_linvel = linvel1 - linvel2;
@@ -435,6 +472,8 @@ where
     let tangent_fallback = force_dir1.orthonormal_vector();
 
     let tangent1 = tangent_fallback.select(use_fallback, tangent_relative_linvel);
+    let use_friction_direction = friction_direction.norm_squared().simd_gt(N::zero());
+    let tangent1 = friction_direction.select(use_friction_direction, tangent1);
     let bitangent1 = force_dir1.cross(&tangent1);
 
     [tangent1, bitangent1]
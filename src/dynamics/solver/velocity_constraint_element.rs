@@ -1,6 +1,6 @@
 use super::DeltaVel;
-use crate::math::{AngVector, Vector, DIM};
-use crate::utils::{WBasis, WDot, WReal};
+use crate::math::{AngVector, Vector, ANG_DIM, DIM};
+use crate::utils::{WAngularInertia, WBasis, WDot, WReal};
 
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct VelocityConstraintTangentPart<N: WReal> {
@@ -37,7 +37,7 @@ impl<N: WReal> VelocityConstraintTangentPart<N> {
         tangents1: [&Vector<N>; DIM - 1],
         im1: &Vector<N>,
         im2: &Vector<N>,
-        limit: N,
+        limits: [N; DIM - 1],
         mj_lambda1: &mut DeltaVel<N>,
         mj_lambda2: &mut DeltaVel<N>,
     ) where
@@ -50,7 +50,8 @@ impl<N: WReal> VelocityConstraintTangentPart<N> {
                 - tangents1[0].dot(&mj_lambda2.linear)
                 + self.gcross2[0].gdot(mj_lambda2.angular)
                 + self.rhs[0];
-            let new_impulse = (self.impulse[0] - self.r[0] * dvel).simd_clamp(-limit, limit);
+            let new_impulse =
+                (self.impulse[0] - self.r[0] * dvel).simd_clamp(-limits[0], limits[0]);
             let dlambda = new_impulse - self.impulse[0];
             self.impulse[0] = new_impulse;
 
@@ -87,7 +88,7 @@ impl<N: WReal> VelocityConstraintTangentPart<N> {
                 let _disable_fe_except =
                         crate::utils::DisableFloatingPointExceptionsFlags::
                         disable_floating_point_exceptions();
-                new_impulse.simd_cap_magnitude(limit)
+                super::cap_friction_impulse(new_impulse, limits)
             };
 
             let dlambda = new_impulse - self.impulse;
@@ -154,6 +155,162 @@ impl<N: WReal> VelocityConstraintNormalPart<N> {
     }
 }
 
+/// The axes of the angular friction of a contact: the rolling axes, followed by the
+/// spinning axis (the contact normal) in 3D.
+#[inline]
+pub(crate) fn angular_friction_axes<N: WReal>(
+    dir1: &Vector<N>,
+    tangents1: &[Vector<N>; DIM - 1],
+) -> [AngVector<N>; ANG_DIM] {
+    #[cfg(feature = "dim2")]
+    {
+        let _ = (dir1, tangents1);
+        [N::one()]
+    }
+    #[cfg(feature = "dim3")]
+    {
+        [tangents1[0], tangents1[1], *dir1]
+    }
+}
+
+/// Caps a friction impulse to the ellipse with the given semi-axes, along the two tangent
+/// directions of a contact.
+///
+/// This reduces to capping its magnitude if both semi-axes are equal.
+#[cfg(feature = "dim3")]
+#[inline(always)]
+pub(crate) fn cap_friction_impulse<N: WReal>(
+    impulse: na::Vector2<N>,
+    limits: [N; 2],
+) -> na::Vector2<N> {
+    let inv_limits = limits.map(crate::utils::simd_inv);
+    let normalized = na::vector![impulse[0] * inv_limits[0], impulse[1] * inv_limits[1]];
+    let capped = normalized.simd_cap_magnitude(N::one());
+    na::vector![capped[0] * limits[0], capped[1] * limits[1]]
+}
+
+/// Caps an angular friction impulse, along the axes given by [`angular_friction_axes`], to the
+/// rolling and spinning friction limits.
+#[inline(always)]
+pub(crate) fn cap_angular_friction_impulse<N: WReal>(
+    mut impulse: [N; ANG_DIM],
+    rolling_limit: N,
+    #[cfg(feature = "dim3")] spinning_limit: N,
+) -> [N; ANG_DIM] {
+    #[cfg(feature = "dim2")]
+    {
+        impulse[0] = impulse[0].simd_clamp(-rolling_limit, rolling_limit);
+    }
+
+    #[cfg(feature = "dim3")]
+    {
+        let rolling_impulse = {
+            let _disable_fe_except =
+                crate::utils::DisableFloatingPointExceptionsFlags::
+                disable_floating_point_exceptions();
+            na::vector![impulse[0], impulse[1]].simd_cap_magnitude(rolling_limit)
+        };
+        impulse[0] = rolling_impulse[0];
+        impulse[1] = rolling_impulse[1];
+        impulse[2] = impulse[2].simd_clamp(-spinning_limit, spinning_limit);
+    }
+
+    impulse
+}
+
+/// The angular friction of a contact constraint, resisting the relative rolling (and, in 3D,
+/// spinning) of the two bodies in contact.
+///
+/// Its impulse along the rolling axes (resp. the spinning axis) is limited by the rolling
+/// (resp. spinning) friction coefficient multiplied by the total normal impulse of the contact.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct VelocityConstraintAngularFrictionPart<N: WReal> {
+    pub gcross1: [AngVector<N>; ANG_DIM],
+    pub gcross2: [AngVector<N>; ANG_DIM],
+    pub rhs: [N; ANG_DIM],
+    pub impulse: [N; ANG_DIM],
+    pub r: [N; ANG_DIM],
+    pub rolling_friction: N,
+    #[cfg(feature = "dim3")]
+    pub spinning_friction: N,
+}
+
+impl<N: WReal> VelocityConstraintAngularFrictionPart<N> {
+    pub fn new<I: WAngularInertia<N, AngVector = AngVector<N>>>(
+        axes: [AngVector<N>; ANG_DIM],
+        ii1: &I,
+        ii2: &I,
+        angvel1: AngVector<N>,
+        angvel2: AngVector<N>,
+        rolling_friction: N,
+        spinning_friction: N,
+    ) -> Self
+    where
+        AngVector<N>: WDot<AngVector<N>, Result = N>,
+    {
+        let mut result = Self {
+            gcross1: [na::zero(); ANG_DIM],
+            gcross2: [na::zero(); ANG_DIM],
+            rhs: [na::zero(); ANG_DIM],
+            impulse: [na::zero(); ANG_DIM],
+            r: [na::zero(); ANG_DIM],
+            rolling_friction,
+            #[cfg(feature = "dim3")]
+            spinning_friction,
+        };
+        #[cfg(feature = "dim2")]
+        let _ = spinning_friction;
+
+        for (i, axis) in axes.iter().enumerate() {
+            let gcross1 = ii1.transform_vector(*axis);
+            let gcross2 = ii2.transform_vector(-*axis);
+            result.gcross1[i] = gcross1;
+            result.gcross2[i] = gcross2;
+            result.rhs[i] = (angvel1 - angvel2).gdot(*axis);
+            result.r[i] = crate::utils::simd_inv(gcross1.gdot(gcross1) + gcross2.gdot(gcross2));
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn solve(
+        &mut self,
+        normal_impulse: N,
+        mj_lambda1: &mut DeltaVel<N>,
+        mj_lambda2: &mut DeltaVel<N>,
+    ) where
+        AngVector<N>: WDot<AngVector<N>, Result = N>,
+    {
+        let mut new_impulse = self.impulse;
+
+        for (i, new_impulse) in new_impulse.iter_mut().enumerate() {
+            let dvel = self.gcross1[i].gdot(mj_lambda1.angular)
+                + self.gcross2[i].gdot(mj_lambda2.angular)
+                + self.rhs[i];
+            *new_impulse = self.impulse[i] - self.r[i] * dvel;
+        }
+
+        let rolling_limit = self.rolling_friction * normal_impulse;
+        #[cfg(feature = "dim3")]
+        let spinning_limit = self.spinning_friction * normal_impulse;
+        let new_impulse = cap_angular_friction_impulse(
+            new_impulse,
+            rolling_limit,
+            #[cfg(feature = "dim3")]
+            spinning_limit,
+        );
+
+        for (i, new_impulse) in new_impulse.iter().enumerate() {
+            let dlambda = *new_impulse - self.impulse[i];
+            mj_lambda1.angular += self.gcross1[i] * dlambda;
+            mj_lambda2.angular += self.gcross2[i] * dlambda;
+        }
+
+        self.impulse = new_impulse;
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct VelocityConstraintElement<N: WReal> {
     pub normal_part: VelocityConstraintNormalPart<N>,
@@ -176,7 +333,8 @@ impl<N: WReal> VelocityConstraintElement<N> {
         #[cfg(feature = "dim3")] tangent1: &Vector<N>,
         im1: &Vector<N>,
         im2: &Vector<N>,
-        limit: N,
+        limits: [N; DIM - 1],
+        angular_friction: Option<&mut VelocityConstraintAngularFrictionPart<N>>,
         mj_lambda1: &mut DeltaVel<N>,
         mj_lambda2: &mut DeltaVel<N>,
         solve_normal: bool,
@@ -202,9 +360,18 @@ impl<N: WReal> VelocityConstraintElement<N> {
             let tangents1 = [&dir1.orthonormal_vector()];
 
             for element in elements.iter_mut() {
-                let limit = limit * element.normal_part.impulse;
+                let normal_impulse = element.normal_part.impulse;
+                let limits = limits.map(|limit| limit * normal_impulse);
                 let part = &mut element.tangent_part;
-                part.solve(tangents1, im1, im2, limit, mj_lambda1, mj_lambda2);
+                part.solve(tangents1, im1, im2, limits, mj_lambda1, mj_lambda2);
+            }
+
+            if let Some(part) = angular_friction {
+                let mut normal_impulse = N::zero();
+                for element in elements.iter() {
+                    normal_impulse += element.normal_part.impulse;
+                }
+                part.solve(normal_impulse, mj_lambda1, mj_lambda2);
             }
         }
     }
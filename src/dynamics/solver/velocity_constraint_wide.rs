@@ -1,5 +1,6 @@
 use super::{
-    AnyVelocityConstraint, DeltaVel, VelocityConstraintElement, VelocityConstraintNormalPart,
+    AnyVelocityConstraint, DeltaVel, VelocityConstraintAngularFrictionPart,
+    VelocityConstraintElement, VelocityConstraintNormalPart,
 };
 use crate::dynamics::{
     IntegrationParameters, RigidBodyIds, RigidBodyMassProps, RigidBodySet, RigidBodyVelocity,
@@ -25,7 +26,8 @@ pub(crate) struct WVelocityConstraint {
     pub im1: Vector<SimdReal>,
     pub im2: Vector<SimdReal>,
     pub cfm_factor: SimdReal,
-    pub limit: SimdReal,
+    pub limits: [SimdReal; DIM - 1],
+    pub angular_friction: Option<VelocityConstraintAngularFrictionPart<SimdReal>>,
     pub mj_lambda1: [usize; SIMD_WIDTH],
     pub mj_lambda2: [usize; SIMD_WIDTH],
     pub manifold_id: [ContactManifoldIndex; SIMD_WIDTH],
@@ -45,11 +47,14 @@ impl WVelocityConstraint {
             assert_eq!(manifolds[ii].data.relative_dominance, 0);
         }
 
-        let cfm_factor = SimdReal::splat(params.cfm_factor());
+        let softness = gather![
+            |ii| params.contact_erp_inv_dt_and_cfm_factor(manifolds[ii].data.softness.as_ref())
+        ];
+        let cfm_factor = SimdReal::from(gather![|ii| softness[ii].1]);
         let dt = SimdReal::splat(params.dt);
         let inv_dt = SimdReal::splat(params.inv_dt());
         let allowed_lin_err = SimdReal::splat(params.allowed_linear_error);
-        let erp_inv_dt = SimdReal::splat(params.erp_inv_dt());
+        let erp_inv_dt = SimdReal::from(gather![|ii| softness[ii].0]);
         let max_penetration_correction = SimdReal::splat(params.max_penetration_correction);
 
         let handles1 = gather![|ii| manifolds[ii].data.rigid_body1.unwrap()];
@@ -92,7 +97,29 @@ impl WVelocityConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 = super::compute_tangent_contact_directions(&force_dir1, &linvel1, &linvel2);
+        let tangents1 = super::compute_tangent_contact_directions(
+            &force_dir1,
+            &Vector::from(gather![|ii| manifolds[ii]
+                .data
+                .friction_direction
+                .unwrap_or_else(Vector::zeros)]),
+            &linvel1,
+            &linvel2,
+        );
+
+        let angular_friction = if manifolds.iter().any(|m| m.data.has_angular_friction()) {
+            Some(VelocityConstraintAngularFrictionPart::new(
+                super::angular_friction_axes(&force_dir1, &tangents1),
+                &ii1,
+                &ii2,
+                angvel1,
+                angvel2,
+                SimdReal::from(gather![|ii| manifolds[ii].data.rolling_friction]),
+                SimdReal::from(gather![|ii| manifolds[ii].data.spinning_friction]),
+            ))
+        } else {
+            None
+        };
 
         for l in (0..num_active_contacts).step_by(MAX_MANIFOLD_POINTS) {
             let manifold_points =
@@ -108,7 +135,8 @@ impl WVelocityConstraint {
                 im1,
                 im2,
                 cfm_factor,
-                limit: SimdReal::splat(0.0),
+                limits: [SimdReal::splat(0.0); DIM - 1],
+                angular_friction,
                 mj_lambda1,
                 mj_lambda2,
                 manifold_id,
@@ -117,7 +145,8 @@ impl WVelocityConstraint {
             };
 
             for k in 0..num_points {
-                let friction = SimdReal::from(gather![|ii| manifold_points[ii][k].friction]);
+                let friction_limits =
+                    gather![|ii| manifolds[ii].data.friction_limits(&manifold_points[ii][k])];
                 let restitution = SimdReal::from(gather![|ii| manifold_points[ii][k].restitution]);
                 let is_bouncy = SimdReal::from(gather![
                     |ii| manifold_points[ii][k].is_bouncy() as u32 as Real
@@ -133,7 +162,8 @@ impl WVelocityConstraint {
                 let vel1 = linvel1 + angvel1.gcross(dp1);
                 let vel2 = linvel2 + angvel2.gcross(dp2);
 
-                constraint.limit = friction;
+                constraint.limits =
+                    std::array::from_fn(|i| SimdReal::from(gather![|ii| friction_limits[ii][i]]));
                 constraint.manifold_contact_id[k] = gather![|ii| manifold_points[ii][k].contact_id];
 
                 // Normal part.
@@ -242,7 +272,8 @@ impl WVelocityConstraint {
             &self.tangent1,
             &self.im1,
             &self.im2,
-            self.limit,
+            self.limits,
+            self.angular_friction.as_mut(),
             &mut mj_lambda1,
             &mut mj_lambda2,
             solve_normal,
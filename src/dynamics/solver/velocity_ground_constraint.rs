@@ -1,6 +1,6 @@
 use super::{
-    AnyVelocityConstraint, DeltaVel, VelocityGroundConstraintElement,
-    VelocityGroundConstraintNormalPart,
+    AnyVelocityConstraint, DeltaVel, VelocityGroundConstraintAngularFrictionPart,
+    VelocityGroundConstraintElement, VelocityGroundConstraintNormalPart,
 };
 use crate::math::{Point, Real, Vector, DIM, MAX_MANIFOLD_POINTS};
 #[cfg(feature = "dim2")]
@@ -18,7 +18,8 @@ pub(crate) struct VelocityGroundConstraint {
     pub tangent1: Vector<Real>, // One of the friction force directions.
     pub im2: Vector<Real>,
     pub cfm_factor: Real,
-    pub limit: Real,
+    pub limits: [Real; DIM - 1],
+    pub angular_friction: Option<VelocityGroundConstraintAngularFrictionPart<Real>>,
     pub elements: [VelocityGroundConstraintElement<Real>; MAX_MANIFOLD_POINTS],
 
     pub manifold_id: ContactManifoldIndex,
@@ -35,9 +36,9 @@ impl VelocityGroundConstraint {
         out_constraints: &mut Vec<AnyVelocityConstraint>,
         insert_at: Option<usize>,
     ) {
-        let cfm_factor = params.cfm_factor();
         let inv_dt = params.inv_dt();
-        let erp_inv_dt = params.erp_inv_dt();
+        let (erp_inv_dt, cfm_factor) =
+            params.contact_erp_inv_dt_and_cfm_factor(manifold.data.softness.as_ref());
 
         let mut handle1 = manifold.data.rigid_body1;
         let mut handle2 = manifold.data.rigid_body2;
@@ -64,8 +65,28 @@ impl VelocityGroundConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let tangents1 = super::compute_tangent_contact_directions(
+            &force_dir1,
+            &manifold
+                .data
+                .friction_direction
+                .unwrap_or_else(Vector::zeros),
+            &vels1.linvel,
+            &vels2.linvel,
+        );
+
+        let angular_friction = if manifold.data.has_angular_friction() {
+            Some(VelocityGroundConstraintAngularFrictionPart::new(
+                super::angular_friction_axes(&force_dir1, &tangents1),
+                &mprops2.effective_world_inv_inertia_sqrt,
+                vels1.angvel,
+                vels2.angvel,
+                manifold.data.rolling_friction,
+                manifold.data.spinning_friction,
+            ))
+        } else {
+            None
+        };
 
         let mj_lambda2 = rb2.ids.active_set_offset;
 
@@ -83,7 +104,8 @@ impl VelocityGroundConstraint {
                 elements: [VelocityGroundConstraintElement::zero(); MAX_MANIFOLD_POINTS],
                 im2: mprops2.effective_inv_mass,
                 cfm_factor,
-                limit: 0.0,
+                limits: [0.0; DIM - 1],
+                angular_friction,
                 mj_lambda2,
                 manifold_id,
                 manifold_contact_id: [0; MAX_MANIFOLD_POINTS],
@@ -130,7 +152,8 @@ impl VelocityGroundConstraint {
                 }
                 constraint.im2 = mprops2.effective_inv_mass;
                 constraint.cfm_factor = cfm_factor;
-                constraint.limit = 0.0;
+                constraint.limits = [0.0; DIM - 1];
+                constraint.angular_friction = angular_friction;
                 constraint.mj_lambda2 = mj_lambda2;
                 constraint.manifold_id = manifold_id;
                 constraint.manifold_contact_id = [0; MAX_MANIFOLD_POINTS];
@@ -146,7 +169,7 @@ impl VelocityGroundConstraint {
                 let vel1 = vels1.linvel + vels1.angvel.gcross(dp1);
                 let vel2 = vels2.linvel + vels2.angvel.gcross(dp2);
 
-                constraint.limit = manifold_point.friction;
+                constraint.limits = manifold.data.friction_limits(manifold_point);
                 constraint.manifold_contact_id[k] = manifold_point.contact_id;
 
                 // Normal part.
@@ -243,7 +266,8 @@ impl VelocityGroundConstraint {
             #[cfg(feature = "dim3")]
             &self.tangent1,
             &self.im2,
-            self.limit,
+            self.limits,
+            self.angular_friction.as_mut(),
             &mut mj_lambda2,
             solve_normal,
             solve_friction,
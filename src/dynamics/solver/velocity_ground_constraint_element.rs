@@ -1,6 +1,6 @@
 use super::DeltaVel;
-use crate::math::{AngVector, Vector, DIM};
-use crate::utils::{WBasis, WDot, WReal};
+use crate::math::{AngVector, Vector, ANG_DIM, DIM};
+use crate::utils::{WAngularInertia, WBasis, WDot, WReal};
 
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct VelocityGroundConstraintTangentPart<N: WReal> {
@@ -34,7 +34,7 @@ impl<N: WReal> VelocityGroundConstraintTangentPart<N> {
         &mut self,
         tangents1: [&Vector<N>; DIM - 1],
         im2: &Vector<N>,
-        limit: N,
+        limits: [N; DIM - 1],
         mj_lambda2: &mut DeltaVel<N>,
     ) where
         AngVector<N>: WDot<AngVector<N>, Result = N>,
@@ -44,7 +44,8 @@ impl<N: WReal> VelocityGroundConstraintTangentPart<N> {
             let dvel = -tangents1[0].dot(&mj_lambda2.linear)
                 + self.gcross2[0].gdot(mj_lambda2.angular)
                 + self.rhs[0];
-            let new_impulse = (self.impulse[0] - self.r[0] * dvel).simd_clamp(-limit, limit);
+            let new_impulse =
+                (self.impulse[0] - self.r[0] * dvel).simd_clamp(-limits[0], limits[0]);
             let dlambda = new_impulse - self.impulse[0];
             self.impulse[0] = new_impulse;
 
@@ -74,7 +75,7 @@ impl<N: WReal> VelocityGroundConstraintTangentPart<N> {
                 let _disable_fe_except =
                     crate::utils::DisableFloatingPointExceptionsFlags::
                     disable_floating_point_exceptions();
-                new_impulse.simd_cap_magnitude(limit)
+                super::cap_friction_impulse(new_impulse, limits)
             };
             let dlambda = new_impulse - self.impulse;
             self.impulse = new_impulse;
@@ -126,6 +127,84 @@ impl<N: WReal> VelocityGroundConstraintNormalPart<N> {
     }
 }
 
+/// The angular friction of a contact constraint involving a single dynamic body.
+///
+/// See `VelocityConstraintAngularFrictionPart` for details.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct VelocityGroundConstraintAngularFrictionPart<N: WReal> {
+    pub gcross2: [AngVector<N>; ANG_DIM],
+    pub rhs: [N; ANG_DIM],
+    pub impulse: [N; ANG_DIM],
+    pub r: [N; ANG_DIM],
+    pub rolling_friction: N,
+    #[cfg(feature = "dim3")]
+    pub spinning_friction: N,
+}
+
+impl<N: WReal> VelocityGroundConstraintAngularFrictionPart<N> {
+    pub fn new<I: WAngularInertia<N, AngVector = AngVector<N>>>(
+        axes: [AngVector<N>; ANG_DIM],
+        ii2: &I,
+        angvel1: AngVector<N>,
+        angvel2: AngVector<N>,
+        rolling_friction: N,
+        spinning_friction: N,
+    ) -> Self
+    where
+        AngVector<N>: WDot<AngVector<N>, Result = N>,
+    {
+        let mut result = Self {
+            gcross2: [na::zero(); ANG_DIM],
+            rhs: [na::zero(); ANG_DIM],
+            impulse: [na::zero(); ANG_DIM],
+            r: [na::zero(); ANG_DIM],
+            rolling_friction,
+            #[cfg(feature = "dim3")]
+            spinning_friction,
+        };
+        #[cfg(feature = "dim2")]
+        let _ = spinning_friction;
+
+        for (i, axis) in axes.iter().enumerate() {
+            let gcross2 = ii2.transform_vector(-*axis);
+            result.gcross2[i] = gcross2;
+            result.rhs[i] = (angvel1 - angvel2).gdot(*axis);
+            result.r[i] = crate::utils::simd_inv(gcross2.gdot(gcross2));
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn solve(&mut self, normal_impulse: N, mj_lambda2: &mut DeltaVel<N>)
+    where
+        AngVector<N>: WDot<AngVector<N>, Result = N>,
+    {
+        let mut new_impulse = self.impulse;
+
+        for (i, new_impulse) in new_impulse.iter_mut().enumerate() {
+            let dvel = self.gcross2[i].gdot(mj_lambda2.angular) + self.rhs[i];
+            *new_impulse = self.impulse[i] - self.r[i] * dvel;
+        }
+
+        let rolling_limit = self.rolling_friction * normal_impulse;
+        #[cfg(feature = "dim3")]
+        let spinning_limit = self.spinning_friction * normal_impulse;
+        let new_impulse = super::cap_angular_friction_impulse(
+            new_impulse,
+            rolling_limit,
+            #[cfg(feature = "dim3")]
+            spinning_limit,
+        );
+
+        for (i, new_impulse) in new_impulse.iter().enumerate() {
+            mj_lambda2.angular += self.gcross2[i] * (*new_impulse - self.impulse[i]);
+        }
+
+        self.impulse = new_impulse;
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct VelocityGroundConstraintElement<N: WReal> {
     pub normal_part: VelocityGroundConstraintNormalPart<N>,
@@ -147,7 +226,8 @@ impl<N: WReal> VelocityGroundConstraintElement<N> {
         dir1: &Vector<N>,
         #[cfg(feature = "dim3")] tangent1: &Vector<N>,
         im2: &Vector<N>,
-        limit: N,
+        limits: [N; DIM - 1],
+        angular_friction: Option<&mut VelocityGroundConstraintAngularFrictionPart<N>>,
         mj_lambda2: &mut DeltaVel<N>,
         solve_normal: bool,
         solve_friction: bool,
@@ -172,9 +252,18 @@ impl<N: WReal> VelocityGroundConstraintElement<N> {
             let tangents1 = [&dir1.orthonormal_vector()];
 
             for element in elements.iter_mut() {
-                let limit = limit * element.normal_part.impulse;
+                let normal_impulse = element.normal_part.impulse;
+                let limits = limits.map(|limit| limit * normal_impulse);
                 let part = &mut element.tangent_part;
-                part.solve(tangents1, im2, limit, mj_lambda2);
+                part.solve(tangents1, im2, limits, mj_lambda2);
+            }
+
+            if let Some(part) = angular_friction {
+                let mut normal_impulse = N::zero();
+                for element in elements.iter() {
+                    normal_impulse += element.normal_part.impulse;
+                }
+                part.solve(normal_impulse, mj_lambda2);
             }
         }
     }
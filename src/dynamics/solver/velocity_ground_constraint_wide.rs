@@ -1,6 +1,6 @@
 use super::{
-    AnyVelocityConstraint, DeltaVel, VelocityGroundConstraintElement,
-    VelocityGroundConstraintNormalPart,
+    AnyVelocityConstraint, DeltaVel, VelocityGroundConstraintAngularFrictionPart,
+    VelocityGroundConstraintElement, VelocityGroundConstraintNormalPart,
 };
 use crate::dynamics::{
     IntegrationParameters, RigidBodyIds, RigidBodyMassProps, RigidBodySet, RigidBodyVelocity,
@@ -25,7 +25,8 @@ pub(crate) struct WVelocityGroundConstraint {
     pub num_contacts: u8,
     pub im2: Vector<SimdReal>,
     pub cfm_factor: SimdReal,
-    pub limit: SimdReal,
+    pub limits: [SimdReal; DIM - 1],
+    pub angular_friction: Option<VelocityGroundConstraintAngularFrictionPart<SimdReal>>,
     pub mj_lambda2: [usize; SIMD_WIDTH],
     pub manifold_id: [ContactManifoldIndex; SIMD_WIDTH],
     pub manifold_contact_id: [[u8; SIMD_WIDTH]; MAX_MANIFOLD_POINTS],
@@ -40,11 +41,14 @@ impl WVelocityGroundConstraint {
         out_constraints: &mut Vec<AnyVelocityConstraint>,
         insert_at: Option<usize>,
     ) {
-        let cfm_factor = SimdReal::splat(params.cfm_factor());
+        let softness = gather![
+            |ii| params.contact_erp_inv_dt_and_cfm_factor(manifolds[ii].data.softness.as_ref())
+        ];
+        let cfm_factor = SimdReal::from(gather![|ii| softness[ii].1]);
         let dt = SimdReal::splat(params.dt);
         let inv_dt = SimdReal::splat(params.inv_dt());
         let allowed_lin_err = SimdReal::splat(params.allowed_linear_error);
-        let erp_inv_dt = SimdReal::splat(params.erp_inv_dt());
+        let erp_inv_dt = SimdReal::from(gather![|ii| softness[ii].0]);
         let max_penetration_correction = SimdReal::splat(params.max_penetration_correction);
 
         let mut handles1 = gather![|ii| manifolds[ii].data.rigid_body1];
@@ -100,7 +104,28 @@ impl WVelocityGroundConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 = super::compute_tangent_contact_directions(&force_dir1, &linvel1, &linvel2);
+        let tangents1 = super::compute_tangent_contact_directions(
+            &force_dir1,
+            &Vector::from(gather![|ii| manifolds[ii]
+                .data
+                .friction_direction
+                .unwrap_or_else(Vector::zeros)]),
+            &linvel1,
+            &linvel2,
+        );
+
+        let angular_friction = if manifolds.iter().any(|m| m.data.has_angular_friction()) {
+            Some(VelocityGroundConstraintAngularFrictionPart::new(
+                super::angular_friction_axes(&force_dir1, &tangents1),
+                &ii2,
+                angvel1,
+                angvel2,
+                SimdReal::from(gather![|ii| manifolds[ii].data.rolling_friction]),
+                SimdReal::from(gather![|ii| manifolds[ii].data.spinning_friction]),
+            ))
+        } else {
+            None
+        };
 
         for l in (0..num_active_contacts).step_by(MAX_MANIFOLD_POINTS) {
             let manifold_points = gather![|ii| &manifolds[ii].data.solver_contacts[l..]];
@@ -114,7 +139,8 @@ impl WVelocityGroundConstraint {
                 elements: [VelocityGroundConstraintElement::zero(); MAX_MANIFOLD_POINTS],
                 im2,
                 cfm_factor,
-                limit: SimdReal::splat(0.0),
+                limits: [SimdReal::splat(0.0); DIM - 1],
+                angular_friction,
                 mj_lambda2,
                 manifold_id,
                 manifold_contact_id: [[0; SIMD_WIDTH]; MAX_MANIFOLD_POINTS],
@@ -122,7 +148,8 @@ impl WVelocityGroundConstraint {
             };
 
             for k in 0..num_points {
-                let friction = SimdReal::from(gather![|ii| manifold_points[ii][k].friction]);
+                let friction_limits =
+                    gather![|ii| manifolds[ii].data.friction_limits(&manifold_points[ii][k])];
                 let restitution = SimdReal::from(gather![|ii| manifold_points[ii][k].restitution]);
                 let is_bouncy = SimdReal::from(gather![
                     |ii| manifold_points[ii][k].is_bouncy() as u32 as Real
@@ -139,7 +166,8 @@ impl WVelocityGroundConstraint {
                 let vel1 = linvel1 + angvel1.gcross(dp1);
                 let vel2 = linvel2 + angvel2.gcross(dp2);
 
-                constraint.limit = friction;
+                constraint.limits =
+                    std::array::from_fn(|i| SimdReal::from(gather![|ii| friction_limits[ii][i]]));
                 constraint.manifold_contact_id[k] = gather![|ii| manifold_points[ii][k].contact_id];
 
                 // Normal part.
@@ -229,7 +257,8 @@ impl WVelocityGroundConstraint {
             #[cfg(feature = "dim3")]
             &self.tangent1,
             &self.im2,
-            self.limit,
+            self.limits,
+            self.angular_friction.as_mut(),
             &mut mj_lambda2,
             solve_normal,
             solve_friction,
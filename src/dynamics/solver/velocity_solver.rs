@@ -65,6 +65,16 @@ impl VelocitySolver {
                     rb.mprops.effective_world_inv_inertia_sqrt * rb.forces.torque * params.dt;
                 dvel.linear +=
                     rb.forces.force.component_mul(&rb.mprops.effective_inv_mass) * params.dt;
+
+                #[cfg(feature = "dim3")]
+                if rb.forces.gyroscopic_forces_enabled {
+                    let impulse = rb.vels.gyroscopic_impulse(
+                        params.dt,
+                        &rb.pos.position.rotation,
+                        &rb.mprops,
+                    );
+                    dvel.angular += rb.mprops.effective_world_inv_inertia_sqrt * impulse;
+                }
             }
         }
 
@@ -153,19 +163,13 @@ impl VelocitySolver {
                     .effective_world_inv_inertia_sqrt
                     .transform_vector(dvel.angular);
 
-                // Update positions.
-                let mut new_pos = rb.pos;
+                // The positions are integrated from these velocities by the physics pipeline.
                 let mut new_vels = rb.vels;
                 new_vels.linvel += dvel.linear;
                 new_vels.angvel += dangvel;
-                new_vels = new_vels.apply_damping(params.dt, &rb.damping);
-                new_pos.next_position = new_vels.integrate(
-                    params.dt,
-                    &rb.pos.position,
-                    &rb.mprops.local_mprops.local_com,
-                );
-                rb.integrated_vels = new_vels;
-                rb.pos = new_pos;
+                rb.integrated_vels = new_vels
+                    .apply_damping(params.dt, &rb.damping)
+                    .apply_limits(&rb.vel_limits);
             }
         }
 
@@ -229,7 +233,10 @@ impl VelocitySolver {
 
                 rb.vels.linvel += dvel.linear;
                 rb.vels.angvel += dangvel;
-                rb.vels = rb.vels.apply_damping(params.dt, &rb.damping);
+                rb.vels = rb
+                    .vels
+                    .apply_damping(params.dt, &rb.damping)
+                    .apply_limits(&rb.vel_limits);
             }
         }
 
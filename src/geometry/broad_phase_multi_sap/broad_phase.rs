@@ -3,7 +3,7 @@ use super::{
 };
 use crate::geometry::broad_phase_multi_sap::SAPProxyIndex;
 use crate::geometry::{
-    ColliderBroadPhaseData, ColliderChanges, ColliderHandle, ColliderPosition, ColliderSet,
+    Aabb, ColliderBroadPhaseData, ColliderChanges, ColliderHandle, ColliderPosition, ColliderSet,
     ColliderShape,
 };
 use crate::math::Real;
@@ -120,6 +120,21 @@ impl Default for BroadPhase {
     }
 }
 
+/// The part of the broad-phase state modified by a simulation step.
+///
+/// This contains the proxies (with their Aabbs), the regions of each layer and the sorted
+/// endpoints of their axes, i.e., everything that determines the pairs reported by the next
+/// update. The collider-to-proxy map and the workspaces are not captured.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+pub(crate) struct BroadPhaseState {
+    proxies: SAPProxies,
+    layers: Vec<SAPLayer>,
+    smallest_layer: u8,
+    largest_layer: u8,
+    reporting_capacity: usize,
+}
+
 impl BroadPhase {
     /// Create a new empty broad-phase.
     pub fn new() -> Self {
@@ -509,6 +524,105 @@ impl BroadPhase {
         self.complete_removals(colliders, removed_colliders);
     }
 
+    /// Rebuilds this broad-phase after every collider was translated by the same amount.
+    ///
+    /// The Aabbs of the colliders must be re-discretized into the regions of the hierarchical grid,
+    /// so this broad-phase is rebuilt from the current collider positions. Every overlapping pair
+    /// found by the rebuild is reported as an `AddPair` event, including the pairs already known
+    /// before the shift. Because rounding errors can change the overlaps of the translated Aabbs,
+    /// the pairs known before the shift and not reported anymore must be removed: give these
+    /// events to [`NarrowPhase::replace_pairs`](crate::geometry::NarrowPhase::replace_pairs).
+    ///
+    /// This is meant to be used for shifting the origin of the whole simulation, see
+    /// `PhysicsWorld::shift_origin`.
+    pub fn shift_origin(
+        &mut self,
+        prediction_distance: Real,
+        colliders: &mut ColliderSet,
+        events: &mut Vec<BroadPhasePairEvent>,
+    ) {
+        self.proxies = SAPProxies::new();
+        self.layers.clear();
+        self.smallest_layer = 0;
+        self.largest_layer = 0;
+        self.colliders_proxy_ids.clear();
+
+        let mut need_region_propagation = false;
+
+        for (handle, co) in colliders.colliders.iter_mut() {
+            let handle = ColliderHandle(handle);
+            co.bf_data.proxy_index = crate::INVALID_U32;
+
+            if !co.is_enabled() {
+                continue;
+            }
+
+            // NOTE: the colliders with pending modifications are inserted too, so that their
+            //       pairs are kept. The next call to `update` handles them as modified proxies.
+            let mut proxy_index = crate::INVALID_U32;
+
+            if self.handle_modified_collider(
+                prediction_distance,
+                handle,
+                &mut proxy_index,
+                (&co.pos, &co.shape, &ColliderChanges::empty()),
+            ) {
+                need_region_propagation = true;
+            }
+
+            if proxy_index != crate::INVALID_U32 {
+                self.colliders_proxy_ids.insert(handle, proxy_index);
+                co.bf_data.proxy_index = proxy_index;
+            }
+        }
+
+        if need_region_propagation {
+            self.propagate_created_regions();
+        }
+
+        self.update_layers_and_find_pairs(events);
+    }
+
+    /// Copies the part of this broad-phase modified by a simulation step into `state`.
+    ///
+    /// This reuses the buffers already allocated by `state`.
+    pub(crate) fn save_state(&self, state: &mut BroadPhaseState) {
+        state.proxies.clone_from(&self.proxies);
+        state.layers.clone_from(&self.layers);
+        state.smallest_layer = self.smallest_layer;
+        state.largest_layer = self.largest_layer;
+        state.reporting_capacity = self.reporting.capacity();
+    }
+
+    /// Restores, in-place, the state saved by [`Self::save_state`].
+    pub(crate) fn restore_state(&mut self, state: &BroadPhaseState) {
+        self.proxies.clone_from(&state.proxies);
+        self.layers.clone_from(&state.layers);
+        self.smallest_layer = state.smallest_layer;
+        self.largest_layer = state.largest_layer;
+
+        // NOTE: the capacity of this workspace affects the order pairs are reported in.
+        //       See the comment on the `reporting` field.
+        if self.reporting.capacity() != state.reporting_capacity {
+            self.reporting =
+                HashMap::with_capacity_and_hasher(state.reporting_capacity, Default::default());
+        }
+    }
+
+    /// Calls `f` on the colliders with a broad-phase Aabb intersecting `aabb`.
+    ///
+    /// The broad-phase Aabbs are enlarged by the prediction distance given to the last call to
+    /// [`Self::update`]. A collider can be reported more than once.
+    pub(crate) fn for_each_collider_intersecting_aabb(
+        &self,
+        aabb: &Aabb,
+        mut f: impl FnMut(ColliderHandle),
+    ) {
+        for layer in &self.layers {
+            layer.for_each_collider_intersecting_aabb(&self.proxies, aabb, &mut f);
+        }
+    }
+
     /// Propagate regions from the smallest layers up to the larger layers.
     ///
     /// Whenever a region is created on a layer `n`, then its Aabb must be
@@ -1,4 +1,5 @@
 pub use self::broad_phase::BroadPhase;
+pub(crate) use self::broad_phase::BroadPhaseState;
 pub use self::broad_phase_pair_event::{BroadPhasePairEvent, ColliderPair};
 pub use self::sap_proxy::SAPProxyIndex;
 
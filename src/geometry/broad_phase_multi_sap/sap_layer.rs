@@ -1,6 +1,6 @@
-use super::{SAPProxies, SAPProxy, SAPRegion, SAPRegionPool};
+use super::{SAPProxies, SAPProxy, SAPProxyData, SAPRegion, SAPRegionPool};
 use crate::geometry::broad_phase_multi_sap::DELETED_AABB_VALUE;
-use crate::geometry::{Aabb, SAPProxyIndex};
+use crate::geometry::{Aabb, ColliderHandle, SAPProxyIndex};
 use crate::math::{Point, Real};
 use parry::bounding_volume::BoundingVolume;
 use parry::utils::hashmap::{Entry, HashMap};
@@ -266,6 +266,76 @@ impl SAPLayer {
         }
     }
 
+    /// Calls `f` on the colliders of this layer with an Aabb intersecting `aabb`.
+    ///
+    /// A collider can be reported more than once if it lies in multiple regions.
+    pub fn for_each_collider_intersecting_aabb(
+        &self,
+        proxies: &SAPProxies,
+        aabb: &Aabb,
+        f: &mut impl FnMut(ColliderHandle),
+    ) {
+        let start = super::point_key(aabb.mins, self.region_width);
+        let end = super::point_key(aabb.maxs, self.region_width);
+        let num_keys = (end - start)
+            .iter()
+            .fold(1.0, |acc, e| acc * (*e as Real + 1.0));
+
+        let mut visit_region = |region_id: SAPProxyIndex| {
+            let region_proxy = &proxies[region_id];
+            if !region_proxy.aabb.intersects(aabb) {
+                return;
+            }
+
+            for endpoint in &region_proxy.data.as_region().axes[0].endpoints {
+                if endpoint.is_start() && !endpoint.is_sentinel() {
+                    let proxy = &proxies[endpoint.proxy()];
+                    if let SAPProxyData::Collider(handle) = proxy.data {
+                        if proxy.aabb.intersects(aabb) {
+                            f(handle)
+                        }
+                    }
+                }
+            }
+        };
+
+        if num_keys > self.regions.len() as Real {
+            // The Aabb covers more regions than there are, so it is faster to
+            // iterate through the existing ones.
+            for (key, region_id) in &self.regions {
+                if (start.coords.zip_map(&key.coords, |s, k| s <= k))
+                    .iter()
+                    .all(|b| *b)
+                    && (key.coords.zip_map(&end.coords, |k, e| k <= e))
+                        .iter()
+                        .all(|b| *b)
+                {
+                    visit_region(*region_id);
+                }
+            }
+        } else {
+            #[cfg(feature = "dim2")]
+            let k_range = 0..1;
+            #[cfg(feature = "dim3")]
+            let k_range = start.z..=end.z;
+
+            for i in start.x..=end.x {
+                for j in start.y..=end.y {
+                    for _k in k_range.clone() {
+                        #[cfg(feature = "dim2")]
+                        let region_key = Point::new(i, j);
+                        #[cfg(feature = "dim3")]
+                        let region_key = Point::new(i, j, _k);
+
+                        if let Some(region_id) = self.regions.get(&region_key) {
+                            visit_region(*region_id);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     pub fn predelete_proxy(&mut self, proxies: &mut SAPProxies, proxy_index: SAPProxyIndex) {
         // Discretize the Aabb to find the regions that need to be invalidated.
         let proxy_aabb = &mut proxies[proxy_index].aabb;
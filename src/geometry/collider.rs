@@ -1,8 +1,9 @@
 use crate::dynamics::{CoefficientCombineRule, MassProperties, RigidBodyHandle};
 use crate::geometry::{
-    ActiveCollisionTypes, ColliderBroadPhaseData, ColliderChanges, ColliderFlags,
-    ColliderMassProps, ColliderMaterial, ColliderParent, ColliderPosition, ColliderShape,
-    ColliderType, InteractionGroups, SharedShape,
+    ActiveCollisionTypes, AnisotropicFriction, ColliderBroadPhaseData, ColliderChanges,
+    ColliderFlags, ColliderMassProps, ColliderMaterial, ColliderParent, ColliderPosition,
+    ColliderShape, ColliderType, ContactSoftness, FeatureMaterials, FluidVolume, InteractionGroups,
+    MaterialId, SharedShape,
 };
 use crate::math::{AngVector, Isometry, Point, Real, Rotation, Vector, DIM};
 use crate::parry::transformation::vhacd::VHACDParameters;
@@ -28,6 +29,10 @@ pub struct Collider {
     pub(crate) flags: ColliderFlags,
     pub(crate) bf_data: ColliderBroadPhaseData,
     contact_force_event_threshold: Real,
+    min_impact_velocity: Real,
+    sensor_ccd_enabled: bool,
+    pub(crate) fluid_volume: Option<FluidVolume>,
+    pub(crate) feature_materials: Option<Box<FeatureMaterials>>,
     /// User-defined data associated to this collider.
     pub user_data: u128,
 }
@@ -50,6 +55,18 @@ impl Collider {
         }
     }
 
+    pub(crate) fn effective_min_impact_velocity(&self) -> Real {
+        if self
+            .flags
+            .active_events
+            .contains(ActiveEvents::CONTACT_IMPACT_EVENTS)
+        {
+            self.min_impact_velocity
+        } else {
+            Real::MAX
+        }
+    }
+
     /// The rigid body this collider is attached to.
     pub fn parent(&self) -> Option<RigidBodyHandle> {
         self.parent.map(|parent| parent.handle)
@@ -100,6 +117,18 @@ impl Collider {
         self.material.friction = coefficient
     }
 
+    /// The anisotropic friction of this collider, if any.
+    pub fn anisotropic_friction(&self) -> Option<AnisotropicFriction> {
+        self.material.anisotropic_friction
+    }
+
+    /// Sets the anisotropic friction of this collider.
+    ///
+    /// Set it to `None` to use the isotropic friction coefficient given by [`Self::friction`].
+    pub fn set_anisotropic_friction(&mut self, anisotropic_friction: Option<AnisotropicFriction>) {
+        self.material.anisotropic_friction = anisotropic_friction;
+    }
+
     /// The combine rule used by this collider to combine its friction
     /// coefficient with the friction coefficient of the other collider it
     /// is in contact with.
@@ -138,11 +167,126 @@ impl Collider {
         self.material.restitution_combine_rule = rule;
     }
 
+    /// The rolling friction coefficient of this collider.
+    pub fn rolling_friction(&self) -> Real {
+        self.material.rolling_friction
+    }
+
+    /// Sets the rolling friction coefficient of this collider.
+    pub fn set_rolling_friction(&mut self, coefficient: Real) {
+        self.material.rolling_friction = coefficient
+    }
+
+    /// The combine rule used by this collider to combine its rolling friction
+    /// coefficient with the rolling friction coefficient of the other collider it
+    /// is in contact with.
+    pub fn rolling_friction_combine_rule(&self) -> CoefficientCombineRule {
+        self.material.rolling_friction_combine_rule
+    }
+
+    /// Sets the combine rule used by this collider to combine its rolling friction
+    /// coefficient with the rolling friction coefficient of the other collider it
+    /// is in contact with.
+    pub fn set_rolling_friction_combine_rule(&mut self, rule: CoefficientCombineRule) {
+        self.material.rolling_friction_combine_rule = rule;
+    }
+
+    /// The spinning friction coefficient of this collider.
+    pub fn spinning_friction(&self) -> Real {
+        self.material.spinning_friction
+    }
+
+    /// Sets the spinning friction coefficient of this collider.
+    pub fn set_spinning_friction(&mut self, coefficient: Real) {
+        self.material.spinning_friction = coefficient
+    }
+
+    /// The combine rule used by this collider to combine its spinning friction
+    /// coefficient with the spinning friction coefficient of the other collider it
+    /// is in contact with.
+    pub fn spinning_friction_combine_rule(&self) -> CoefficientCombineRule {
+        self.material.spinning_friction_combine_rule
+    }
+
+    /// Sets the combine rule used by this collider to combine its spinning friction
+    /// coefficient with the spinning friction coefficient of the other collider it
+    /// is in contact with.
+    pub fn set_spinning_friction_combine_rule(&mut self, rule: CoefficientCombineRule) {
+        self.material.spinning_friction_combine_rule = rule;
+    }
+
+    /// The softness of the contacts involving this collider, if it overrides the global one.
+    pub fn softness(&self) -> Option<ContactSoftness> {
+        self.material.softness
+    }
+
+    /// Sets the softness of the contacts involving this collider.
+    ///
+    /// Set it to `None` to use the global softness given by the `IntegrationParameters`.
+    pub fn set_softness(&mut self, softness: Option<ContactSoftness>) {
+        self.material.softness = softness;
+    }
+
+    /// The combine rule used by this collider to combine its softness
+    /// with the softness of the other collider it is in contact with.
+    pub fn softness_combine_rule(&self) -> CoefficientCombineRule {
+        self.material.softness_combine_rule
+    }
+
+    /// Sets the combine rule used by this collider to combine its softness
+    /// with the softness of the other collider it is in contact with.
+    pub fn set_softness_combine_rule(&mut self, rule: CoefficientCombineRule) {
+        self.material.softness_combine_rule = rule;
+    }
+
+    /// The identifier of the material of this collider, used to look up the properties of its
+    /// contacts in the `MaterialPairTable` of the narrow-phase.
+    pub fn material_id(&self) -> Option<MaterialId> {
+        self.material.material_id
+    }
+
+    /// Sets the identifier of the material of this collider.
+    pub fn set_material_id(&mut self, material_id: Option<MaterialId>) {
+        self.material.material_id = material_id;
+    }
+
     /// Sets the total force magnitude beyond which a contact force event can be emitted.
     pub fn set_contact_force_event_threshold(&mut self, threshold: Real) {
         self.contact_force_event_threshold = threshold;
     }
 
+    /// Sets the relative normal velocity beyond which a contact impact event can be emitted.
+    ///
+    /// Should be `>= 0`: negative or NaN values are replaced by zero.
+    pub fn set_min_impact_velocity(&mut self, min_impact_velocity: Real) {
+        self.min_impact_velocity = min_impact_velocity.max(0.0);
+    }
+
+    /// Sets the fluid filling this collider.
+    ///
+    /// The fluid only applies buoyancy and drag forces if this collider is a sensor.
+    pub fn set_fluid_volume(&mut self, fluid_volume: Option<FluidVolume>) {
+        self.fluid_volume = fluid_volume;
+    }
+
+    /// Sets the materials of the triangles or heightfield cells of this collider.
+    ///
+    /// The contacts with a feature assigned to a material use this material instead of the
+    /// collider’s material.
+    pub fn set_feature_materials(&mut self, feature_materials: Option<FeatureMaterials>) {
+        self.feature_materials = feature_materials.map(Box::new);
+    }
+
+    /// Enables or disables the swept detection of the rigid-bodies crossing this collider if it
+    /// is a sensor.
+    ///
+    /// If enabled, a collider attached to a fast rigid-body passing through this sensor during a
+    /// single timestep triggers a sensor pass-through event, even if that rigid-body doesn’t
+    /// have CCD enabled.
+    pub fn set_sensor_ccd_enabled(&mut self, enabled: bool) {
+        self.sensor_ccd_enabled = enabled;
+    }
+
     /// Sets whether or not this is a sensor collider.
     pub fn set_sensor(&mut self, is_sensor: bool) {
         if is_sensor != self.is_sensor() {
@@ -394,6 +538,26 @@ impl Collider {
     pub fn contact_force_event_threshold(&self) -> Real {
         self.contact_force_event_threshold
     }
+
+    /// The relative normal velocity beyond which a contact impact event can be emitted.
+    pub fn min_impact_velocity(&self) -> Real {
+        self.min_impact_velocity
+    }
+
+    /// Is the swept detection of the rigid-bodies crossing this sensor enabled?
+    pub fn is_sensor_ccd_enabled(&self) -> bool {
+        self.sensor_ccd_enabled
+    }
+
+    /// The fluid filling this collider, if any.
+    pub fn fluid_volume(&self) -> Option<&FluidVolume> {
+        self.fluid_volume.as_ref()
+    }
+
+    /// The materials of the triangles or heightfield cells of this collider, if any.
+    pub fn feature_materials(&self) -> Option<&FeatureMaterials> {
+        self.feature_materials.as_deref()
+    }
 }
 
 /// A structure responsible for building a new collider.
@@ -407,16 +571,34 @@ pub struct ColliderBuilder {
     pub mass_properties: ColliderMassProps,
     /// The friction coefficient of the collider to be built.
     pub friction: Real,
+    /// The anisotropic friction of the collider to be built.
+    pub anisotropic_friction: Option<AnisotropicFriction>,
     /// The rule used to combine two friction coefficients.
     pub friction_combine_rule: CoefficientCombineRule,
     /// The restitution coefficient of the collider to be built.
     pub restitution: Real,
     /// The rule used to combine two restitution coefficients.
     pub restitution_combine_rule: CoefficientCombineRule,
+    /// The rolling friction coefficient of the collider to be built.
+    pub rolling_friction: Real,
+    /// The rule used to combine two rolling friction coefficients.
+    pub rolling_friction_combine_rule: CoefficientCombineRule,
+    /// The spinning friction coefficient of the collider to be built.
+    pub spinning_friction: Real,
+    /// The rule used to combine two spinning friction coefficients.
+    pub spinning_friction_combine_rule: CoefficientCombineRule,
+    /// The softness of the contacts involving the collider to be built.
+    pub softness: Option<ContactSoftness>,
+    /// The rule used to combine two contact softnesses.
+    pub softness_combine_rule: CoefficientCombineRule,
+    /// The identifier of the material of the collider to be built.
+    pub material_id: Option<MaterialId>,
     /// The position of this collider.
     pub position: Isometry<Real>,
     /// Is this collider a sensor?
     pub is_sensor: bool,
+    /// Is the swept detection of the rigid-bodies crossing the sensor being built enabled?
+    pub sensor_ccd_enabled: bool,
     /// Contact pairs enabled for this collider.
     pub active_collision_types: ActiveCollisionTypes,
     /// Physics hooks enabled for this collider.
@@ -433,6 +615,14 @@ pub struct ColliderBuilder {
     pub enabled: bool,
     /// The total force magnitude beyond which a contact force event can be emitted.
     pub contact_force_event_threshold: Real,
+    /// The relative normal velocity beyond which a contact impact event can be emitted.
+    ///
+    /// Should be `>= 0`: negative or NaN values are replaced by zero.
+    pub min_impact_velocity: Real,
+    /// The fluid filling the collider being built.
+    pub fluid_volume: Option<FluidVolume>,
+    /// The materials of the triangles or heightfield cells of the collider being built.
+    pub feature_materials: Option<FeatureMaterials>,
 }
 
 impl ColliderBuilder {
@@ -442,19 +632,31 @@ impl ColliderBuilder {
             shape,
             mass_properties: ColliderMassProps::default(),
             friction: Self::default_friction(),
+            anisotropic_friction: None,
             restitution: 0.0,
             position: Isometry::identity(),
             is_sensor: false,
+            sensor_ccd_enabled: false,
             user_data: 0,
             collision_groups: InteractionGroups::all(),
             solver_groups: InteractionGroups::all(),
             friction_combine_rule: CoefficientCombineRule::Average,
             restitution_combine_rule: CoefficientCombineRule::Average,
+            rolling_friction: 0.0,
+            rolling_friction_combine_rule: CoefficientCombineRule::Average,
+            spinning_friction: 0.0,
+            spinning_friction_combine_rule: CoefficientCombineRule::Average,
+            softness: None,
+            softness_combine_rule: CoefficientCombineRule::Average,
+            material_id: None,
             active_collision_types: ActiveCollisionTypes::default(),
             active_hooks: ActiveHooks::empty(),
             active_events: ActiveEvents::empty(),
             enabled: true,
             contact_force_event_threshold: 0.0,
+            min_impact_velocity: 0.0,
+            fluid_volume: None,
+            feature_materials: None,
         }
     }
 
@@ -741,6 +943,14 @@ impl ColliderBuilder {
         self
     }
 
+    /// Enables or disables the swept detection of the rigid-bodies crossing the sensor being built.
+    ///
+    /// See [`Collider::set_sensor_ccd_enabled`] for details.
+    pub fn sensor_ccd_enabled(mut self, enabled: bool) -> Self {
+        self.sensor_ccd_enabled = enabled;
+        self
+    }
+
     /// The set of physics hooks enabled for this collider.
     pub fn active_hooks(mut self, active_hooks: ActiveHooks) -> Self {
         self.active_hooks = active_hooks;
@@ -765,6 +975,14 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets the anisotropic friction of the collider this builder will build.
+    ///
+    /// This replaces the isotropic friction coefficient set with [`Self::friction`].
+    pub fn anisotropic_friction(mut self, anisotropic_friction: AnisotropicFriction) -> Self {
+        self.anisotropic_friction = Some(anisotropic_friction);
+        self
+    }
+
     /// Sets the rule to be used to combine two friction coefficients in a contact.
     pub fn friction_combine_rule(mut self, rule: CoefficientCombineRule) -> Self {
         self.friction_combine_rule = rule;
@@ -783,6 +1001,48 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets the rolling friction coefficient of the collider this builder will build.
+    pub fn rolling_friction(mut self, rolling_friction: Real) -> Self {
+        self.rolling_friction = rolling_friction;
+        self
+    }
+
+    /// Sets the rule to be used to combine two rolling friction coefficients in a contact.
+    pub fn rolling_friction_combine_rule(mut self, rule: CoefficientCombineRule) -> Self {
+        self.rolling_friction_combine_rule = rule;
+        self
+    }
+
+    /// Sets the spinning friction coefficient of the collider this builder will build.
+    pub fn spinning_friction(mut self, spinning_friction: Real) -> Self {
+        self.spinning_friction = spinning_friction;
+        self
+    }
+
+    /// Sets the rule to be used to combine two spinning friction coefficients in a contact.
+    pub fn spinning_friction_combine_rule(mut self, rule: CoefficientCombineRule) -> Self {
+        self.spinning_friction_combine_rule = rule;
+        self
+    }
+
+    /// Sets the softness of the contacts involving the collider this builder will build.
+    pub fn softness(mut self, softness: ContactSoftness) -> Self {
+        self.softness = Some(softness);
+        self
+    }
+
+    /// Sets the rule to be used to combine two contact softnesses.
+    pub fn softness_combine_rule(mut self, rule: CoefficientCombineRule) -> Self {
+        self.softness_combine_rule = rule;
+        self
+    }
+
+    /// Sets the identifier of the material of the collider this builder will build.
+    pub fn material_id(mut self, material_id: MaterialId) -> Self {
+        self.material_id = Some(material_id);
+        self
+    }
+
     /// Sets the uniform density of the collider this builder will build.
     ///
     /// This will be overridden by a call to [`Self::mass`] or [`Self::mass_properties`] so it only
@@ -822,6 +1082,32 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets the relative normal velocity beyond which a contact impact event can be emitted.
+    ///
+    /// Should be `>= 0`: negative or NaN values are replaced by zero.
+    pub fn min_impact_velocity(mut self, min_impact_velocity: Real) -> Self {
+        self.min_impact_velocity = min_impact_velocity.max(0.0);
+        self
+    }
+
+    /// Fills the collider to be built with a fluid.
+    ///
+    /// The fluid applies buoyancy and drag forces to the dynamic bodies intersecting the
+    /// collider. This only has an effect if the collider is a sensor.
+    pub fn fluid_volume(mut self, fluid_volume: FluidVolume) -> Self {
+        self.fluid_volume = Some(fluid_volume);
+        self
+    }
+
+    /// Sets the materials of the triangles or heightfield cells of the collider to be built.
+    ///
+    /// The contacts with a feature assigned to a material use this material instead of the
+    /// collider’s material.
+    pub fn feature_materials(mut self, feature_materials: FeatureMaterials) -> Self {
+        self.feature_materials = Some(feature_materials);
+        self
+    }
+
     /// Sets the initial translation of the collider to be created.
     ///
     /// If the collider will be attached to a rigid-body, this sets the translation relative to the
@@ -875,9 +1161,17 @@ impl ColliderBuilder {
         let shape = self.shape.clone();
         let material = ColliderMaterial {
             friction: self.friction,
+            anisotropic_friction: self.anisotropic_friction,
             restitution: self.restitution,
             friction_combine_rule: self.friction_combine_rule,
             restitution_combine_rule: self.restitution_combine_rule,
+            rolling_friction: self.rolling_friction,
+            spinning_friction: self.spinning_friction,
+            rolling_friction_combine_rule: self.rolling_friction_combine_rule,
+            spinning_friction_combine_rule: self.spinning_friction_combine_rule,
+            softness: self.softness,
+            softness_combine_rule: self.softness_combine_rule,
+            material_id: self.material_id,
         };
         let flags = ColliderFlags {
             collision_groups: self.collision_groups,
@@ -911,6 +1205,10 @@ impl ColliderBuilder {
             flags,
             coll_type,
             contact_force_event_threshold: self.contact_force_event_threshold,
+            min_impact_velocity: self.min_impact_velocity.max(0.0),
+            sensor_ccd_enabled: self.sensor_ccd_enabled,
+            fluid_volume: self.fluid_volume,
+            feature_materials: self.feature_materials.clone().map(Box::new),
             user_data: self.user_data,
         }
     }
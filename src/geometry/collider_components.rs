@@ -1,8 +1,9 @@
 use crate::dynamics::{CoefficientCombineRule, MassProperties, RigidBodyHandle, RigidBodyType};
-use crate::geometry::{InteractionGroups, SAPProxyIndex, Shape, SharedShape};
-use crate::math::{Isometry, Real};
+use crate::geometry::{InteractionGroups, MaterialId, SAPProxyIndex, Shape, SharedShape};
+use crate::math::{Isometry, Real, Vector};
 use crate::parry::partitioning::IndexedData;
 use crate::pipeline::{ActiveEvents, ActiveHooks};
+use na::{RealField, Unit};
 use std::ops::{Deref, DerefMut};
 
 /// The unique identifier of a collider added to a collider set.
@@ -262,6 +263,11 @@ pub struct ColliderMaterial {
     /// The greater the value, the stronger the friction forces will be.
     /// Should be `>= 0`.
     pub friction: Real,
+    /// The anisotropic friction of this collider.
+    ///
+    /// If set, it replaces `friction` with coefficients depending on the direction of the
+    /// friction force.
+    pub anisotropic_friction: Option<AnisotropicFriction>,
     /// The restitution coefficient of this collider.
     ///
     /// Increase this value to make contacts with this collider more "bouncy".
@@ -272,6 +278,35 @@ pub struct ColliderMaterial {
     pub friction_combine_rule: CoefficientCombineRule,
     /// The rule applied to combine the restitution coefficients of two colliders.
     pub restitution_combine_rule: CoefficientCombineRule,
+    /// The rolling friction coefficient of this collider.
+    ///
+    /// This is a length: the torque resisting the relative rolling of two colliders in contact is
+    /// limited by this coefficient multiplied by their normal contact force. Should be `>= 0`.
+    pub rolling_friction: Real,
+    /// The spinning friction coefficient of this collider.
+    ///
+    /// This is a length: the torque resisting the relative rotation of two colliders in contact
+    /// around their contact normal is limited by this coefficient multiplied by their normal
+    /// contact force. Should be `>= 0`. This has no effect in 2D.
+    pub spinning_friction: Real,
+    /// The rule applied to combine the rolling friction coefficients of two colliders.
+    pub rolling_friction_combine_rule: CoefficientCombineRule,
+    /// The rule applied to combine the spinning friction coefficients of two colliders.
+    pub spinning_friction_combine_rule: CoefficientCombineRule,
+    /// The softness of the contacts involving this collider.
+    ///
+    /// If `None`, the contacts involving this collider use the softness of the other collider
+    /// in contact, or the global `erp` and `damping_ratio` of the `IntegrationParameters` if
+    /// none of them has a softness.
+    pub softness: Option<ContactSoftness>,
+    /// The rule applied to combine the softness of two colliders.
+    pub softness_combine_rule: CoefficientCombineRule,
+    /// The identifier of the material of this collider.
+    ///
+    /// If the pair of materials of two colliders in contact is listed in the `MaterialPairTable`
+    /// of the narrow-phase, the friction and restitution of their contacts are read from this
+    /// table instead of being combined with the combine rules.
+    pub material_id: Option<MaterialId>,
 }
 
 impl ColliderMaterial {
@@ -283,19 +318,186 @@ impl ColliderMaterial {
             ..Default::default()
         }
     }
+
+    /// The friction coefficient of this material along the world-space unit direction `dir`,
+    /// for a collider with the given position.
+    pub(crate) fn friction_along(&self, position: &Isometry<Real>, dir: &Vector<Real>) -> Real {
+        match &self.anisotropic_friction {
+            Some(anisotropic) => {
+                let cos = (position * *anisotropic.local_direction).dot(dir);
+                let sin2 = (1.0 - cos * cos).max(0.0);
+                (anisotropic.primary_friction.powi(2) * cos * cos
+                    + anisotropic.secondary_friction.powi(2) * sin2)
+                    .sqrt()
+            }
+            None => self.friction,
+        }
+    }
+}
+
+/// Friction coefficients depending on the direction of the friction force.
+///
+/// The friction coefficient is `primary_friction` along the primary direction, `secondary_friction`
+/// along any direction orthogonal to it, and is interpolated elliptically in-between.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct AnisotropicFriction {
+    /// The primary friction direction, in the collider’s local-space.
+    pub local_direction: Unit<Vector<Real>>,
+    /// The friction coefficient along the primary direction.
+    pub primary_friction: Real,
+    /// The friction coefficient along the directions orthogonal to the primary direction.
+    pub secondary_friction: Real,
+}
+
+impl AnisotropicFriction {
+    /// Creates anisotropic friction coefficients with the given primary direction (in the
+    /// collider’s local-space) and coefficients.
+    pub fn new(
+        local_direction: Unit<Vector<Real>>,
+        primary_friction: Real,
+        secondary_friction: Real,
+    ) -> Self {
+        Self {
+            local_direction,
+            primary_friction,
+            secondary_friction,
+        }
+    }
 }
 
 impl Default for ColliderMaterial {
     fn default() -> Self {
         Self {
             friction: 1.0,
+            anisotropic_friction: None,
             restitution: 0.0,
             friction_combine_rule: CoefficientCombineRule::default(),
             restitution_combine_rule: CoefficientCombineRule::default(),
+            rolling_friction: 0.0,
+            spinning_friction: 0.0,
+            rolling_friction_combine_rule: CoefficientCombineRule::default(),
+            spinning_friction_combine_rule: CoefficientCombineRule::default(),
+            softness: None,
+            softness_combine_rule: CoefficientCombineRule::default(),
+            material_id: None,
         }
     }
 }
 
+/// Materials assigned to the triangles of a triangle mesh, or to the cells of a heightfield.
+///
+/// The contacts with a feature assigned to a material use this material instead of the material
+/// of the collider.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct FeatureMaterials {
+    /// The materials that can be assigned to the features.
+    pub materials: Vec<ColliderMaterial>,
+    /// The index, in `materials`, of the material of each feature.
+    ///
+    /// For heightfields, this is indexed by cell: the cell at the row `i` and column `j` has
+    /// the index `i + j * nrows` in 3D, and `i` in 2D. For other shapes, this is indexed by
+    /// sub-shape, e.g., by triangle for triangle meshes. The features without a valid index use
+    /// the collider’s material.
+    pub indices: Vec<u32>,
+}
+
+impl FeatureMaterials {
+    /// Assigns the given materials to the features of a collider.
+    pub fn new(materials: Vec<ColliderMaterial>, indices: Vec<u32>) -> Self {
+        Self { materials, indices }
+    }
+
+    /// The index, in `materials`, of the material of the given sub-shape of `shape`.
+    pub fn material_index(&self, shape: &dyn Shape, subshape: u32) -> Option<u32> {
+        // The triangles of a heightfield are numbered from the cells, for each half of the cells.
+        #[cfg(feature = "dim3")]
+        let subshape = match shape.as_heightfield() {
+            Some(heightfield) => subshape % (heightfield.nrows() * heightfield.ncols()) as u32,
+            None => subshape,
+        };
+        #[cfg(feature = "dim2")]
+        let _ = shape;
+
+        let index = *self.indices.get(subshape as usize)?;
+        (index < self.materials.len() as u32).then_some(index)
+    }
+}
+
+/// The compliance of a contact, modeled as a damped spring along the contact normal.
+///
+/// The spring is described by its natural frequency and damping ratio, instead of its stiffness
+/// and damping, so that the same softness behaves the same way independently from the masses
+/// of the bodies in contact.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct ContactSoftness {
+    /// The natural frequency of the contact spring, in Hertz.
+    ///
+    /// The smaller the value, the softer the contact. Very large values make the contact
+    /// as rigid as the timestep length allows.
+    pub natural_frequency: Real,
+    /// The damping ratio of the contact spring.
+    ///
+    /// A value of `1.0` makes the spring critically damped. Smaller values make the contact
+    /// bouncier, and greater values make it absorb more energy.
+    pub damping_ratio: Real,
+}
+
+impl ContactSoftness {
+    /// Creates a new contact softness with the given natural frequency (in Hertz) and damping ratio.
+    pub fn new(natural_frequency: Real, damping_ratio: Real) -> Self {
+        Self {
+            natural_frequency,
+            damping_ratio,
+        }
+    }
+
+    /// Combines the softness of two colliders in contact.
+    ///
+    /// If only one of them has a softness, it is used as-is.
+    pub(crate) fn combine(
+        material1: &ColliderMaterial,
+        material2: &ColliderMaterial,
+    ) -> Option<ContactSoftness> {
+        match (material1.softness, material2.softness) {
+            (Some(softness1), Some(softness2)) => {
+                let rule1 = material1.softness_combine_rule as u8;
+                let rule2 = material2.softness_combine_rule as u8;
+                Some(ContactSoftness {
+                    natural_frequency: CoefficientCombineRule::combine(
+                        softness1.natural_frequency,
+                        softness2.natural_frequency,
+                        rule1,
+                        rule2,
+                    ),
+                    damping_ratio: CoefficientCombineRule::combine(
+                        softness1.damping_ratio,
+                        softness2.damping_ratio,
+                        rule1,
+                        rule2,
+                    ),
+                })
+            }
+            (softness1, softness2) => softness1.or(softness2),
+        }
+    }
+
+    /// The ERP coefficient multiplied by the inverse timestep length, and the CFM factor,
+    /// to be used in the constraints resolution for a timestep of length `dt`.
+    ///
+    /// See `IntegrationParameters::cfm_factor` for details on the CFM factor.
+    pub(crate) fn erp_inv_dt_and_cfm_factor(&self, dt: Real) -> (Real, Real) {
+        let omega = Real::two_pi() * self.natural_frequency;
+        let a1 = 2.0 * self.damping_ratio + dt * omega;
+        let a2 = dt * omega * a1;
+        let erp_inv_dt = omega * crate::utils::inv(a1);
+        let cfm_factor = a2 / (1.0 + a2);
+        (erp_inv_dt, cfm_factor)
+    }
+}
+
 bitflags::bitflags! {
     #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
     /// Flags affecting whether or not collision-detection happens between two colliders
@@ -438,3 +640,281 @@ impl From<ActiveEvents> for ColliderFlags {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{AnisotropicFriction, ColliderMaterial, ContactSoftness, FeatureMaterials};
+    use crate::dynamics::{CoefficientCombineRule, FixedJointBuilder, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_utils;
+    use crate::pipeline::{ActiveEvents, PhysicsWorld};
+    use na::RealField;
+
+    #[test]
+    fn soft_contacts_penetrate_deeper() {
+        let mut world = test_utils::world();
+        let ground = test_utils::cuboid(10.0, 1.0);
+        world.insert_collider(ground.translation(-Vector::y()));
+
+        // Two balls resting on the ground, one of them with a soft contact.
+        let mut ball = |x: Real, softness: Option<ContactSoftness>| {
+            let body = world.insert_rigid_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * x + Vector::y() * 0.5)
+                    .can_sleep(false),
+            );
+            let mut collider = ColliderBuilder::ball(0.5);
+            collider.softness = softness;
+            world.insert_collider_with_parent(collider, body);
+            body
+        };
+        let rigid = ball(-2.0, None);
+        let soft = ball(2.0, Some(ContactSoftness::new(1.0, 1.0)));
+
+        for _ in 0..600 {
+            world.step(&(), &());
+        }
+
+        // A spring with a natural frequency `f` sags by `g / (2 * pi * f)²` under gravity.
+        let sag = 10.0 / Real::two_pi().powi(2);
+        let rigid_depth = 0.5 - world.bodies[rigid].translation().y;
+        let soft_depth = 0.5 - world.bodies[soft].translation().y;
+        assert!(rigid_depth < 1.0e-2, "{}", rigid_depth);
+        assert!((soft_depth - sag).abs() < 0.05 * sag, "{}", soft_depth);
+    }
+
+    #[test]
+    fn rolling_and_spinning_friction_stop_balls() {
+        let mut world = test_utils::world();
+        let ground = test_utils::cuboid(100.0, 1.0);
+        world.insert_collider(ground.translation(-Vector::y()));
+
+        // Balls rolling on the ground, with and without rolling friction.
+        let ball =
+            |world: &mut PhysicsWorld, x: Real, rolling_friction: Real, spinning_friction: Real| {
+                let body = world.insert_rigid_body(
+                    RigidBodyBuilder::dynamic()
+                        .translation(Vector::x() * x + Vector::y() * 0.5)
+                        .linvel(Vector::x() * 2.0),
+                );
+                let collider = ColliderBuilder::ball(0.5)
+                    .rolling_friction(rolling_friction)
+                    .spinning_friction(spinning_friction);
+                world.insert_collider_with_parent(collider, body);
+                body
+            };
+        let rolling = ball(&mut world, 0.0, 0.0, 0.0);
+        let stopped = ball(&mut world, 10.0, 0.05, 0.0);
+
+        // The same balls, as the roots of multibodies.
+        let mut links = [(-10.0, 0.0), (-20.0, 0.05)].map(|(x, rolling_friction)| {
+            let root = ball(&mut world, x, rolling_friction, 0.0);
+            let child = world.insert_rigid_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * x + Vector::y() * 0.5)
+                    .additional_mass(0.1),
+            );
+            let joint = world.insert_multibody_joint(root, child, FixedJointBuilder::new(), true);
+            (root, joint.unwrap())
+        });
+        // The root velocities are only taken from the multibody degrees of freedom.
+        world.step(&(), &());
+        for (_, joint) in &mut links {
+            let multibody = world.multibody_joints.get_mut(*joint).unwrap().0;
+            multibody.generalized_velocity_mut()[0] = 2.0;
+        }
+
+        // Balls spinning around the contact normal, with and without spinning friction.
+        #[cfg(feature = "dim3")]
+        let (spinning, stopped_spinning) = {
+            let spinning = ball(&mut world, 20.0, 0.0, 0.0);
+            let stopped_spinning = ball(&mut world, 30.0, 0.0, 0.05);
+            for handle in [spinning, stopped_spinning] {
+                world.bodies[handle].set_linvel(Vector::zeros(), true);
+                world.bodies[handle].set_angvel(Vector::y() * 10.0, true);
+            }
+            (spinning, stopped_spinning)
+        };
+
+        for _ in 0..150 {
+            world.step(&(), &());
+        }
+
+        // NOTE: the multibody ball without rolling friction slows down too, so we check them
+        //       before it gets too slow.
+        assert!(world.bodies[links[0].0].linvel().x > 0.3);
+        assert!(world.bodies[links[1].0].linvel().norm() < 1.0e-2);
+
+        for _ in 0..150 {
+            world.step(&(), &());
+        }
+
+        assert!(world.bodies[rolling].linvel().x > 1.0);
+        assert!(world.bodies[stopped].linvel().norm() < 1.0e-2);
+
+        #[cfg(feature = "dim3")]
+        {
+            assert!(world.bodies[spinning].angvel().y > 9.0);
+            assert!(world.bodies[stopped_spinning].angvel().norm() < 1.0e-2);
+        }
+    }
+
+    #[test]
+    fn anisotropic_friction_depends_on_the_sliding_direction() {
+        let mut world = test_utils::world();
+        let ground = test_utils::cuboid(100.0, 1.0);
+        // The primary direction of the ground is along the contact normal, so its friction is
+        // isotropic on the tangent plane, but the anisotropic friction of the boxes still applies.
+        world.insert_collider(
+            ground
+                .translation(-Vector::y())
+                .anisotropic_friction(AnisotropicFriction::new(Vector::y_axis(), 0.0, 1.0))
+                .friction_combine_rule(CoefficientCombineRule::Multiply),
+        );
+
+        // Boxes sliding along the x axis, with isotropic or anisotropic friction.
+        let mut sliding_box =
+            |x: Real, friction: Real, anisotropic: Option<AnisotropicFriction>| {
+                let body = world.insert_rigid_body(
+                    RigidBodyBuilder::dynamic()
+                        .translation(Vector::x() * x + Vector::y() * 0.5)
+                        .linvel(Vector::x() * 5.0),
+                );
+                let mut collider = test_utils::cuboid(0.5, 0.5).friction(friction);
+                collider.anisotropic_friction = anisotropic;
+                world.insert_collider_with_parent(collider, body);
+                body
+            };
+        let low_friction = sliding_box(0.0, 0.1, None);
+        let along = sliding_box(
+            10.0,
+            0.0,
+            Some(AnisotropicFriction::new(Vector::x_axis(), 0.1, 1.0)),
+        );
+        #[cfg(feature = "dim2")]
+        let primary_direction = Vector::y_axis();
+        #[cfg(feature = "dim3")]
+        let primary_direction = Vector::z_axis();
+        let across = sliding_box(
+            20.0,
+            0.0,
+            Some(AnisotropicFriction::new(primary_direction, 0.1, 1.0)),
+        );
+
+        for _ in 0..60 {
+            world.step(&(), &());
+        }
+
+        // Sliding along the primary direction is as slippery as an isotropic friction of `0.1`,
+        // while sliding across it stops the box.
+        let expected = world.bodies[low_friction].linvel();
+        let along_vel = world.bodies[along].linvel();
+        assert!(expected.x > 3.9);
+        assert!((along_vel - expected).norm() < 1.0e-3, "{}", along_vel);
+        assert!(world.bodies[across].linvel().norm() < 5.0e-2);
+    }
+
+    #[test]
+    fn heightfield_cells_with_different_materials() {
+        let mut world = test_utils::world();
+
+        // A terrain made of four cells along the x axis, with ice on the two first ones.
+        let ice = ColliderMaterial {
+            friction: 0.0,
+            friction_combine_rule: CoefficientCombineRule::Min,
+            ..ColliderMaterial::default()
+        };
+        let rock = ColliderMaterial {
+            friction: 1.0,
+            friction_combine_rule: CoefficientCombineRule::Max,
+            ..ColliderMaterial::default()
+        };
+        #[cfg(feature = "dim2")]
+        let (terrain, indices) = (
+            ColliderBuilder::heightfield(na::DVector::zeros(5), Vector::new(40.0, 1.0)),
+            vec![0, 0, 1, 1],
+        );
+        #[cfg(feature = "dim3")]
+        let (terrain, indices) = (
+            ColliderBuilder::heightfield(na::DMatrix::zeros(3, 5), Vector::new(40.0, 1.0, 40.0)),
+            vec![0, 0, 0, 0, 1, 1, 1, 1],
+        );
+        let terrain = world.insert_collider(
+            terrain
+                .feature_materials(FeatureMaterials::new(vec![ice, rock], indices))
+                .active_events(
+                    ActiveEvents::CONTACT_IMPACT_EVENTS
+                        | ActiveEvents::CONTACT_FORCE_EVENTS
+                        | ActiveEvents::CONTACT_PERSISTED_EVENTS,
+                )
+                .contact_force_event_threshold(0.0),
+        );
+
+        // Boxes falling on the ice and on the rock, while sliding along the x axis.
+        let mut sliding_box = |x: Real| {
+            let body = world.insert_rigid_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * x + Vector::y() * 1.5)
+                    .linvel(Vector::x() * 2.0),
+            );
+            let collider = test_utils::cuboid(0.5, 0.5);
+            (body, world.insert_collider_with_parent(collider, body))
+        };
+        let (on_ice, on_ice_collider) = sliding_box(-17.0);
+        let (on_rock, _) = sliding_box(13.0);
+
+        let events = test_utils::EventChannels::new();
+
+        for _ in 0..120 {
+            world.step(&(), &events.collector);
+        }
+
+        // The contact events report the material of the contacted cells.
+        let check_material = |collider1, collider2, material1, material2| {
+            let (terrain_material, other) = if collider1 == terrain {
+                (material1, collider2)
+            } else {
+                (material2, collider1)
+            };
+            let expected = if other == on_ice_collider { 0 } else { 1 };
+            assert_eq!(terrain_material, Some(expected));
+        };
+
+        let impacts: Vec<_> = events.impacts.try_iter().collect();
+        assert!(!impacts.is_empty());
+        for e in impacts {
+            check_material(
+                e.collider1,
+                e.collider2,
+                e.feature_material1,
+                e.feature_material2,
+            );
+        }
+
+        let forces: Vec<_> = events.forces.try_iter().collect();
+        assert!(!forces.is_empty());
+        for e in forces {
+            check_material(
+                e.collider1,
+                e.collider2,
+                e.feature_material1,
+                e.feature_material2,
+            );
+        }
+
+        let persisted: Vec<_> = events.persisted.try_iter().collect();
+        assert!(!persisted.is_empty());
+        for e in persisted {
+            check_material(
+                e.collider1,
+                e.collider2,
+                e.feature_material1,
+                e.feature_material2,
+            );
+        }
+
+        assert!((world.bodies[on_ice].linvel().x - 2.0).abs() < 1.0e-3);
+        assert!(world.bodies[on_rock].linvel().x.abs() < 1.0e-2);
+    }
+}
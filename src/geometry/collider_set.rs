@@ -1,7 +1,8 @@
 use crate::data::arena::Arena;
 use crate::dynamics::{IslandManager, RigidBodyHandle, RigidBodySet};
 use crate::geometry::{Collider, ColliderChanges, ColliderHandle, ColliderParent};
-use crate::math::Isometry;
+use crate::math::{Isometry, Real, Vector};
+use crate::pipeline::ActiveEvents;
 use std::ops::{Index, IndexMut};
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -11,6 +12,10 @@ pub struct ColliderSet {
     pub(crate) colliders: Arena<Collider>,
     pub(crate) modified_colliders: Vec<ColliderHandle>,
     pub(crate) removed_colliders: Vec<ColliderHandle>,
+    // The colliders with a fluid volume, updated when the modified colliders are taken.
+    pub(crate) fluid_colliders: Vec<ColliderHandle>,
+    // The colliders emitting contact impact events, updated when the modified colliders are taken.
+    pub(crate) impact_event_colliders: Vec<ColliderHandle>,
 }
 
 impl ColliderSet {
@@ -20,10 +25,39 @@ impl ColliderSet {
             colliders: Arena::new(),
             modified_colliders: Vec::new(),
             removed_colliders: Vec::new(),
+            fluid_colliders: Vec::new(),
+            impact_event_colliders: Vec::new(),
         }
     }
 
     pub(crate) fn take_modified(&mut self) -> Vec<ColliderHandle> {
+        fn update_membership(
+            list: &mut Vec<ColliderHandle>,
+            handle: ColliderHandle,
+            is_member: bool,
+        ) {
+            match (is_member, list.iter().position(|h| *h == handle)) {
+                (true, None) => list.push(handle),
+                (false, Some(id)) => {
+                    list.swap_remove(id);
+                }
+                _ => {}
+            }
+        }
+
+        for handle in &self.modified_colliders {
+            let co = self.colliders.get(handle.0);
+            let has_fluid = co.map(|co| co.fluid_volume.is_some()) == Some(true);
+            let emits_impacts = co.map(|co| {
+                co.flags
+                    .active_events
+                    .contains(ActiveEvents::CONTACT_IMPACT_EVENTS)
+            }) == Some(true);
+
+            update_membership(&mut self.fluid_colliders, *handle, has_fluid);
+            update_membership(&mut self.impact_event_colliders, *handle, emits_impacts);
+        }
+
         std::mem::replace(&mut self.modified_colliders, vec![])
     }
 
@@ -66,6 +100,16 @@ impl ColliderSet {
         self.iter_mut().filter(|(_, c)| c.is_enabled())
     }
 
+    /// Translates every collider of this set by `translation`.
+    ///
+    /// The colliders are moved without being flagged as modified. This is meant to be used
+    /// for shifting the origin of the whole simulation, see `PhysicsWorld::shift_origin`.
+    pub fn shift_origin(&mut self, translation: &Vector<Real>) {
+        for (_, co) in self.colliders.iter_mut() {
+            co.pos.0.translation.vector += translation;
+        }
+    }
+
     /// The number of colliders on this set.
     pub fn len(&self) -> usize {
         self.colliders.len()
@@ -216,6 +260,18 @@ impl ColliderSet {
          */
         self.removed_colliders.push(handle);
 
+        if collider.fluid_volume.is_some() {
+            self.fluid_colliders.retain(|h| *h != handle);
+        }
+
+        if collider
+            .flags
+            .active_events
+            .contains(ActiveEvents::CONTACT_IMPACT_EVENTS)
+        {
+            self.impact_event_colliders.retain(|h| *h != handle);
+        }
+
         Some(collider)
     }
 
@@ -1,6 +1,6 @@
 use crate::dynamics::{RigidBodyHandle, RigidBodySet};
-use crate::geometry::{ColliderHandle, ColliderSet, Contact, ContactManifold};
-use crate::math::{Point, Real, Vector};
+use crate::geometry::{ColliderHandle, ColliderSet, Contact, ContactManifold, ContactSoftness};
+use crate::math::{Point, Real, Vector, DIM};
 use crate::pipeline::EventHandler;
 use crate::prelude::CollisionEventFlags;
 use parry::query::ContactManifoldsWorkspace;
@@ -273,6 +273,35 @@ pub struct ContactManifoldData {
     pub solver_contacts: Vec<SolverContact>,
     /// The relative dominance of the bodies involved in this contact manifold.
     pub relative_dominance: i16,
+    /// The world-space direction the friction of this contact manifold is aligned with, if
+    /// its friction is anisotropic.
+    ///
+    /// This is orthogonal to `normal`, along the direction with the largest friction. The
+    /// `friction` of the solver contacts applies along this direction.
+    #[cfg(feature = "dim3")]
+    pub friction_direction: Option<Vector<Real>>,
+    /// The ratio between the friction coefficient along `normal.cross(friction_direction)` and
+    /// the friction coefficient along `friction_direction`, between `0` and `1`.
+    ///
+    /// The friction orthogonal to the friction direction is derived from the `friction` of
+    /// each solver contact by the constraints solver, so the solver contacts modified by the
+    /// physics hooks keep this ratio. This is `1` if the friction is isotropic.
+    #[cfg(feature = "dim3")]
+    pub secondary_friction_ratio: Real,
+    /// The index, in the `FeatureMaterials` of the first collider, of the material of its
+    /// sub-shape involved in this contact manifold.
+    pub feature_material1: Option<u32>,
+    /// The index, in the `FeatureMaterials` of the second collider, of the material of its
+    /// sub-shape involved in this contact manifold.
+    pub feature_material2: Option<u32>,
+    /// The effective rolling friction coefficient of this contact manifold.
+    pub rolling_friction: Real,
+    /// The effective spinning friction coefficient of this contact manifold.
+    pub spinning_friction: Real,
+    /// The softness of the contacts of this manifold.
+    ///
+    /// If `None`, the global softness given by the `IntegrationParameters` is used.
+    pub softness: Option<ContactSoftness>,
     /// A user-defined piece of data.
     pub user_data: u32,
 }
@@ -335,6 +364,15 @@ impl ContactManifoldData {
             normal: Vector::zeros(),
             solver_contacts: Vec::new(),
             relative_dominance: 0,
+            #[cfg(feature = "dim3")]
+            friction_direction: None,
+            #[cfg(feature = "dim3")]
+            secondary_friction_ratio: 1.0,
+            feature_material1: None,
+            feature_material2: None,
+            rolling_friction: 0.0,
+            spinning_friction: 0.0,
+            softness: None,
             user_data: 0,
         }
     }
@@ -345,6 +383,26 @@ impl ContactManifoldData {
     pub fn num_active_contacts(&self) -> usize {
         self.solver_contacts.len()
     }
+
+    /// The friction coefficients of the given solver contact of this contact manifold, along
+    /// its friction directions.
+    pub(crate) fn friction_limits(&self, contact: &SolverContact) -> [Real; DIM - 1] {
+        #[cfg(feature = "dim2")]
+        return [contact.friction];
+
+        #[cfg(feature = "dim3")]
+        {
+            [
+                contact.friction,
+                contact.friction * self.secondary_friction_ratio,
+            ]
+        }
+    }
+
+    /// Does this contact manifold need rolling or spinning friction constraints?
+    pub(crate) fn has_angular_friction(&self) -> bool {
+        self.rolling_friction != 0.0 || (cfg!(feature = "dim3") && self.spinning_friction != 0.0)
+    }
 }
 
 /// Additional methods for the contact manifold.
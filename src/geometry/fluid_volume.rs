@@ -0,0 +1,893 @@
+use crate::dynamics::{IslandManager, RigidBodySet};
+use crate::geometry::{Collider, ColliderSet, NarrowPhase};
+use crate::math::{Isometry, Point, Real, Vector, DIM};
+use crate::utils::WCross;
+use na::{RealField, Unit};
+#[cfg(feature = "dim2")]
+use parry::query::details::clip_halfspace_polygon;
+use parry::shape::{Ball, Capsule, Cuboid, Shape, TypedShape};
+#[cfg(feature = "dim3")]
+use parry::shape::{Cone, ConvexPolyhedron, Cylinder};
+
+/// The properties of a fluid filling a sensor collider.
+///
+/// A sensor collider with a fluid volume applies buoyancy and drag forces to the dynamic
+/// rigid-bodies intersecting it. The fluid fills the local-space AABB of the sensor’s shape up
+/// to its highest point along the direction opposite to the gravity. The submerged part of each
+/// intersecting collider is computed by clipping its shape with the fluid surface and with the
+/// sides and bottom of this AABB.
+///
+/// Balls, cuboids, capsules, triangles, convex polygons/polyhedra, cylinders, cones,
+/// triangle meshes (assumed closed), and compound shapes of these are supported. The
+/// rounded borders of round shapes are ignored. Other shapes don’t experience any
+/// buoyancy nor drag.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FluidVolume {
+    /// The density of the fluid.
+    ///
+    /// The buoyancy force is the weight of the fluid displaced by the submerged part of a collider.
+    pub density: Real,
+    /// The linear drag coefficient of the fluid, per unit of submerged volume (default: `1.0`).
+    pub linear_drag: Real,
+    /// The angular drag coefficient of the fluid, per unit of submerged volume (default: `0.5`).
+    pub angular_drag: Real,
+    /// The velocity of the fluid’s flow (default: zero).
+    ///
+    /// The linear drag is computed from the velocity of the submerged colliders relative to
+    /// this flow, so a non-zero flow velocity carries the floating objects along.
+    pub flow_velocity: Vector<Real>,
+}
+
+impl FluidVolume {
+    /// Creates a still fluid with the given density and default drag coefficients.
+    pub fn new(density: Real) -> Self {
+        Self {
+            density,
+            linear_drag: 1.0,
+            angular_drag: 0.5,
+            flow_velocity: Vector::zeros(),
+        }
+    }
+
+    /// Sets the linear drag coefficient of this fluid.
+    pub fn linear_drag(mut self, linear_drag: Real) -> Self {
+        self.linear_drag = linear_drag;
+        self
+    }
+
+    /// Sets the angular drag coefficient of this fluid.
+    pub fn angular_drag(mut self, angular_drag: Real) -> Self {
+        self.angular_drag = angular_drag;
+        self
+    }
+
+    /// Sets the velocity of this fluid’s flow.
+    pub fn flow_velocity(mut self, flow_velocity: Vector<Real>) -> Self {
+        self.flow_velocity = flow_velocity;
+        self
+    }
+}
+
+/// Applies the buoyancy and drag forces of the fluid volumes to the active dynamic bodies
+/// intersecting them.
+///
+/// This keeps the buffers used to clip the submerged shapes, so they are reused from one
+/// timestep to the next.
+pub(crate) struct FluidSolver {
+    planes: Vec<ClippingPlane>,
+    accumulator: VolumeAccumulator,
+}
+
+impl FluidSolver {
+    pub fn new() -> Self {
+        Self {
+            planes: vec![],
+            accumulator: VolumeAccumulator::default(),
+        }
+    }
+
+    pub fn apply(
+        &mut self,
+        gravity: &Vector<Real>,
+        islands: &IslandManager,
+        narrow_phase: &NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &ColliderSet,
+    ) {
+        if colliders.fluid_colliders.is_empty() {
+            return;
+        }
+
+        // Without gravity, there is no fluid surface: the fluid fills the whole sensor.
+        let up = Unit::try_new(-gravity, 1.0e-6);
+
+        for fluid_handle in &colliders.fluid_colliders {
+            let fluid_co = match colliders.get(*fluid_handle) {
+                Some(fluid_co) if fluid_co.is_sensor() => fluid_co,
+                _ => continue,
+            };
+            let fluid = match fluid_co.fluid_volume.as_ref() {
+                Some(fluid) => fluid,
+                None => continue,
+            };
+
+            ClippingPlane::fluid_boundaries(&fluid_co.pos, &*fluid_co.shape, up, &mut self.planes);
+
+            for (handle1, handle2, intersecting) in narrow_phase.intersections_with(*fluid_handle) {
+                let handle = if handle1 == *fluid_handle {
+                    handle2
+                } else {
+                    handle1
+                };
+
+                match colliders.get(handle) {
+                    Some(co) if intersecting && !co.is_sensor() => {
+                        self.apply_to_collider(gravity, fluid, co, islands, bodies)
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn apply_to_collider(
+        &mut self,
+        gravity: &Vector<Real>,
+        fluid: &FluidVolume,
+        co: &Collider,
+        islands: &IslandManager,
+        bodies: &mut RigidBodySet,
+    ) {
+        let handle = match co.parent() {
+            Some(handle) => handle,
+            None => return,
+        };
+        let rb = match bodies.get(handle) {
+            Some(rb) => rb,
+            None => return,
+        };
+
+        if islands.active_dynamic_bodies().get(rb.ids.active_set_id) != Some(&handle) {
+            return;
+        }
+
+        let submerged = match SubmergedVolume::compute(
+            &*co.shape,
+            &co.pos,
+            &self.planes,
+            &mut self.accumulator,
+        ) {
+            Some(submerged) => submerged,
+            None => return,
+        };
+
+        let rb = bodies.index_mut_internal(handle);
+        let lever = submerged.centroid - rb.mprops.world_com;
+        let velocity_at_centroid = rb.vels.linvel + rb.vels.angvel.gcross(lever);
+
+        let body_gravity = rb
+            .forces
+            .gravity
+            .acceleration(gravity, &rb.mprops.world_com);
+        let buoyancy = -body_gravity * (fluid.density * submerged.volume);
+        let drag =
+            (fluid.flow_velocity - velocity_at_centroid) * (fluid.linear_drag * submerged.volume);
+        let force = buoyancy + drag;
+
+        rb.forces.force += force;
+        rb.forces.torque +=
+            lever.gcross(force) - rb.vels.angvel * (fluid.angular_drag * submerged.volume);
+    }
+}
+
+/// A plane bounding the fluid: the fluid lies on the side opposite to its normal.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct ClippingPlane {
+    pub point: Point<Real>,
+    pub normal: Unit<Vector<Real>>,
+}
+
+impl ClippingPlane {
+    /// Sets `planes` to the planes bounding the fluid filling the local-space AABB of `shape` at
+    /// the position `pos`: the faces of this AABB, and the fluid surface if the `up` direction is
+    /// known.
+    pub fn fluid_boundaries(
+        pos: &Isometry<Real>,
+        shape: &dyn Shape,
+        up: Option<Unit<Vector<Real>>>,
+        planes: &mut Vec<Self>,
+    ) {
+        let aabb = shape.compute_local_aabb();
+        planes.clear();
+
+        if let Some(up) = up {
+            // The fluid fills the AABB up to its highest point along `up`.
+            let local_up = pos.inverse_transform_vector(&up);
+            let mut local_surface = aabb.mins;
+            for i in 0..DIM {
+                if local_up[i] > 0.0 {
+                    local_surface[i] = aabb.maxs[i];
+                }
+            }
+            planes.push(Self {
+                point: pos * local_surface,
+                normal: up,
+            });
+        }
+
+        for i in 0..DIM {
+            let axis = pos * Vector::ith_axis(i);
+            planes.push(Self {
+                point: pos * aabb.mins,
+                normal: -axis,
+            });
+            planes.push(Self {
+                point: pos * aabb.maxs,
+                normal: axis,
+            });
+        }
+    }
+
+    fn distance(&self, pt: &Point<Real>) -> Real {
+        (pt - self.point).dot(&self.normal)
+    }
+}
+
+/// The volume and the world-space centroid of the part of a shape inside of a fluid.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct SubmergedVolume {
+    pub volume: Real,
+    pub centroid: Point<Real>,
+}
+
+impl SubmergedVolume {
+    /// Computes the part of `shape`, at the position `pos`, lying behind all the given planes.
+    ///
+    /// Returns `None` if the shape isn’t submerged, or isn’t supported.
+    pub fn compute(
+        shape: &dyn Shape,
+        pos: &Isometry<Real>,
+        planes: &[ClippingPlane],
+        acc: &mut VolumeAccumulator,
+    ) -> Option<Self> {
+        acc.volume = 0.0;
+        acc.weighted_centroid = Vector::zeros();
+        acc.add_shape(shape, pos, planes);
+
+        if acc.volume > 0.0 {
+            Some(Self {
+                volume: acc.volume,
+                centroid: Point::from(acc.weighted_centroid / acc.volume),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+// Number of subdivisions used to discretize the curved shapes.
+const NUM_SUBDIVISIONS: u32 = 16;
+
+/// The `i`-th of the `NUM_SUBDIVISIONS` points discretizing the given fraction of a circle.
+#[cfg(feature = "dim2")]
+fn arc_point(radius: Real, i: u32, fraction: Real) -> Vector<Real> {
+    let angle = Real::two_pi() * fraction * (i as Real) / (NUM_SUBDIVISIONS as Real);
+    Vector::new(angle.cos(), angle.sin()) * radius
+}
+
+#[derive(Default)]
+pub(crate) struct VolumeAccumulator {
+    volume: Real,
+    weighted_centroid: Vector<Real>,
+    #[cfg(feature = "dim2")]
+    clipped: Vec<Point<Real>>,
+    #[cfg(feature = "dim2")]
+    clipped_tmp: Vec<Point<Real>>,
+    #[cfg(feature = "dim3")]
+    faces: Vec<[Point<Real>; 3]>,
+    #[cfg(feature = "dim3")]
+    clipped_faces: Vec<[Point<Real>; 3]>,
+    #[cfg(feature = "dim3")]
+    unit_meshes: UnitMeshes,
+}
+
+/// The discretizations of the curved shapes and cuboids, computed once with unit dimensions
+/// and then scaled to the dimensions of each shape.
+#[cfg(feature = "dim3")]
+#[derive(Default)]
+struct UnitMeshes([TriMeshBuffers; 5]);
+
+#[cfg(feature = "dim3")]
+type TriMeshBuffers = (Vec<Point<Real>>, Vec<[u32; 3]>);
+
+#[cfg(feature = "dim3")]
+#[derive(Copy, Clone)]
+enum UnitMesh {
+    // A ball with a radius of 1.
+    Ball,
+    // A cuboid with half-extents of 1.
+    Cuboid,
+    // A capsule along the `y` axis with a radius of 0.5 and a half-height of 1.
+    Capsule,
+    // A cylinder along the `y` axis with a radius and a half-height of 1.
+    Cylinder,
+    // A cone along the `y` axis with a radius and a half-height of 1.
+    Cone,
+}
+
+#[cfg(feature = "dim3")]
+impl UnitMeshes {
+    fn get(&mut self, mesh: UnitMesh) -> &TriMeshBuffers {
+        let buffers = &mut self.0[mesh as usize];
+
+        if buffers.1.is_empty() {
+            *buffers = match mesh {
+                UnitMesh::Ball => Ball::new(1.0).to_trimesh(NUM_SUBDIVISIONS, NUM_SUBDIVISIONS / 2),
+                UnitMesh::Cuboid => Cuboid::new(Vector::repeat(1.0)).to_trimesh(),
+                UnitMesh::Capsule => {
+                    Capsule::new_y(1.0, 0.5).to_trimesh(NUM_SUBDIVISIONS, NUM_SUBDIVISIONS / 2)
+                }
+                UnitMesh::Cylinder => Cylinder::new(1.0, 1.0).to_trimesh(NUM_SUBDIVISIONS),
+                UnitMesh::Cone => Cone::new(1.0, 1.0).to_trimesh(NUM_SUBDIVISIONS),
+            };
+        }
+
+        buffers
+    }
+}
+
+impl VolumeAccumulator {
+    fn add(&mut self, volume: Real, centroid: Point<Real>) {
+        self.volume += volume;
+        self.weighted_centroid += centroid.coords * volume;
+    }
+
+    fn add_shape(&mut self, shape: &dyn Shape, pos: &Isometry<Real>, planes: &[ClippingPlane]) {
+        match shape.as_typed_shape() {
+            TypedShape::Ball(ball) => self.add_ball(ball, pos, planes),
+            TypedShape::Compound(compound) => {
+                for (part_pos, part) in compound.shapes() {
+                    self.add_shape(&**part, &(pos * part_pos), planes);
+                }
+            }
+            #[cfg(feature = "dim2")]
+            TypedShape::Cuboid(s) => self.add_cuboid(s, pos, planes),
+            #[cfg(feature = "dim2")]
+            TypedShape::RoundCuboid(s) => self.add_cuboid(&s.inner_shape, pos, planes),
+            #[cfg(feature = "dim2")]
+            TypedShape::Capsule(s) => self.add_capsule(s, pos, planes),
+            #[cfg(feature = "dim2")]
+            TypedShape::Triangle(s) => {
+                self.add_polygon(s.vertices().iter().map(|pt| pos * pt), planes)
+            }
+            #[cfg(feature = "dim2")]
+            TypedShape::RoundTriangle(s) => {
+                self.add_polygon(s.inner_shape.vertices().iter().map(|pt| pos * pt), planes)
+            }
+            #[cfg(feature = "dim2")]
+            TypedShape::ConvexPolygon(s) => {
+                self.add_polygon(s.points().iter().map(|pt| pos * pt), planes)
+            }
+            #[cfg(feature = "dim2")]
+            TypedShape::RoundConvexPolygon(s) => {
+                self.add_polygon(s.inner_shape.points().iter().map(|pt| pos * pt), planes)
+            }
+            #[cfg(feature = "dim2")]
+            TypedShape::TriMesh(s) => {
+                for tri in s.triangles() {
+                    self.add_polygon(tri.vertices().iter().map(|pt| pos * pt), planes);
+                }
+            }
+            #[cfg(feature = "dim3")]
+            TypedShape::Cuboid(s) => {
+                self.add_scaled_unit_mesh(UnitMesh::Cuboid, s.half_extents, pos, planes)
+            }
+            #[cfg(feature = "dim3")]
+            TypedShape::RoundCuboid(s) => {
+                self.add_scaled_unit_mesh(UnitMesh::Cuboid, s.inner_shape.half_extents, pos, planes)
+            }
+            #[cfg(feature = "dim3")]
+            TypedShape::Capsule(s) => self.add_capsule(s, pos, planes),
+            #[cfg(feature = "dim3")]
+            TypedShape::Cylinder(s) => self.add_scaled_unit_mesh(
+                UnitMesh::Cylinder,
+                Vector::new(s.radius, s.half_height, s.radius),
+                pos,
+                planes,
+            ),
+            #[cfg(feature = "dim3")]
+            TypedShape::RoundCylinder(s) => self.add_scaled_unit_mesh(
+                UnitMesh::Cylinder,
+                Vector::new(
+                    s.inner_shape.radius,
+                    s.inner_shape.half_height,
+                    s.inner_shape.radius,
+                ),
+                pos,
+                planes,
+            ),
+            #[cfg(feature = "dim3")]
+            TypedShape::Cone(s) => self.add_scaled_unit_mesh(
+                UnitMesh::Cone,
+                Vector::new(s.radius, s.half_height, s.radius),
+                pos,
+                planes,
+            ),
+            #[cfg(feature = "dim3")]
+            TypedShape::RoundCone(s) => self.add_scaled_unit_mesh(
+                UnitMesh::Cone,
+                Vector::new(
+                    s.inner_shape.radius,
+                    s.inner_shape.half_height,
+                    s.inner_shape.radius,
+                ),
+                pos,
+                planes,
+            ),
+            #[cfg(feature = "dim3")]
+            TypedShape::ConvexPolyhedron(s) => self.add_convex_polyhedron(s, pos, planes),
+            #[cfg(feature = "dim3")]
+            TypedShape::RoundConvexPolyhedron(s) => {
+                self.add_convex_polyhedron(&s.inner_shape, pos, planes)
+            }
+            #[cfg(feature = "dim3")]
+            TypedShape::TriMesh(s) => {
+                self.faces.clear();
+                self.faces.extend(
+                    s.triangles()
+                        .map(|tri| [pos * tri.a, pos * tri.b, pos * tri.c]),
+                );
+                self.add_closed_faces(planes);
+            }
+            _ => {}
+        }
+    }
+
+    fn add_ball(&mut self, ball: &Ball, pos: &Isometry<Real>, planes: &[ClippingPlane]) {
+        let radius = ball.radius;
+        let center = Point::from(pos.translation.vector);
+        let mut cut_plane = None;
+
+        for plane in planes {
+            let dist = plane.distance(&center);
+
+            if dist >= radius {
+                // The ball is entirely outside of the fluid.
+                return;
+            }
+
+            if dist > -radius {
+                if cut_plane.is_some() {
+                    // The ball crosses several planes: clip its discretization instead.
+                    #[cfg(feature = "dim2")]
+                    self.add_polygon(
+                        (0..NUM_SUBDIVISIONS).map(|i| center + arc_point(radius, i, 1.0)),
+                        planes,
+                    );
+                    #[cfg(feature = "dim3")]
+                    self.add_scaled_unit_mesh(UnitMesh::Ball, Vector::repeat(radius), pos, planes);
+                    return;
+                }
+
+                cut_plane = Some(plane);
+            }
+        }
+
+        let plane = match cut_plane {
+            Some(plane) => plane,
+            None => {
+                // The ball is entirely inside of the fluid.
+                #[cfg(feature = "dim2")]
+                let volume = Real::pi() * radius * radius;
+                #[cfg(feature = "dim3")]
+                let volume = Real::pi() * radius * radius * radius * 4.0 / 3.0;
+                self.add(volume, center);
+                return;
+            }
+        };
+
+        // Height of the submerged cap.
+        let h = radius - plane.distance(&center);
+
+        #[cfg(feature = "dim2")]
+        let (volume, centroid_dist) = {
+            let half_chord = (2.0 * radius * h - h * h).max(0.0).sqrt();
+            let area = radius * radius * ((radius - h) / radius).clamp(-1.0, 1.0).acos()
+                - (radius - h) * half_chord;
+            let dist = if area > 0.0 {
+                2.0 / 3.0 * half_chord * half_chord * half_chord / area
+            } else {
+                0.0
+            };
+            (area, dist)
+        };
+        #[cfg(feature = "dim3")]
+        let (volume, centroid_dist) = {
+            let volume = Real::pi() * h * h * (3.0 * radius - h) / 3.0;
+            let dist = 3.0 * (2.0 * radius - h) * (2.0 * radius - h) / (4.0 * (3.0 * radius - h));
+            (volume, dist)
+        };
+
+        self.add(volume, center - *plane.normal * centroid_dist);
+    }
+
+    #[cfg(feature = "dim2")]
+    fn add_cuboid(&mut self, cuboid: &Cuboid, pos: &Isometry<Real>, planes: &[ClippingPlane]) {
+        let he = cuboid.half_extents;
+        let vertices = [
+            Point::new(-he.x, -he.y),
+            Point::new(he.x, -he.y),
+            Point::new(he.x, he.y),
+            Point::new(-he.x, he.y),
+        ];
+        self.add_polygon(vertices.iter().map(|pt| pos * pt), planes);
+    }
+
+    #[cfg(feature = "dim2")]
+    fn add_capsule(&mut self, capsule: &Capsule, pos: &Isometry<Real>, planes: &[ClippingPlane]) {
+        // Two half-circles on each side of the capsule’s segment, in its canonical frame.
+        let frame = pos * capsule.canonical_transform();
+        let half_height = capsule.half_height();
+        let vertices = (0..2 * NUM_SUBDIVISIONS).map(|i| {
+            let pt =
+                Point::new(0.0, half_height) + arc_point(capsule.radius, i % NUM_SUBDIVISIONS, 0.5);
+
+            if i < NUM_SUBDIVISIONS {
+                frame * pt
+            } else {
+                frame * -pt
+            }
+        });
+        self.add_polygon(vertices, planes);
+    }
+
+    #[cfg(feature = "dim2")]
+    fn add_polygon(
+        &mut self,
+        vertices: impl Iterator<Item = Point<Real>>,
+        planes: &[ClippingPlane],
+    ) {
+        self.clipped.clear();
+        self.clipped.extend(vertices);
+
+        for plane in planes {
+            clip_halfspace_polygon(
+                &plane.point,
+                &plane.normal,
+                &self.clipped,
+                &mut self.clipped_tmp,
+            );
+            std::mem::swap(&mut self.clipped, &mut self.clipped_tmp);
+        }
+
+        if self.clipped.len() < 3 {
+            return;
+        }
+
+        // Triangle fan around the first vertex of the clipped polygon.
+        let a = self.clipped[0];
+        for i in 1..self.clipped.len() - 1 {
+            let (b, c) = (self.clipped[i], self.clipped[i + 1]);
+            // The polygon orientation doesn’t matter: take the absolute value.
+            let area = ((b - a).perp(&(c - a)) / 2.0).abs();
+            self.add(area, Point::from((a.coords + b.coords + c.coords) / 3.0));
+        }
+    }
+
+    #[cfg(feature = "dim3")]
+    fn add_capsule(&mut self, capsule: &Capsule, pos: &Isometry<Real>, planes: &[ClippingPlane]) {
+        // Move the hemispheres of the unit capsule, then scale them by the capsule’s diameter.
+        let frame = pos * capsule.canonical_transform();
+        let diameter = capsule.radius * 2.0;
+        let half_height = capsule.half_height();
+        self.add_unit_mesh(
+            UnitMesh::Capsule,
+            |pt| {
+                let y = if pt.y < 0.0 {
+                    (pt.y + 1.0) * diameter - half_height
+                } else {
+                    (pt.y - 1.0) * diameter + half_height
+                };
+                frame * Point::new(pt.x * diameter, y, pt.z * diameter)
+            },
+            planes,
+        );
+    }
+
+    #[cfg(feature = "dim3")]
+    fn add_scaled_unit_mesh(
+        &mut self,
+        mesh: UnitMesh,
+        scale: Vector<Real>,
+        pos: &Isometry<Real>,
+        planes: &[ClippingPlane],
+    ) {
+        self.add_unit_mesh(
+            mesh,
+            |pt| pos * Point::from(pt.coords.component_mul(&scale)),
+            planes,
+        );
+    }
+
+    #[cfg(feature = "dim3")]
+    fn add_unit_mesh(
+        &mut self,
+        mesh: UnitMesh,
+        transform: impl Fn(&Point<Real>) -> Point<Real>,
+        planes: &[ClippingPlane],
+    ) {
+        let (vertices, indices) = self.unit_meshes.get(mesh);
+        self.faces.clear();
+        self.faces.extend(
+            indices
+                .iter()
+                .map(|idx| idx.map(|i| transform(&vertices[i as usize]))),
+        );
+        self.add_closed_faces(planes);
+    }
+
+    #[cfg(feature = "dim3")]
+    fn add_convex_polyhedron(
+        &mut self,
+        poly: &ConvexPolyhedron,
+        pos: &Isometry<Real>,
+        planes: &[ClippingPlane],
+    ) {
+        let (points, adj) = (poly.points(), poly.vertices_adj_to_face());
+        self.faces.clear();
+
+        // Triangle fan around the first vertex of each face.
+        for face in poly.faces() {
+            let i1 = face.first_vertex_or_edge as usize;
+            let i2 = i1 + face.num_vertices_or_edges as usize;
+            let first = pos * points[adj[i1] as usize];
+
+            for ids in adj[i1 + 1..i2].windows(2) {
+                self.faces.push([
+                    first,
+                    pos * points[ids[0] as usize],
+                    pos * points[ids[1] as usize],
+                ]);
+            }
+        }
+
+        self.add_closed_faces(planes);
+    }
+
+    /// Clips the closed triangle mesh formed by `self.faces` by each plane, and adds the volume
+    /// of the result.
+    #[cfg(feature = "dim3")]
+    fn add_closed_faces(&mut self, planes: &[ClippingPlane]) {
+        for plane in planes {
+            self.clipped_faces.clear();
+            // The faces closing the mesh on the clipping plane are built as a triangle fan
+            // around an arbitrary point of this plane.
+            let mut cap_center = None;
+
+            for face in &self.faces {
+                let mut clipped = [Point::origin(); 4];
+                let mut len = 0;
+                let mut exit = None;
+                let mut entry = None;
+
+                for i in 0..3 {
+                    let (a, b) = (face[i], face[(i + 1) % 3]);
+                    let (dist_a, dist_b) = (plane.distance(&a), plane.distance(&b));
+
+                    if dist_a <= 0.0 {
+                        clipped[len] = a;
+                        len += 1;
+                    }
+
+                    if (dist_a <= 0.0) != (dist_b <= 0.0) {
+                        let pt = a + (b - a) * (dist_a / (dist_a - dist_b));
+                        clipped[len] = pt;
+                        len += 1;
+
+                        if dist_a <= 0.0 {
+                            exit = Some(pt);
+                        } else {
+                            entry = Some(pt);
+                        }
+                    }
+                }
+
+                for i in 1..len.saturating_sub(1) {
+                    self.clipped_faces
+                        .push([clipped[0], clipped[i], clipped[i + 1]]);
+                }
+
+                // The clipped face has an edge going from `exit` to `entry` on the plane. The
+                // cap traverses it in the opposite direction to keep the mesh consistently
+                // oriented.
+                if let (Some(exit), Some(entry)) = (exit, entry) {
+                    let center = *cap_center.get_or_insert(exit);
+                    self.clipped_faces.push([center, entry, exit]);
+                }
+            }
+
+            std::mem::swap(&mut self.faces, &mut self.clipped_faces);
+        }
+
+        let apex = match self.faces.first() {
+            Some(face) => face[0],
+            None => return,
+        };
+
+        // Sum the signed volumes of the tetrahedra formed by the faces of the closed clipped
+        // mesh and an arbitrary apex.
+        for i in 0..self.faces.len() {
+            let [a, b, c] = self.faces[i];
+            let volume = (a - apex).dot(&(b - apex).cross(&(c - apex))) / 6.0;
+            let centroid = (apex.coords + a.coords + b.coords + c.coords) / 4.0;
+            self.add(volume, Point::from(centroid));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ClippingPlane, FluidVolume, SubmergedVolume, VolumeAccumulator};
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::{ColliderBuilder, SharedShape};
+    use crate::math::{Isometry, Point, Real, Vector};
+    use crate::pipeline::test_utils;
+
+    #[test]
+    fn floating_box_settles_at_equilibrium() {
+        // Half of a ball below the surface.
+        let ball = SharedShape::ball(1.0);
+        let surface = ClippingPlane {
+            point: Point::origin(),
+            normal: Vector::y_axis(),
+        };
+        let acc = &mut VolumeAccumulator::default();
+        let half =
+            SubmergedVolume::compute(&*ball, &Isometry::identity(), &[surface], acc).unwrap();
+        let full_volume = ball.mass_properties(1.0).mass();
+        assert!((half.volume - full_volume / 2.0).abs() < 1.0e-4);
+        assert!(half.centroid.y < 0.0);
+
+        let mut world = test_utils::world();
+        // The fluid surface is at y = 0.
+        #[cfg(feature = "dim2")]
+        let (pool, floater) = (
+            ColliderBuilder::cuboid(10.0, 5.0),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (pool, floater) = (
+            ColliderBuilder::cuboid(10.0, 5.0, 10.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        let pool = world.insert_collider(
+            pool.translation(Vector::y() * -5.0)
+                .sensor(true)
+                .fluid_volume(FluidVolume::new(1000.0).linear_drag(5000.0)),
+        );
+        let handle = world.insert_rigid_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 1.0)
+                .can_sleep(false),
+        );
+        // A box half as dense as the fluid floats half-submerged.
+        world.insert_collider_with_parent(floater.density(500.0), handle);
+
+        for _ in 0..600 {
+            world.step(&(), &());
+        }
+
+        let y: Real = world.bodies[handle].translation().y;
+        assert!(y.abs() < 0.05, "{}", y);
+
+        // Without its fluid, the pool doesn’t hold the box anymore.
+        world.colliders[pool].set_fluid_volume(None);
+        for _ in 0..60 {
+            world.step(&(), &());
+        }
+
+        let y: Real = world.bodies[handle].translation().y;
+        assert!(y < -1.0, "{}", y);
+    }
+
+    #[test]
+    fn submerged_volume_of_discretized_shapes() {
+        #[cfg(feature = "dim2")]
+        let shapes = [
+            SharedShape::cuboid(0.5, 1.0),
+            SharedShape::capsule_y(1.0, 0.5),
+            SharedShape::triangle(
+                Point::new(-1.0, -1.0),
+                Point::new(1.0, -1.0),
+                Point::new(0.0, 2.0),
+            ),
+        ];
+        #[cfg(feature = "dim3")]
+        let shapes = [
+            SharedShape::cuboid(0.5, 1.0, 1.5),
+            SharedShape::capsule_y(1.0, 0.5),
+            SharedShape::cylinder(1.0, 0.5),
+            SharedShape::cone(1.0, 0.5),
+            SharedShape::convex_hull(&[
+                Point::new(-1.0, -1.0, -1.0),
+                Point::new(1.0, -1.0, -1.0),
+                Point::new(0.0, 1.0, -1.0),
+                Point::new(0.0, 0.0, 1.0),
+            ])
+            .unwrap(),
+        ];
+        let acc = &mut VolumeAccumulator::default();
+        #[cfg(feature = "dim2")]
+        let pos = Isometry::new(Vector::new(1.0, 2.0), 0.3);
+        #[cfg(feature = "dim3")]
+        let pos = Isometry::new(Vector::new(1.0, 2.0, 3.0), Vector::new(0.3, 0.2, 0.1));
+
+        for shape in &shapes {
+            let mprops = shape.mass_properties(1.0);
+            let submerged = SubmergedVolume::compute(&**shape, &pos, &[], acc).unwrap();
+            // The curved shapes are discretized.
+            assert!((submerged.volume - mprops.mass()).abs() < mprops.mass() * 0.05);
+            assert!((submerged.centroid - pos * mprops.local_com).norm() < 1.0e-2);
+        }
+    }
+
+    #[test]
+    fn body_overhanging_the_pool_edge() {
+        // The pool spans x ∈ [-10, 10] and y ∈ [-10, 0].
+        #[cfg(feature = "dim2")]
+        let (pool, floater) = (
+            SharedShape::cuboid(10.0, 5.0),
+            SharedShape::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (pool, floater) = (
+            SharedShape::cuboid(10.0, 5.0, 10.0),
+            SharedShape::cuboid(0.5, 0.5, 0.5),
+        );
+        let pool_pos = Isometry::from(Vector::y() * -5.0);
+        let mut planes = vec![];
+        ClippingPlane::fluid_boundaries(&pool_pos, &*pool, Some(Vector::y_axis()), &mut planes);
+        let acc = &mut VolumeAccumulator::default();
+
+        // Only the half of the fully-submerged box inside of the pool is taken into account.
+        let pos = Isometry::from(Vector::x() * 10.0 - Vector::y() * 2.0);
+        let submerged = SubmergedVolume::compute(&*floater, &pos, &planes, acc).unwrap();
+        let full_volume = floater.mass_properties(1.0).mass();
+        assert!((submerged.volume - full_volume / 2.0).abs() < 1.0e-4);
+        assert!((submerged.centroid.x - 9.75).abs() < 1.0e-4);
+        assert!((submerged.centroid.y + 2.0).abs() < 1.0e-4);
+
+        // Same with a ball crossing both the surface and the pool edge.
+        let ball = SharedShape::ball(0.5);
+        let pos = Isometry::from(Vector::x() * 10.0);
+        let submerged = SubmergedVolume::compute(&*ball, &pos, &planes, acc).unwrap();
+        let full_volume = ball.mass_properties(1.0).mass();
+        assert!((submerged.volume - full_volume / 4.0).abs() < full_volume * 0.02);
+        assert!(submerged.centroid.x < 10.0 && submerged.centroid.y < 0.0);
+
+        let mut world = test_utils::world();
+        world.insert_collider(
+            ColliderBuilder::new(pool)
+                .position(pool_pos)
+                .sensor(true)
+                .fluid_volume(FluidVolume::new(1000.0).linear_drag(5000.0)),
+        );
+        let handle = world.insert_rigid_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * 10.0)
+                .lock_rotations()
+                .can_sleep(false),
+        );
+        // A box a quarter as dense as the fluid, with half of its width overhanging the pool,
+        // floats half-submerged.
+        world.insert_collider_with_parent(ColliderBuilder::new(floater).density(250.0), handle);
+
+        for _ in 0..600 {
+            world.step(&(), &());
+        }
+
+        let y: Real = world.bodies[handle].translation().y;
+        assert!(y.abs() < 0.05, "{}", y);
+    }
+}
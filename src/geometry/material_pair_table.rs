@@ -0,0 +1,185 @@
+use crate::geometry::ContactSoftness;
+use crate::math::Real;
+use std::collections::HashMap;
+
+/// The identifier of a collider material, used to look up the properties of the contacts between
+/// two materials in a [`MaterialPairTable`].
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MaterialId(pub u32);
+
+/// The properties of the contacts between two collider materials.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MaterialPairProperties {
+    /// The friction coefficient of the contacts between the two materials.
+    ///
+    /// This friction is isotropic: it overrides the anisotropic friction of the colliders.
+    pub friction: Real,
+    /// The restitution coefficient of the contacts between the two materials.
+    pub restitution: Real,
+    /// The softness of the contacts between the two materials.
+    ///
+    /// If `None`, the softness of the colliders is combined with their combine rules.
+    pub softness: Option<ContactSoftness>,
+}
+
+impl MaterialPairProperties {
+    /// Creates the properties of the contacts between two materials, without any softness.
+    pub fn new(friction: Real, restitution: Real) -> Self {
+        Self {
+            friction,
+            restitution,
+            softness: None,
+        }
+    }
+
+    /// Sets the softness of the contacts between the two materials.
+    pub fn softness(mut self, softness: ContactSoftness) -> Self {
+        self.softness = Some(softness);
+        self
+    }
+}
+
+/// A table giving the properties of the contacts between pairs of collider materials.
+///
+/// When two colliders with a [`MaterialId`] are in contact and their pair of materials is listed
+/// in this table, their friction and restitution (and, optionally, softness) are read from this
+/// table instead of being combined with the `CoefficientCombineRule` of the colliders.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default)]
+pub struct MaterialPairTable {
+    pairs: HashMap<(MaterialId, MaterialId), MaterialPairProperties>,
+}
+
+impl MaterialPairTable {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn key(material1: MaterialId, material2: MaterialId) -> (MaterialId, MaterialId) {
+        (material1.min(material2), material1.max(material2))
+    }
+
+    /// The number of material pairs in this table.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Is this table empty?
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Sets the properties of the contacts between two materials.
+    ///
+    /// The order of the materials doesn’t matter. Returns the properties previously set for this
+    /// pair, if any.
+    pub fn insert(
+        &mut self,
+        material1: MaterialId,
+        material2: MaterialId,
+        properties: MaterialPairProperties,
+    ) -> Option<MaterialPairProperties> {
+        self.pairs
+            .insert(Self::key(material1, material2), properties)
+    }
+
+    /// Removes the properties of the contacts between two materials.
+    pub fn remove(
+        &mut self,
+        material1: MaterialId,
+        material2: MaterialId,
+    ) -> Option<MaterialPairProperties> {
+        self.pairs.remove(&Self::key(material1, material2))
+    }
+
+    /// The properties of the contacts between two materials, if this pair is listed.
+    pub fn get(
+        &self,
+        material1: MaterialId,
+        material2: MaterialId,
+    ) -> Option<&MaterialPairProperties> {
+        self.pairs.get(&Self::key(material1, material2))
+    }
+
+    /// Iterates through all the material pairs of this table, and their properties.
+    pub fn iter(
+        &self,
+    ) -> impl ExactSizeIterator<Item = (MaterialId, MaterialId, &MaterialPairProperties)> {
+        self.pairs
+            .iter()
+            .map(|((material1, material2), properties)| (*material1, *material2, properties))
+    }
+
+    /// Removes all the material pairs of this table.
+    pub fn clear(&mut self) {
+        self.pairs.clear()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MaterialId, MaterialPairProperties};
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_utils;
+
+    #[test]
+    fn material_pairs_override_combine_rules() {
+        const ICE: MaterialId = MaterialId(0);
+        const RUBBER: MaterialId = MaterialId(1);
+        const WOOD: MaterialId = MaterialId(2);
+
+        let mut world = test_utils::world();
+        world.narrow_phase.material_pairs_mut().insert(
+            ICE,
+            ICE,
+            MaterialPairProperties::new(0.0, 0.0),
+        );
+        world.narrow_phase.material_pairs_mut().insert(
+            RUBBER,
+            ICE,
+            MaterialPairProperties::new(0.5, 0.25),
+        );
+
+        let ground = test_utils::cuboid(100.0, 1.0);
+        let ground = ground.translation(-Vector::y()).friction(1.0);
+        let ground = world.insert_collider(ground.material_id(ICE));
+
+        // Boxes sliding on the ice, made of materials listed or not in the table.
+        let mut sliding_box = |x: Real, material_id: MaterialId| {
+            let body = world.insert_rigid_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * x + Vector::y() * 0.5)
+                    .linvel(Vector::x() * 5.0),
+            );
+            let collider = test_utils::cuboid(0.5, 0.5);
+            let collider = collider.friction(0.2).material_id(material_id);
+            world.insert_collider_with_parent(collider, body)
+        };
+        let ice = sliding_box(0.0, ICE);
+        let rubber = sliding_box(10.0, RUBBER);
+        let wood = sliding_box(20.0, WOOD);
+
+        world.step(&(), &());
+
+        // The pairs of the table are symmetric, and the unlisted pairs use the combine rules.
+        for (collider, friction, restitution) in
+            [(ice, 0.0, 0.0), (rubber, 0.5, 0.25), (wood, 0.6, 0.0)]
+        {
+            let pair = world.narrow_phase.contact_pair(ground, collider).unwrap();
+            let contact = &pair.manifolds[0].data.solver_contacts[0];
+            assert_eq!(contact.friction, friction);
+            assert_eq!(contact.restitution, restitution);
+        }
+
+        for _ in 0..60 {
+            world.step(&(), &());
+        }
+
+        let ice_body = world.colliders[ice].parent().unwrap();
+        assert!((world.bodies[ice_body].linvel().x - 5.0).abs() < 1.0e-3);
+    }
+}
@@ -12,14 +12,17 @@ pub use self::interaction_graph::{
     ColliderGraphIndex, InteractionGraph, RigidBodyGraphIndex, TemporaryInteractionIndex,
 };
 pub use self::interaction_groups::{Group, InteractionGroups};
+pub use self::material_pair_table::{MaterialId, MaterialPairProperties, MaterialPairTable};
 pub use self::narrow_phase::NarrowPhase;
 
 pub use self::collider::{Collider, ColliderBuilder};
 pub use self::collider_set::ColliderSet;
+pub(crate) use self::fluid_volume::FluidSolver;
+pub use self::fluid_volume::FluidVolume;
 
 pub use parry::query::TrackedContact;
 
-use crate::math::{Real, Vector};
+use crate::math::{Point, Real, Vector};
 
 /// A contact between two colliders.
 pub type Contact = parry::query::TrackedContact<ContactData>;
@@ -142,6 +145,12 @@ pub struct ContactForceEvent {
     pub max_force_direction: Vector<Real>,
     /// The magnitude of the largest force at a contact point of this contact pair.
     pub max_force_magnitude: Real,
+    /// The index, in the `FeatureMaterials` of the first collider, of the material of its
+    /// sub-shape involved in the contact with the largest force.
+    pub feature_material1: Option<u32>,
+    /// The index, in the `FeatureMaterials` of the second collider, of the material of its
+    /// sub-shape involved in the contact with the largest force.
+    pub feature_material2: Option<u32>,
 }
 
 impl ContactForceEvent {
@@ -162,6 +171,8 @@ impl ContactForceEvent {
                 if pt.data.impulse > result.max_force_magnitude {
                     result.max_force_magnitude = pt.data.impulse;
                     result.max_force_direction = m.data.normal;
+                    result.feature_material1 = m.data.feature_material1;
+                    result.feature_material2 = m.data.feature_material2;
                 }
             }
 
@@ -179,8 +190,124 @@ impl ContactForceEvent {
     }
 }
 
-pub(crate) use self::broad_phase_multi_sap::SAPProxyIndex;
-pub(crate) use self::narrow_phase::ContactManifoldIndex;
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+/// Event emitted at each timestep for each pair of colliders with active contacts.
+pub struct ContactPersistedEvent {
+    /// The first collider involved in the contact.
+    pub collider1: ColliderHandle,
+    /// The second collider involved in the contact.
+    pub collider2: ColliderHandle,
+    /// The number of active contacts between the two colliders.
+    pub num_active_contacts: usize,
+    /// The sum of all the impulses applied between the two colliders during the timestep,
+    /// including all its CCD substeps.
+    pub total_impulse: Vector<Real>,
+    /// The sum of the magnitudes of each impulse applied between the two colliders during the
+    /// timestep, including all its CCD substeps.
+    pub total_impulse_magnitude: Real,
+    /// The index, in the `FeatureMaterials` of the first collider, of the material of its
+    /// sub-shape involved in the contact manifold with the largest impulse.
+    pub feature_material1: Option<u32>,
+    /// The index, in the `FeatureMaterials` of the second collider, of the material of its
+    /// sub-shape involved in the contact manifold with the largest impulse.
+    pub feature_material2: Option<u32>,
+}
+
+impl ContactPersistedEvent {
+    /// Init a contact persisted event from a contact pair.
+    ///
+    /// The impulses are the ones currently stored by `pair`, i.e., the ones applied during the
+    /// last solved substep.
+    pub fn from_contact_pair(pair: &ContactPair) -> Self {
+        let mut result = ContactPersistedEvent {
+            collider1: pair.collider1,
+            collider2: pair.collider2,
+            ..ContactPersistedEvent::default()
+        };
+
+        let mut max_manifold_impulse = -1.0;
+
+        for m in &pair.manifolds {
+            let mut total_manifold_impulse = 0.0;
+            for pt in m.contacts() {
+                total_manifold_impulse += pt.data.impulse;
+            }
+
+            if total_manifold_impulse > max_manifold_impulse {
+                max_manifold_impulse = total_manifold_impulse;
+                result.feature_material1 = m.data.feature_material1;
+                result.feature_material2 = m.data.feature_material2;
+            }
+
+            result.num_active_contacts += m.data.num_active_contacts();
+            result.total_impulse += m.data.normal * total_manifold_impulse;
+            result.total_impulse_magnitude += total_manifold_impulse;
+        }
+
+        result
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// Event emitted for each contact point hit with a relative normal velocity exceeding the
+/// minimum impact velocity of the colliders involved.
+pub struct ContactImpactEvent {
+    /// The first collider involved in the contact.
+    pub collider1: ColliderHandle,
+    /// The second collider involved in the contact.
+    pub collider2: ColliderHandle,
+    /// The world-space contact point.
+    pub point: Point<Real>,
+    /// The world-space contact normal, pointing from the first collider toward the second one.
+    pub normal: Vector<Real>,
+    /// The relative velocity of the two colliders along the contact normal before the
+    /// constraints solver resolves the contact, positive if they are approaching.
+    pub impact_velocity: Real,
+    /// The index of the sub-shape of the first collider involved in the contact.
+    ///
+    /// For triangle meshes, this is the index of the triangle that was hit.
+    pub subshape1: u32,
+    /// The index of the sub-shape of the second collider involved in the contact.
+    pub subshape2: u32,
+    /// The feature (vertex, edge, or face) of the first collider’s sub-shape involved in the contact.
+    pub feature1: PackedFeatureId,
+    /// The feature (vertex, edge, or face) of the second collider’s sub-shape involved in the contact.
+    pub feature2: PackedFeatureId,
+    /// The index, in the `FeatureMaterials` of the first collider, of the material of its
+    /// sub-shape involved in the contact.
+    pub feature_material1: Option<u32>,
+    /// The index, in the `FeatureMaterials` of the second collider, of the material of its
+    /// sub-shape involved in the contact.
+    pub feature_material2: Option<u32>,
+    /// The impulse applied at this contact point during the timestep, along the contact normal.
+    pub impulse: Real,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// Event emitted when a collider passes through a sensor during a single timestep, without
+/// intersecting it at the beginning nor at the end of the timestep.
+pub struct SensorPassThroughEvent {
+    /// The first collider involved in the pass-through.
+    pub collider1: ColliderHandle,
+    /// The second collider involved in the pass-through.
+    pub collider2: ColliderHandle,
+    /// The time elapsed since the beginning of the timestep when both colliders started
+    /// intersecting.
+    pub time_of_entry: Real,
+}
+
+impl SensorPassThroughEvent {
+    /// The collision started and collision stopped events matching this pass-through.
+    pub fn collision_events(&self) -> [CollisionEvent; 2] {
+        [
+            CollisionEvent::Started(self.collider1, self.collider2, CollisionEventFlags::SENSOR),
+            CollisionEvent::Stopped(self.collider1, self.collider2, CollisionEventFlags::SENSOR),
+        ]
+    }
+}
+
+pub(crate) use self::broad_phase_multi_sap::{BroadPhaseState, SAPProxyIndex};
+pub(crate) use self::narrow_phase::{ContactManifoldIndex, NarrowPhaseState};
 pub(crate) use parry::partitioning::Qbvh;
 pub use parry::shape::*;
 
@@ -200,8 +327,10 @@ mod collider_components;
 mod contact_pair;
 mod interaction_graph;
 mod interaction_groups;
+mod material_pair_table;
 mod narrow_phase;
 
 mod broad_phase_qbvh;
 mod collider;
 mod collider_set;
+mod fluid_volume;
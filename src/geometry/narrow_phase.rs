@@ -2,25 +2,29 @@
 use rayon::prelude::*;
 
 use crate::data::graph::EdgeIndex;
-use crate::data::Coarena;
+use crate::data::{Coarena, Index};
 use crate::dynamics::{
     CoefficientCombineRule, ImpulseJointSet, IslandManager, RigidBodyDominance, RigidBodySet,
     RigidBodyType,
 };
 use crate::geometry::{
-    BroadPhasePairEvent, ColliderChanges, ColliderGraphIndex, ColliderHandle, ColliderPair,
-    ColliderSet, CollisionEvent, ContactData, ContactManifold, ContactManifoldData, ContactPair,
-    InteractionGraph, IntersectionPair, SolverContact, SolverFlags, TemporaryInteractionIndex,
+    BroadPhasePairEvent, Collider, ColliderChanges, ColliderGraphIndex, ColliderHandle,
+    ColliderMaterial, ColliderPair, ColliderSet, CollisionEvent, ContactData, ContactManifold,
+    ContactManifoldData, ContactPair, ContactSoftness, InteractionGraph, IntersectionPair,
+    MaterialPairTable, SolverContact, SolverFlags, TemporaryInteractionIndex,
 };
-use crate::math::{Real, Vector};
+use crate::math::{Isometry, Real, Vector};
 use crate::pipeline::{
     ActiveEvents, ActiveHooks, ContactModificationContext, EventHandler, PairFilterContext,
     PhysicsHooks,
 };
 use crate::prelude::{CollisionEventFlags, MultibodyJointSet};
+use crate::utils::WBasis;
+#[cfg(feature = "dim3")]
+use na::Unit;
 use parry::query::{DefaultQueryDispatcher, PersistentQueryDispatcher};
 use parry::utils::IsometryOpt;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -58,10 +62,24 @@ pub struct NarrowPhase {
     contact_graph: InteractionGraph<ColliderHandle, ContactPair>,
     intersection_graph: InteractionGraph<ColliderHandle, IntersectionPair>,
     graph_indices: Coarena<ColliderGraphIndices>,
+    material_pairs: MaterialPairTable,
 }
 
 pub(crate) type ContactManifoldIndex = usize;
 
+/// The part of the narrow-phase state modified by a simulation step.
+///
+/// This contains the contact and intersection pairs, including their contact points, their
+/// impulses (used for warm-starting), and their event flags. The query dispatcher and the
+/// material-pair table are not captured.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+pub(crate) struct NarrowPhaseState {
+    contact_graph: InteractionGraph<ColliderHandle, ContactPair>,
+    intersection_graph: InteractionGraph<ColliderHandle, IntersectionPair>,
+    graph_indices: Coarena<ColliderGraphIndices>,
+}
+
 impl Default for NarrowPhase {
     fn default() -> Self {
         Self::new()
@@ -84,6 +102,7 @@ impl NarrowPhase {
             contact_graph: InteractionGraph::new(),
             intersection_graph: InteractionGraph::new(),
             graph_indices: Coarena::new(),
+            material_pairs: MaterialPairTable::new(),
         }
     }
 
@@ -95,6 +114,37 @@ impl NarrowPhase {
         &*self.query_dispatcher
     }
 
+    /// The table giving the properties of the contacts between pairs of collider materials.
+    pub fn material_pairs(&self) -> &MaterialPairTable {
+        &self.material_pairs
+    }
+
+    /// The mutable table giving the properties of the contacts between pairs of collider materials.
+    ///
+    /// Modifications are taken into account the next time the contacts are computed.
+    pub fn material_pairs_mut(&mut self) -> &mut MaterialPairTable {
+        &mut self.material_pairs
+    }
+
+    /// Copies the part of this narrow-phase modified by a simulation step into `state`.
+    ///
+    /// This reuses the buffers already allocated by `state`.
+    pub(crate) fn save_state(&self, state: &mut NarrowPhaseState) {
+        state.contact_graph.clone_from(&self.contact_graph);
+        state
+            .intersection_graph
+            .clone_from(&self.intersection_graph);
+        state.graph_indices.clone_from(&self.graph_indices);
+    }
+
+    /// Restores, in-place, the state saved by [`Self::save_state`].
+    pub(crate) fn restore_state(&mut self, state: &NarrowPhaseState) {
+        self.contact_graph.clone_from(&state.contact_graph);
+        self.intersection_graph
+            .clone_from(&state.intersection_graph);
+        self.graph_indices.clone_from(&state.graph_indices);
+    }
+
     /// The contact graph containing all contact pairs and their contact information.
     pub fn contact_graph(&self) -> &InteractionGraph<ColliderHandle, ContactPair> {
         &self.contact_graph
@@ -249,6 +299,75 @@ impl NarrowPhase {
             .map(|e| (e.0, e.1, e.2.intersecting))
     }
 
+    /// Registers the pairs found by a broad-phase rebuilt from scratch, and removes the pairs
+    /// not found by this rebuild.
+    ///
+    /// The pairs known by both are preserved, with their contact manifolds. This is meant to
+    /// be used after [`BroadPhase::shift_origin`](crate::geometry::BroadPhase::shift_origin).
+    pub fn replace_pairs(
+        &mut self,
+        mut islands: Option<&mut IslandManager>,
+        colliders: &ColliderSet,
+        bodies: &mut RigidBodySet,
+        broad_phase_events: &[BroadPhasePairEvent],
+        events: &dyn EventHandler,
+    ) {
+        fn key(handle1: ColliderHandle, handle2: ColliderHandle) -> (Index, Index) {
+            (handle1.0.min(handle2.0), handle1.0.max(handle2.0))
+        }
+
+        let found_pairs: HashSet<_> = broad_phase_events
+            .iter()
+            .filter_map(|event| match event {
+                BroadPhasePairEvent::AddPair(pair) => Some(key(pair.collider1, pair.collider2)),
+                BroadPhasePairEvent::DeletePair(_) => None,
+            })
+            .collect();
+        let intersection_nodes = &self.intersection_graph.graph.nodes;
+        let known_pairs = self
+            .contact_graph
+            .graph
+            .edges
+            .iter()
+            .map(|edge| (edge.weight.collider1, edge.weight.collider2))
+            .chain(self.intersection_graph.graph.edges.iter().map(|edge| {
+                (
+                    intersection_nodes[edge.source().index()].weight,
+                    intersection_nodes[edge.target().index()].weight,
+                )
+            }));
+        let removed_pairs: Vec<_> = known_pairs
+            .filter(|(handle1, handle2)| !found_pairs.contains(&key(*handle1, *handle2)))
+            .map(|(handle1, handle2)| {
+                BroadPhasePairEvent::DeletePair(ColliderPair::new(handle1, handle2))
+            })
+            .collect();
+
+        self.register_pairs(
+            islands.as_deref_mut(),
+            colliders,
+            bodies,
+            &removed_pairs,
+            events,
+        );
+        self.register_pairs(islands, colliders, bodies, broad_phase_events, events);
+    }
+
+    /// Translates the world-space contact data cached by this narrow-phase by `translation`.
+    ///
+    /// The contact manifolds are expressed in the local-space of the colliders, so they remain
+    /// valid. Only the world-space solver contacts need to be moved. This is meant to be used
+    /// for shifting the origin of the whole simulation, see `PhysicsWorld::shift_origin`.
+    pub fn shift_origin(&mut self, translation: &Vector<Real>) {
+        for pair in self.contact_graph.graph.edges.iter_mut() {
+            for manifold in &mut pair.weight.manifolds {
+                for contact in &mut manifold.data.solver_contacts {
+                    contact.point += translation;
+                }
+            }
+        }
+    }
+
     // #[cfg(feature = "parallel")]
     // pub(crate) fn contact_pairs_vec_mut(&mut self) -> &mut Vec<ContactPair> {
     //     &mut self.contact_graph.interactions
@@ -792,6 +911,7 @@ impl NarrowPhase {
         }
 
         let query_dispatcher = &*self.query_dispatcher;
+        let material_pairs = &self.material_pairs;
 
         // TODO: don't iterate on all the edges.
         par_iter_mut!(&mut self.contact_graph.graph.edges).for_each(|edge| {
@@ -900,17 +1020,12 @@ impl NarrowPhase {
                     &mut pair.workspace,
                 );
 
-                let friction = CoefficientCombineRule::combine(
-                    co1.material.friction,
-                    co2.material.friction,
-                    co1.material.friction_combine_rule as u8,
-                    co2.material.friction_combine_rule as u8,
-                );
-                let restitution = CoefficientCombineRule::combine(
-                    co1.material.restitution,
-                    co2.material.restitution,
-                    co1.material.restitution_combine_rule as u8,
-                    co2.material.restitution_combine_rule as u8,
+                let pair_material = CombinedMaterial::new(
+                    &co1.material,
+                    &co1.pos.0,
+                    &co2.material,
+                    &co2.pos.0,
+                    material_pairs,
                 );
 
                 let zero = RigidBodyDominance(0); // The value doesn't matter, it will be MAX because of the effective groups.
@@ -931,10 +1046,62 @@ impl NarrowPhase {
                     manifold.data.rigid_body1 = co1.parent.map(|p| p.handle);
                     manifold.data.rigid_body2 = co2.parent.map(|p| p.handle);
                     manifold.data.solver_flags = solver_flags;
+
+                    // Use the materials of the contacted triangles or heightfield cells, if any.
+                    manifold.data.feature_material1 =
+                        co1.feature_materials.as_deref().and_then(|materials| {
+                            materials.material_index(&*co1.shape, manifold.subshape1)
+                        });
+                    manifold.data.feature_material2 =
+                        co2.feature_materials.as_deref().and_then(|materials| {
+                            materials.material_index(&*co2.shape, manifold.subshape2)
+                        });
+                    let feature_material;
+                    let material = if manifold.data.feature_material1.is_some()
+                        || manifold.data.feature_material2.is_some()
+                    {
+                        feature_material = CombinedMaterial::new(
+                            feature_material_or_default(co1, manifold.data.feature_material1),
+                            &co1.pos.0,
+                            feature_material_or_default(co2, manifold.data.feature_material2),
+                            &co2.pos.0,
+                            material_pairs,
+                        );
+                        &feature_material
+                    } else {
+                        &pair_material
+                    };
+                    let (friction, restitution) = (material.friction, material.restitution);
+
+                    manifold.data.rolling_friction = material.rolling_friction;
+                    manifold.data.spinning_friction = material.spinning_friction;
+                    manifold.data.softness = material.softness;
                     manifold.data.relative_dominance = dominance1.effective_group(&rb_type1)
                         - dominance2.effective_group(&rb_type2);
                     manifold.data.normal = world_pos1 * manifold.local_n1;
 
+                    // Resolve the anisotropic friction along the tangent plane of this manifold.
+                    #[cfg(feature = "dim2")]
+                    let friction = if material.is_anisotropic() {
+                        let tangent = manifold.data.normal.orthonormal_vector();
+                        material.friction_along(&tangent)
+                    } else {
+                        friction
+                    };
+                    #[cfg(feature = "dim3")]
+                    let friction = {
+                        let normal = manifold.data.normal;
+                        let (direction, friction, secondary_friction) =
+                            material.friction_on_tangent_plane(&normal, friction);
+                        manifold.data.friction_direction = direction;
+                        manifold.data.secondary_friction_ratio = if friction > 0.0 {
+                            secondary_friction / friction
+                        } else {
+                            1.0
+                        };
+                        friction
+                    };
+
                     // Generate solver contacts.
                     for (contact_id, contact) in manifold.points.iter().enumerate() {
                         assert!(
@@ -1077,3 +1244,158 @@ impl NarrowPhase {
         }
     }
 }
+
+/// The coefficients of the contacts between two collider materials.
+struct CombinedMaterial<'a> {
+    material1: &'a ColliderMaterial,
+    pos1: &'a Isometry<Real>,
+    material2: &'a ColliderMaterial,
+    pos2: &'a Isometry<Real>,
+    friction: Real,
+    restitution: Real,
+    rolling_friction: Real,
+    spinning_friction: Real,
+    softness: Option<ContactSoftness>,
+    /// The world-space primary friction directions of the materials with an anisotropic friction.
+    anisotropic_directions: [Option<Vector<Real>>; 2],
+}
+
+impl<'a> CombinedMaterial<'a> {
+    fn new(
+        material1: &'a ColliderMaterial,
+        pos1: &'a Isometry<Real>,
+        material2: &'a ColliderMaterial,
+        pos2: &'a Isometry<Real>,
+        material_pairs: &MaterialPairTable,
+    ) -> Self {
+        let mut result = Self {
+            material1,
+            pos1,
+            material2,
+            pos2,
+            friction: CoefficientCombineRule::combine(
+                material1.friction,
+                material2.friction,
+                material1.friction_combine_rule as u8,
+                material2.friction_combine_rule as u8,
+            ),
+            restitution: CoefficientCombineRule::combine(
+                material1.restitution,
+                material2.restitution,
+                material1.restitution_combine_rule as u8,
+                material2.restitution_combine_rule as u8,
+            ),
+            rolling_friction: CoefficientCombineRule::combine(
+                material1.rolling_friction,
+                material2.rolling_friction,
+                material1.rolling_friction_combine_rule as u8,
+                material2.rolling_friction_combine_rule as u8,
+            ),
+            spinning_friction: CoefficientCombineRule::combine(
+                material1.spinning_friction,
+                material2.spinning_friction,
+                material1.spinning_friction_combine_rule as u8,
+                material2.spinning_friction_combine_rule as u8,
+            ),
+            softness: ContactSoftness::combine(material1, material2),
+            anisotropic_directions: [
+                material1
+                    .anisotropic_friction
+                    .map(|anisotropic| pos1 * *anisotropic.local_direction),
+                material2
+                    .anisotropic_friction
+                    .map(|anisotropic| pos2 * *anisotropic.local_direction),
+            ],
+        };
+
+        // The material pair table overrides the combine rules.
+        if let Some(pair) = material1
+            .material_id
+            .zip(material2.material_id)
+            .and_then(|(id1, id2)| material_pairs.get(id1, id2))
+        {
+            result.friction = pair.friction;
+            result.restitution = pair.restitution;
+            result.softness = pair.softness.or(result.softness);
+            result.anisotropic_directions = [None; 2];
+        }
+
+        result
+    }
+
+    fn is_anisotropic(&self) -> bool {
+        self.anisotropic_directions.iter().any(Option::is_some)
+    }
+
+    /// The friction direction and the friction coefficients along and across it, on the tangent
+    /// plane with the given normal.
+    ///
+    /// The combined friction is approximated by an ellipse aligned with the direction having the
+    /// largest friction among the projections of the primary directions of both materials and
+    /// their orthogonal directions. The friction direction is `None` if the friction is
+    /// isotropic on the tangent plane.
+    #[cfg(feature = "dim3")]
+    fn friction_on_tangent_plane(
+        &self,
+        normal: &Vector<Real>,
+        isotropic_friction: Real,
+    ) -> (Option<Vector<Real>>, Real, Real) {
+        if !self.is_anisotropic() {
+            return (None, isotropic_friction, isotropic_friction);
+        }
+
+        let mut result: Option<(Vector<Real>, Real, Real)> = None;
+
+        for direction in self.anisotropic_directions.iter().flatten() {
+            let tangent = direction - normal * normal.dot(direction);
+
+            // Skip the primary directions parallel to the normal.
+            if let Some(tangent) = Unit::try_new(tangent, 1.0e-5) {
+                let bitangent = normal.cross(&tangent);
+                let (friction, secondary_friction) = (
+                    self.friction_along(&tangent),
+                    self.friction_along(&bitangent),
+                );
+                let candidate = if friction >= secondary_friction {
+                    (*tangent, friction, secondary_friction)
+                } else {
+                    (bitangent, secondary_friction, friction)
+                };
+
+                match result {
+                    Some((_, best_friction, _)) if best_friction >= candidate.1 => {}
+                    _ => result = Some(candidate),
+                }
+            }
+        }
+
+        match result {
+            Some((direction, friction, secondary_friction)) => {
+                (Some(direction), friction, secondary_friction)
+            }
+            None => {
+                // All the primary directions are parallel to the normal.
+                let friction = self.friction_along(&normal.orthonormal_vector());
+                (None, friction, friction)
+            }
+        }
+    }
+
+    /// Combines the friction coefficients of both materials along the given world-space unit
+    /// tangent direction.
+    fn friction_along(&self, dir: &Vector<Real>) -> Real {
+        CoefficientCombineRule::combine(
+            self.material1.friction_along(self.pos1, dir),
+            self.material2.friction_along(self.pos2, dir),
+            self.material1.friction_combine_rule as u8,
+            self.material2.friction_combine_rule as u8,
+        )
+    }
+}
+
+/// The material of the given feature material index of a collider, or the collider’s material.
+fn feature_material_or_default(co: &Collider, index: Option<u32>) -> &ColliderMaterial {
+    index
+        .and_then(|index| co.feature_materials.as_ref()?.materials.get(index as usize))
+        .unwrap_or(&co.material)
+}
@@ -1,6 +1,9 @@
-use crate::dynamics::RigidBodySet;
-use crate::geometry::{ColliderSet, CollisionEvent, ContactForceEvent, ContactPair};
-use crate::math::Real;
+use crate::dynamics::{JointBrokenEvent, RigidBodySet};
+use crate::geometry::{
+    ColliderSet, CollisionEvent, ContactForceEvent, ContactImpactEvent, ContactPair,
+    ContactPersistedEvent, SensorPassThroughEvent,
+};
+use crate::math::{Real, Vector};
 use crossbeam::channel::Sender;
 
 bitflags::bitflags! {
@@ -13,6 +16,12 @@ bitflags::bitflags! {
         /// If set, Rapier will call `EventHandler::handle_contact_force_event`
         /// whenever relevant for this collider.
         const CONTACT_FORCE_EVENTS = 0b0010;
+        /// If set, Rapier will call `EventHandler::handle_contact_persisted_event`
+        /// at each timestep for each contact pair with active contacts involving this collider.
+        const CONTACT_PERSISTED_EVENTS = 0b0100;
+        /// If set, Rapier will call `EventHandler::handle_contact_impact_event`
+        /// whenever relevant for this collider.
+        const CONTACT_IMPACT_EVENTS = 0b1000;
     }
 }
 
@@ -66,6 +75,75 @@ pub trait EventHandler: Send + Sync {
         contact_pair: &ContactPair,
         total_force_magnitude: Real,
     );
+
+    /// Handle a contact persisted event.
+    ///
+    /// A contact persisted event is generated at each timestep for each contact pair with
+    /// active contacts simulated by the solver, if at least one of the involved colliders has
+    /// the `ActiveEvents::CONTACT_PERSISTED_EVENTS` flag set. This includes the timestep the
+    /// contact started. No event is generated for contact pairs between sleeping rigid-bodies.
+    ///
+    /// The event is generated once per timestep, after all its CCD substeps. The impulses of
+    /// `contact_pair` are the ones applied during the last substep of this timestep, whereas
+    /// `total_impulse` and `total_impulse_magnitude` are summed over all its substeps.
+    fn handle_contact_persisted_event(
+        &self,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        _contact_pair: &ContactPair,
+        _total_impulse: Vector<Real>,
+        _total_impulse_magnitude: Real,
+    ) {
+    }
+
+    /// Handle a contact impact event.
+    ///
+    /// A contact impact event is generated for each contact point where the two colliders
+    /// approach each other, before the constraints solver resolves the contact, with a normal
+    /// velocity `> Collider::min_impact_velocity` of any of these colliders with the
+    /// `ActiveEvents::CONTACT_IMPACT_EVENTS` flag set. Contact points that won’t be reached
+    /// during the timestep at this velocity are ignored.
+    fn handle_contact_impact_event(
+        &self,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        _event: ContactImpactEvent,
+    ) {
+    }
+
+    /// Handle a sensor pass-through event.
+    ///
+    /// A sensor pass-through event is generated whenever a collider crosses a sensor during a
+    /// single timestep, without intersecting it at the beginning nor at the end of the timestep.
+    /// This is detected only if the rigid-body of that collider has CCD enabled, or if the sensor
+    /// has `Collider::is_sensor_ccd_enabled` set. At least one of the involved colliders must have
+    /// the `ActiveEvents::COLLISION_EVENTS` flag set.
+    ///
+    /// By default, this calls `Self::handle_collision_event` with the collision started and
+    /// collision stopped events of this pass-through.
+    fn handle_sensor_pass_through_event(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        event: SensorPassThroughEvent,
+    ) {
+        for collision_event in event.collision_events() {
+            self.handle_collision_event(bodies, colliders, collision_event, None);
+        }
+    }
+
+    /// Handle a joint broken event.
+    ///
+    /// A joint broken event is generated whenever the force or torque applied by a joint
+    /// exceeds its `GenericJoint::break_force` or `GenericJoint::break_torque`. The broken
+    /// impulse joint is disabled, and the broken multibody joint is removed from its set.
+    fn handle_joint_broken_event(
+        &self,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        _event: JointBrokenEvent,
+    ) {
+    }
 }
 
 impl EventHandler for () {
@@ -93,6 +171,10 @@ impl EventHandler for () {
 pub struct ChannelEventCollector {
     collision_event_sender: Sender<CollisionEvent>,
     contact_force_event_sender: Sender<ContactForceEvent>,
+    contact_persisted_event_sender: Option<Sender<ContactPersistedEvent>>,
+    contact_impact_event_sender: Option<Sender<ContactImpactEvent>>,
+    joint_broken_event_sender: Option<Sender<JointBrokenEvent>>,
+    sensor_pass_through_event_sender: Option<Sender<SensorPassThroughEvent>>,
 }
 
 impl ChannelEventCollector {
@@ -104,8 +186,50 @@ impl ChannelEventCollector {
         Self {
             collision_event_sender,
             contact_force_event_sender,
+            contact_persisted_event_sender: None,
+            contact_impact_event_sender: None,
+            joint_broken_event_sender: None,
+            sensor_pass_through_event_sender: None,
         }
     }
+
+    /// Also collects the contact persisted events into the given crossbeam channel.
+    pub fn with_contact_persisted_events(
+        mut self,
+        contact_persisted_event_sender: Sender<ContactPersistedEvent>,
+    ) -> Self {
+        self.contact_persisted_event_sender = Some(contact_persisted_event_sender);
+        self
+    }
+
+    /// Also collects the contact impact events into the given crossbeam channel.
+    pub fn with_contact_impact_events(
+        mut self,
+        contact_impact_event_sender: Sender<ContactImpactEvent>,
+    ) -> Self {
+        self.contact_impact_event_sender = Some(contact_impact_event_sender);
+        self
+    }
+
+    /// Also collects the joint broken events into the given crossbeam channel.
+    pub fn with_joint_broken_events(
+        mut self,
+        joint_broken_event_sender: Sender<JointBrokenEvent>,
+    ) -> Self {
+        self.joint_broken_event_sender = Some(joint_broken_event_sender);
+        self
+    }
+
+    /// Also collects the sensor pass-through events into the given crossbeam channel.
+    ///
+    /// Their collision started and collision stopped events are collected as usual.
+    pub fn with_sensor_pass_through_events(
+        mut self,
+        sensor_pass_through_event_sender: Sender<SensorPassThroughEvent>,
+    ) -> Self {
+        self.sensor_pass_through_event_sender = Some(sensor_pass_through_event_sender);
+        self
+    }
 }
 
 impl EventHandler for ChannelEventCollector {
@@ -130,4 +254,59 @@ impl EventHandler for ChannelEventCollector {
         let result = ContactForceEvent::from_contact_pair(dt, contact_pair, total_force_magnitude);
         let _ = self.contact_force_event_sender.send(result);
     }
+
+    fn handle_contact_persisted_event(
+        &self,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        contact_pair: &ContactPair,
+        total_impulse: Vector<Real>,
+        total_impulse_magnitude: Real,
+    ) {
+        if let Some(sender) = &self.contact_persisted_event_sender {
+            let result = ContactPersistedEvent {
+                total_impulse,
+                total_impulse_magnitude,
+                ..ContactPersistedEvent::from_contact_pair(contact_pair)
+            };
+            let _ = sender.send(result);
+        }
+    }
+
+    fn handle_contact_impact_event(
+        &self,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        event: ContactImpactEvent,
+    ) {
+        if let Some(sender) = &self.contact_impact_event_sender {
+            let _ = sender.send(event);
+        }
+    }
+
+    fn handle_sensor_pass_through_event(
+        &self,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        event: SensorPassThroughEvent,
+    ) {
+        for collision_event in event.collision_events() {
+            let _ = self.collision_event_sender.send(collision_event);
+        }
+
+        if let Some(sender) = &self.sensor_pass_through_event_sender {
+            let _ = sender.send(event);
+        }
+    }
+
+    fn handle_joint_broken_event(
+        &self,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        event: JointBrokenEvent,
+    ) {
+        if let Some(sender) = &self.joint_broken_event_sender {
+            let _ = sender.send(event);
+        }
+    }
 }
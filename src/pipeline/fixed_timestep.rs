@@ -0,0 +1,322 @@
+use crate::data::Coarena;
+use crate::dynamics::{IslandManager, RigidBodyHandle, RigidBodyPosition, RigidBodySet};
+use crate::math::{Isometry, Real};
+use crate::pipeline::{EventHandler, PhysicsHooks, PhysicsWorld};
+
+/// A driver running fixed-length timesteps from a variable amount of elapsed time.
+///
+/// The elapsed wall-clock time given to [`FixedTimestepAccumulator::advance`] is accumulated,
+/// and as many timesteps of length `IntegrationParameters::dt` as possible are executed. The
+/// time left in the accumulator after this is used to interpolate the rigid-body poses between
+/// their positions before and after the last timestep, for rendering purpose.
+#[derive(Clone, Debug)]
+pub struct FixedTimestepAccumulator {
+    /// The maximum number of timesteps executed by a single call to `advance`
+    /// (default: `8`).
+    ///
+    /// If more timesteps would be needed to catch up with the elapsed time, the
+    /// extra time is dropped. This avoids the "spiral of death" where the simulation
+    /// takes more time to compute than the time it simulates.
+    pub max_substeps: usize,
+    accumulator: Real,
+    dt: Real,
+    poses: Coarena<RigidBodyPosition>,
+    recorded_bodies: Vec<RigidBodyHandle>,
+}
+
+impl Default for FixedTimestepAccumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FixedTimestepAccumulator {
+    /// Creates a new accumulator with no accumulated time.
+    pub fn new() -> Self {
+        Self {
+            max_substeps: 8,
+            accumulator: 0.0,
+            dt: 0.0,
+            poses: Coarena::new(),
+            recorded_bodies: vec![],
+        }
+    }
+
+    /// Sets the maximum number of timesteps executed by a single call to `advance`.
+    pub fn with_max_substeps(mut self, max_substeps: usize) -> Self {
+        self.max_substeps = max_substeps;
+        self
+    }
+
+    /// The amount of time accumulated but not simulated yet.
+    pub fn accumulated_time(&self) -> Real {
+        self.accumulator
+    }
+
+    /// Resets the accumulated time and the recorded poses to zero.
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+        self.clear_recorded_poses();
+    }
+
+    /// The interpolation factor, between `0.0` and `1.0`, between the rigid-body poses before
+    /// and after the last timestep.
+    pub fn alpha(&self) -> Real {
+        if self.dt > 0.0 {
+            (self.accumulator / self.dt).min(1.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// Accumulates `elapsed` time, and executes as many fixed timesteps on `world` as needed.
+    ///
+    /// Returns the number of timesteps actually executed.
+    pub fn advance(
+        &mut self,
+        world: &mut PhysicsWorld,
+        elapsed: Real,
+        hooks: &dyn PhysicsHooks,
+        events: &dyn EventHandler,
+    ) -> usize {
+        let PhysicsWorld {
+            gravity,
+            integration_parameters,
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+            query_pipeline,
+            force_generators,
+            pipeline,
+        } = world;
+
+        self.advance_with(
+            integration_parameters.dt,
+            elapsed,
+            islands,
+            bodies,
+            |islands, bodies| {
+                pipeline.step_with_force_generators(
+                    gravity,
+                    integration_parameters,
+                    islands,
+                    broad_phase,
+                    narrow_phase,
+                    bodies,
+                    colliders,
+                    impulse_joints,
+                    multibody_joints,
+                    ccd_solver,
+                    Some(query_pipeline),
+                    force_generators,
+                    hooks,
+                    events,
+                )
+            },
+        )
+    }
+
+    /// Accumulates `elapsed` time, and executes as many fixed timesteps of length `dt` as
+    /// needed by calling `step`.
+    ///
+    /// This is the equivalent of [`Self::advance`] for simulations stepped with a
+    /// [`PhysicsPipeline`](crate::pipeline::PhysicsPipeline) directly: `step` must execute one
+    /// timestep with the given island manager and rigid-body set, with an
+    /// `IntegrationParameters::dt` equal to `dt`.
+    ///
+    /// Returns the number of timesteps actually executed.
+    pub fn advance_with(
+        &mut self,
+        dt: Real,
+        elapsed: Real,
+        islands: &mut IslandManager,
+        bodies: &mut RigidBodySet,
+        mut step: impl FnMut(&mut IslandManager, &mut RigidBodySet),
+    ) -> usize {
+        self.dt = dt;
+
+        if self.dt <= 0.0 {
+            return 0;
+        }
+
+        self.accumulator += elapsed.max(0.0);
+        let mut num_steps = 0;
+
+        while self.accumulator >= self.dt && num_steps < self.max_substeps {
+            self.record_poses_before_step(islands, bodies);
+            step(islands, bodies);
+            self.accumulator -= self.dt;
+            num_steps += 1;
+        }
+
+        if num_steps == self.max_substeps && self.accumulator >= self.dt {
+            // Drop the time we couldn’t catch up with.
+            self.accumulator %= self.dt;
+        }
+
+        if num_steps > 0 {
+            self.record_poses_after_step(islands, bodies);
+        }
+
+        num_steps
+    }
+
+    /// The position of the given rigid-body, interpolated between its positions before
+    /// and after the last timestep using [`Self::alpha`].
+    ///
+    /// If the rigid-body didn’t move during the last timestep, or if it was modified by
+    /// the user since then, its current position is returned as-is.
+    pub fn interpolated_position(
+        &self,
+        handle: RigidBodyHandle,
+        bodies: &RigidBodySet,
+    ) -> Option<Isometry<Real>> {
+        let rb = bodies.get(handle)?;
+
+        match self.poses.get(handle.0) {
+            Some(pose) if pose.next_position == *rb.position() => {
+                Some(pose.interpolate(self.alpha()))
+            }
+            _ => Some(*rb.position()),
+        }
+    }
+
+    fn clear_recorded_poses(&mut self) {
+        for handle in self.recorded_bodies.drain(..) {
+            self.poses.remove(handle.0, RigidBodyPosition::default());
+        }
+    }
+
+    fn record_poses_before_step(&mut self, islands: &IslandManager, bodies: &RigidBodySet) {
+        self.clear_recorded_poses();
+
+        for handle in islands.iter_active_bodies() {
+            if let Some(rb) = bodies.get(handle) {
+                self.poses
+                    .insert(handle.0, RigidBodyPosition::from(*rb.position()));
+                self.recorded_bodies.push(handle);
+            }
+        }
+    }
+
+    fn record_poses_after_step(&mut self, islands: &IslandManager, bodies: &RigidBodySet) {
+        for handle in &self.recorded_bodies {
+            if let (Some(pose), Some(rb)) = (self.poses.get_mut(handle.0), bodies.get(*handle)) {
+                pose.next_position = *rb.position();
+            }
+        }
+
+        // The rigid-bodies woken up during the last timestep weren’t recorded before it. They
+        // didn’t move while sleeping, so their previous pose is deduced from their velocity.
+        for handle in islands.iter_active_bodies() {
+            if self.poses.get(handle.0).is_some() {
+                continue;
+            }
+
+            if let Some(rb) = bodies.get(handle) {
+                let previous_position =
+                    rb.vels
+                        .integrate(-self.dt, rb.position(), &rb.mprops.local_mprops.local_com);
+                self.poses.insert(
+                    handle.0,
+                    RigidBodyPosition {
+                        position: previous_position,
+                        next_position: *rb.position(),
+                    },
+                );
+                self.recorded_bodies.push(handle);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FixedTimestepAccumulator;
+    use crate::dynamics::{
+        CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
+        RigidBodyBuilder, RigidBodySet,
+    };
+    use crate::geometry::{BroadPhase, ColliderBuilder, ColliderSet, NarrowPhase};
+    use crate::math::Vector;
+    use crate::pipeline::{PhysicsPipeline, PhysicsWorld};
+
+    #[test]
+    fn fixed_timestep_interpolation() {
+        let mut world = PhysicsWorld::new();
+        let handle = world.insert_rigid_body(RigidBodyBuilder::dynamic().linvel(Vector::x()));
+        let dt = world.integration_parameters.dt;
+        let mut accumulator = FixedTimestepAccumulator::new().with_max_substeps(4);
+
+        assert_eq!(accumulator.advance(&mut world, dt * 0.5, &(), &()), 0);
+        assert_eq!(accumulator.advance(&mut world, dt * 2.0, &(), &()), 2);
+        assert!((accumulator.alpha() - 0.5).abs() < 1.0e-3);
+
+        let current = world.bodies[handle].translation().x;
+        let interpolated = accumulator
+            .interpolated_position(handle, &world.bodies)
+            .unwrap();
+        assert!((interpolated.translation.vector.x - (current - dt * 0.5)).abs() < 1.0e-4);
+
+        // Too much elapsed time: the number of steps is capped.
+        assert_eq!(accumulator.advance(&mut world, dt * 10.0, &(), &()), 4);
+        assert!(accumulator.accumulated_time() < dt);
+    }
+
+    #[test]
+    fn fixed_timestep_with_physics_pipeline() {
+        let mut pipeline = PhysicsPipeline::new();
+        let gravity = Vector::y() * -9.81;
+        let params = IntegrationParameters::default();
+        let mut islands = IslandManager::new();
+        let mut broad_phase = BroadPhase::new();
+        let mut narrow_phase = NarrowPhase::new();
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut ccd_solver = CCDSolver::new();
+        let mut accumulator = FixedTimestepAccumulator::new();
+
+        let handle = bodies.insert(RigidBodyBuilder::dynamic().sleeping(true));
+        colliders.insert_with_parent(ColliderBuilder::ball(0.5), handle, &mut bodies);
+        // The rigid-body is woken up during the next timestep.
+        bodies[handle].wake_up(true);
+
+        let num_steps = accumulator.advance_with(
+            params.dt,
+            params.dt * 1.5,
+            &mut islands,
+            &mut bodies,
+            |islands, bodies| {
+                pipeline.step(
+                    &gravity,
+                    &params,
+                    islands,
+                    &mut broad_phase,
+                    &mut narrow_phase,
+                    bodies,
+                    &mut colliders,
+                    &mut impulse_joints,
+                    &mut multibody_joints,
+                    &mut ccd_solver,
+                    None,
+                    &(),
+                    &(),
+                )
+            },
+        );
+        assert_eq!(num_steps, 1);
+
+        // The woken-up rigid-body is interpolated from its pose before the timestep.
+        let current = bodies[handle].translation().y;
+        assert!(current < 0.0);
+        let interpolated = accumulator.interpolated_position(handle, &bodies).unwrap();
+        assert!((interpolated.translation.vector.y - current * 0.5).abs() < 1.0e-5);
+    }
+}
@@ -2,8 +2,14 @@
 
 pub use collision_pipeline::CollisionPipeline;
 pub use event_handler::{ActiveEvents, ChannelEventCollector, EventHandler};
-pub use physics_hooks::{ActiveHooks, ContactModificationContext, PairFilterContext, PhysicsHooks};
+pub use fixed_timestep::FixedTimestepAccumulator;
+pub use physics_hooks::{
+    ActiveHooks, ContactModificationContext, PairFilterContext, PhysicsHooks, StepStageContext,
+};
 pub use physics_pipeline::PhysicsPipeline;
+pub use physics_world::PhysicsWorld;
+pub use physics_world_batch::{BatchObservations, PhysicsWorldBatch};
+pub use physics_world_snapshot::PhysicsWorldSnapshot;
 pub use query_pipeline::{QueryFilter, QueryFilterFlags, QueryPipeline, QueryPipelineMode};
 
 #[cfg(feature = "debug-render")]
@@ -14,10 +20,18 @@ pub use self::debug_render_pipeline::{
 
 mod collision_pipeline;
 mod event_handler;
+mod fixed_timestep;
 mod physics_hooks;
 mod physics_pipeline;
+mod physics_world;
+mod physics_world_batch;
+mod physics_world_hash;
+mod physics_world_snapshot;
 mod query_pipeline;
 mod user_changes;
 
+#[cfg(test)]
+pub(crate) mod test_utils;
+
 #[cfg(feature = "debug-render")]
 mod debug_render_pipeline;
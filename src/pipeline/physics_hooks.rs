@@ -1,4 +1,4 @@
-use crate::dynamics::{RigidBodyHandle, RigidBodySet};
+use crate::dynamics::{IslandManager, RigidBodyHandle, RigidBodySet};
 use crate::geometry::{ColliderHandle, ColliderSet, ContactManifold, SolverContact, SolverFlags};
 use crate::math::{Real, Vector};
 use na::ComplexField;
@@ -45,6 +45,21 @@ pub struct ContactModificationContext<'a> {
     pub user_data: &'a mut u32,
 }
 
+/// Context given to the physics hooks called at the different stages of a timestep.
+pub struct StepStageContext<'a> {
+    /// The length of the time interval simulated by the current stage.
+    ///
+    /// This is smaller than `IntegrationParameters::dt` if the timestep is split into
+    /// CCD substeps or solver substeps.
+    pub dt: Real,
+    /// The set of rigid-bodies.
+    ///
+    /// Only the velocities of the rigid-bodies should be modified through this set.
+    pub bodies: &'a mut RigidBodySet,
+    /// The island manager, giving access to the set of active rigid-bodies.
+    pub islands: &'a IslandManager,
+}
+
 impl<'a> ContactModificationContext<'a> {
     /// Helper function to update `self` to emulate a oneway-platform.
     ///
@@ -152,6 +167,15 @@ pub trait PhysicsHooks {
 
     /// Modifies the set of contacts seen by the constraints solver.
     fn modify_solver_contacts(&self, _context: &mut ContactModificationContext) {}
+
+    /// Called after the forces applied to the active rigid-bodies are computed.
+    fn after_forces_computation(&self, _context: &mut StepStageContext) {}
+
+    /// Called after the velocity constraints are solved, before the positions are integrated.
+    fn after_velocity_solve(&self, _context: &mut StepStageContext) {}
+
+    /// Called after the continuous collision-detection motion clamping.
+    fn after_ccd(&self, _context: &mut StepStageContext) {}
 }
 
 /// User-defined functions called by the physics engines during one timestep in order to customize its behavior.
@@ -235,6 +259,29 @@ pub trait PhysicsHooks: Send + Sync {
     ///
     /// The world-space contact normal can be modified in `context.normal`.
     fn modify_solver_contacts(&self, _context: &mut ContactModificationContext) {}
+
+    /// Called after the forces applied to the active rigid-bodies are computed.
+    ///
+    /// This is called once per CCD substep, right before the constraints solver runs. Gravity
+    /// and user-defined forces have been accumulated at this point, but not applied to the
+    /// velocities yet. Velocities modified here are taken into account by the constraints solver.
+    fn after_forces_computation(&self, _context: &mut StepStageContext) {}
+
+    /// Called after the velocity constraints are solved, before the positions are integrated.
+    ///
+    /// This is called once per solver substep. The velocities of the active rigid-bodies are the
+    /// ones computed by the constraints solver. Velocities modified here (to clamp speeds or apply
+    /// control impulses for example) are used to integrate the positions, without being subject
+    /// to the constraints anymore. The rigid-bodies attached to multibody joints are not
+    /// affected by velocity modifications at this stage.
+    fn after_velocity_solve(&self, _context: &mut StepStageContext) {}
+
+    /// Called after the continuous collision-detection motion clamping.
+    ///
+    /// This is called once per CCD substep (even if CCD is disabled), before the rigid-bodies
+    /// are moved to their final positions. Velocities modified here only affect the next
+    /// timestep.
+    fn after_ccd(&self, _context: &mut StepStageContext) {}
 }
 
 impl PhysicsHooks for () {
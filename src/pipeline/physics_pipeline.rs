@@ -4,17 +4,19 @@ use crate::counters::Counters;
 #[cfg(not(feature = "parallel"))]
 use crate::dynamics::IslandSolver;
 use crate::dynamics::{
-    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
-    RigidBodyChanges, RigidBodyHandle, RigidBodyPosition, RigidBodyType,
+    CCDSolver, ForceGeneratorSet, ImpulseJointSet, IntegrationParameters, IslandManager,
+    JointBreaker, MultibodyJointSet, RigidBodyChanges, RigidBodyHandle, RigidBodyPosition,
+    RigidBodyType, RigidBodyVelocity, SubstepSolver,
 };
 #[cfg(feature = "parallel")]
 use crate::dynamics::{JointGraphEdge, ParallelIslandSolver as IslandSolver};
 use crate::geometry::{
     BroadPhase, BroadPhasePairEvent, ColliderChanges, ColliderHandle, ColliderPair,
-    ContactManifoldIndex, NarrowPhase, TemporaryInteractionIndex,
+    ContactImpactEvent, ContactManifoldIndex, FluidSolver, NarrowPhase, SolverFlags,
+    TemporaryInteractionIndex,
 };
 use crate::math::{Real, Vector};
-use crate::pipeline::{EventHandler, PhysicsHooks, QueryPipeline};
+use crate::pipeline::{ActiveEvents, EventHandler, PhysicsHooks, QueryPipeline, StepStageContext};
 use {crate::dynamics::RigidBodySet, crate::geometry::ColliderSet};
 
 /// The physics pipeline, responsible for stepping the whole physics simulation.
@@ -37,6 +39,16 @@ pub struct PhysicsPipeline {
     broadphase_collider_pairs: Vec<ColliderPair>,
     broad_phase_events: Vec<BroadPhasePairEvent>,
     solvers: Vec<IslandSolver>,
+    substep_solver: SubstepSolver,
+    joint_breaker: JointBreaker,
+    fluid_solver: FluidSolver,
+    solved_velocities: Vec<RigidBodyVelocity>,
+    // The impact events detected before the solver, with the manifold and contact indices
+    // used to retrieve their impulses after the solver.
+    impact_events: Vec<(ContactImpactEvent, usize, usize)>,
+    // The contact pairs solved during the current timestep, emitting contact persisted events,
+    // with their total impulse and total impulse magnitude for each solved substep.
+    persisted_contact_pairs: Vec<(ColliderHandle, ColliderHandle, Vector<Real>, Real)>,
 }
 
 impl Default for PhysicsPipeline {
@@ -57,6 +69,12 @@ impl PhysicsPipeline {
         PhysicsPipeline {
             counters: Counters::new(true),
             solvers: vec![],
+            substep_solver: SubstepSolver::new(),
+            joint_breaker: JointBreaker::new(),
+            fluid_solver: FluidSolver::new(),
+            solved_velocities: vec![],
+            impact_events: vec![],
+            persisted_contact_pairs: vec![],
             contact_pair_indices: vec![],
             manifold_indices: vec![],
             joint_constraint_indices: vec![],
@@ -161,11 +179,14 @@ impl PhysicsPipeline {
         gravity: &Vector<Real>,
         integration_parameters: &IntegrationParameters,
         islands: &mut IslandManager,
+        broad_phase: &BroadPhase,
         narrow_phase: &mut NarrowPhase,
         bodies: &mut RigidBodySet,
         colliders: &mut ColliderSet,
         impulse_joints: &mut ImpulseJointSet,
         multibody_joints: &mut MultibodyJointSet,
+        force_generators: Option<&mut ForceGeneratorSet>,
+        hooks: &dyn PhysicsHooks,
         events: &dyn EventHandler,
     ) {
         self.counters.stages.island_construction_time.resume();
@@ -190,118 +211,215 @@ impl PhysicsPipeline {
                 .resize(islands.num_islands(), Vec::new());
         }
 
-        let mut manifolds = Vec::new();
-        narrow_phase.select_active_contacts(
-            islands,
-            bodies,
-            &mut self.contact_pair_indices,
-            &mut manifolds,
-            &mut self.manifold_indices,
-        );
-        impulse_joints.select_active_interactions(
-            islands,
-            bodies,
-            &mut self.joint_constraint_indices,
-        );
+        let num_substeps = integration_parameters.num_solver_substeps.max(1);
+        let substep_params = &integration_parameters.solver_substep_parameters();
 
         self.counters.stages.update_time.resume();
         for handle in islands.active_dynamic_bodies() {
             let rb = bodies.index_mut_internal(*handle);
             rb.mprops.update_world_mass_properties(&rb.pos.position);
             let effective_mass = rb.mprops.effective_mass();
+            let gravity = rb
+                .forces
+                .gravity
+                .acceleration(gravity, &rb.mprops.world_com);
             rb.forces
                 .compute_effective_force_and_torque(&gravity, &effective_mass);
         }
 
+        if let Some(force_generators) = force_generators {
+            force_generators.apply(
+                integration_parameters.dt,
+                islands,
+                broad_phase,
+                bodies,
+                colliders,
+            );
+        }
+        self.fluid_solver
+            .apply(gravity, islands, narrow_phase, bodies, colliders);
+        crate::dynamics::apply_aerodynamic_forces(
+            integration_parameters.dt,
+            islands,
+            bodies,
+            colliders,
+        );
+
         for multibody in &mut multibody_joints.multibodies {
-            multibody
-                .1
-                .update_dynamics(integration_parameters.dt, bodies);
+            multibody.1.update_dynamics(substep_params.dt, bodies);
             multibody.1.update_acceleration(bodies);
         }
         self.counters.stages.update_time.pause();
 
+        hooks.after_forces_computation(&mut StepStageContext {
+            dt: integration_parameters.dt,
+            bodies,
+            islands,
+        });
+
+        self.collect_contact_impacts(integration_parameters.dt, narrow_phase, bodies, colliders);
+
+        let mut manifolds = Vec::new();
+        narrow_phase.select_active_contacts(
+            islands,
+            bodies,
+            &mut self.contact_pair_indices,
+            &mut manifolds,
+            &mut self.manifold_indices,
+        );
+        impulse_joints.select_active_interactions(
+            islands,
+            bodies,
+            &mut self.joint_constraint_indices,
+        );
+
+        self.joint_breaker.init(bodies, multibody_joints);
+
         self.counters.stages.solver_time.resume();
         if self.solvers.len() < islands.num_islands() {
             self.solvers
                 .resize_with(islands.num_islands(), IslandSolver::new);
         }
 
-        #[cfg(not(feature = "parallel"))]
-        {
-            enable_flush_to_zero!();
+        if num_substeps > 1 {
+            self.substep_solver
+                .init(islands, bodies, &manifolds, impulse_joints.joints_mut());
+        }
 
-            for island_id in 0..islands.num_islands() {
-                self.solvers[island_id].init_and_solve(
-                    island_id,
-                    &mut self.counters,
-                    integration_parameters,
+        for substep in 0..num_substeps {
+            if substep > 0 {
+                self.substep_solver.prepare_next_substep(
+                    substep_params.dt,
                     islands,
                     bodies,
-                    &mut manifolds[..],
-                    &self.manifold_indices[island_id],
-                    impulse_joints.joints_mut(),
-                    &self.joint_constraint_indices[island_id],
-                    multibody_joints,
-                )
+                    &mut manifolds,
+                );
+
+                // The multibody links moved during the last substep.
+                for multibody in &mut multibody_joints.multibodies {
+                    multibody.1.update_dynamics(substep_params.dt, bodies);
+                    multibody.1.update_acceleration(bodies);
+                }
             }
-        }
 
-        #[cfg(feature = "parallel")]
-        {
-            use crate::geometry::ContactManifold;
-            use rayon::prelude::*;
-            use std::sync::atomic::Ordering;
-
-            let num_islands = islands.num_islands();
-            let solvers = &mut self.solvers[..num_islands];
-            let bodies = &std::sync::atomic::AtomicPtr::new(bodies as *mut _);
-            let manifolds = &std::sync::atomic::AtomicPtr::new(&mut manifolds as *mut _);
-            let impulse_joints =
-                &std::sync::atomic::AtomicPtr::new(impulse_joints.joints_vec_mut() as *mut _);
-            let multibody_joints = &std::sync::atomic::AtomicPtr::new(multibody_joints as *mut _);
-            let manifold_indices = &self.manifold_indices[..];
-            let joint_constraint_indices = &self.joint_constraint_indices[..];
-
-            rayon::scope(|scope| {
+            #[cfg(not(feature = "parallel"))]
+            {
                 enable_flush_to_zero!();
 
-                solvers
-                    .par_iter_mut()
-                    .enumerate()
-                    .for_each(|(island_id, solver)| {
-                        let bodies: &mut RigidBodySet =
-                            unsafe { std::mem::transmute(bodies.load(Ordering::Relaxed)) };
-                        let manifolds: &mut Vec<&mut ContactManifold> =
-                            unsafe { std::mem::transmute(manifolds.load(Ordering::Relaxed)) };
-                        let impulse_joints: &mut Vec<JointGraphEdge> =
-                            unsafe { std::mem::transmute(impulse_joints.load(Ordering::Relaxed)) };
-                        let multibody_joints: &mut MultibodyJointSet = unsafe {
-                            std::mem::transmute(multibody_joints.load(Ordering::Relaxed))
-                        };
+                for island_id in 0..islands.num_islands() {
+                    self.solvers[island_id].init_and_solve(
+                        island_id,
+                        &mut self.counters,
+                        substep_params,
+                        islands,
+                        bodies,
+                        &mut manifolds[..],
+                        &self.manifold_indices[island_id],
+                        impulse_joints.joints_mut(),
+                        &self.joint_constraint_indices[island_id],
+                        multibody_joints,
+                    )
+                }
+            }
 
-                        solver.init_and_solve(
-                            scope,
-                            island_id,
-                            islands,
-                            integration_parameters,
-                            bodies,
-                            manifolds,
-                            &manifold_indices[island_id],
-                            impulse_joints,
-                            &joint_constraint_indices[island_id],
-                            multibody_joints,
-                        )
-                    });
-            });
+            #[cfg(feature = "parallel")]
+            {
+                use crate::geometry::ContactManifold;
+                use rayon::prelude::*;
+                use std::sync::atomic::Ordering;
+
+                let num_islands = islands.num_islands();
+                let solvers = &mut self.solvers[..num_islands];
+                let bodies = &std::sync::atomic::AtomicPtr::new(bodies as *mut _);
+                let manifolds = &std::sync::atomic::AtomicPtr::new(&mut manifolds as *mut _);
+                let impulse_joints =
+                    &std::sync::atomic::AtomicPtr::new(impulse_joints.joints_vec_mut() as *mut _);
+                let multibody_joints =
+                    &std::sync::atomic::AtomicPtr::new(multibody_joints as *mut _);
+                let manifold_indices = &self.manifold_indices[..];
+                let joint_constraint_indices = &self.joint_constraint_indices[..];
+
+                rayon::scope(|scope| {
+                    enable_flush_to_zero!();
+
+                    solvers
+                        .par_iter_mut()
+                        .enumerate()
+                        .for_each(|(island_id, solver)| {
+                            let bodies: &mut RigidBodySet =
+                                unsafe { std::mem::transmute(bodies.load(Ordering::Relaxed)) };
+                            let manifolds: &mut Vec<&mut ContactManifold> =
+                                unsafe { std::mem::transmute(manifolds.load(Ordering::Relaxed)) };
+                            let impulse_joints: &mut Vec<JointGraphEdge> = unsafe {
+                                std::mem::transmute(impulse_joints.load(Ordering::Relaxed))
+                            };
+                            let multibody_joints: &mut MultibodyJointSet = unsafe {
+                                std::mem::transmute(multibody_joints.load(Ordering::Relaxed))
+                            };
+
+                            solver.init_and_solve(
+                                scope,
+                                island_id,
+                                islands,
+                                substep_params,
+                                bodies,
+                                manifolds,
+                                &manifold_indices[island_id],
+                                impulse_joints,
+                                &joint_constraint_indices[island_id],
+                                multibody_joints,
+                            )
+                        });
+                });
+            }
+
+            self.integrate_positions(substep_params.dt, islands, bodies, multibody_joints, hooks);
+
+            if num_substeps > 1 {
+                self.substep_solver
+                    .accumulate_impulses(&mut manifolds, impulse_joints.joints_mut());
+            }
         }
 
-        // Generate contact force events if needed.
+        if num_substeps > 1 {
+            self.substep_solver.finalize(
+                islands,
+                bodies,
+                &mut manifolds,
+                impulse_joints.joints_mut(),
+            );
+        }
+
+        self.joint_breaker.break_joints(
+            integration_parameters.dt,
+            islands,
+            bodies,
+            colliders,
+            impulse_joints,
+            &self.joint_constraint_indices,
+            multibody_joints,
+            events,
+        );
+
+        // Generate contact force events if needed, and collect the pairs emitting contact
+        // persisted events at the end of the timestep.
         let inv_dt = crate::utils::inv(integration_parameters.dt);
         for pair_id in self.contact_pair_indices.drain(..) {
             let pair = narrow_phase.contact_pair_at_index(pair_id);
             let co1 = &colliders[pair.collider1];
             let co2 = &colliders[pair.collider2];
+
+            if (co1.flags.active_events | co2.flags.active_events)
+                .contains(ActiveEvents::CONTACT_PERSISTED_EVENTS)
+            {
+                self.persisted_contact_pairs.push((
+                    pair.collider1,
+                    pair.collider2,
+                    pair.total_impulse(),
+                    pair.total_impulse_magnitude(),
+                ));
+            }
+
             let threshold = co1
                 .effective_contact_force_event_threshold()
                 .min(co2.effective_contact_force_event_threshold());
@@ -323,11 +441,144 @@ impl PhysicsPipeline {
             }
         }
 
+        for (mut event, manifold_id, contact_id) in self.impact_events.drain(..) {
+            if let Some(pair) = narrow_phase.contact_pair(event.collider1, event.collider2) {
+                event.impulse = pair.manifolds[manifold_id].points[contact_id].data.impulse;
+            }
+
+            events.handle_contact_impact_event(bodies, colliders, event);
+        }
+
         self.counters.stages.solver_time.pause();
     }
 
+    /// Detects the contact points with an impact velocity large enough to emit an impact event.
+    ///
+    /// This must be called before the constraints solver modifies the velocities.
+    fn collect_contact_impacts(
+        &mut self,
+        dt: Real,
+        narrow_phase: &NarrowPhase,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+    ) {
+        self.impact_events.clear();
+
+        if colliders.impact_event_colliders.is_empty() {
+            return;
+        }
+
+        for pair in narrow_phase.contact_pairs() {
+            if !pair.has_any_active_contact {
+                continue;
+            }
+
+            let co1 = &colliders[pair.collider1];
+            let co2 = &colliders[pair.collider2];
+            let threshold = co1
+                .effective_min_impact_velocity()
+                .min(co2.effective_min_impact_velocity());
+
+            if threshold == Real::MAX {
+                continue;
+            }
+
+            for (manifold_id, manifold) in pair.manifolds.iter().enumerate() {
+                if !manifold
+                    .data
+                    .solver_flags
+                    .contains(SolverFlags::COMPUTE_IMPULSES)
+                {
+                    continue;
+                }
+
+                let rb1 = manifold.data.rigid_body1.map(|h| &bodies[h]);
+                let rb2 = manifold.data.rigid_body2.map(|h| &bodies[h]);
+
+                for contact in &manifold.data.solver_contacts {
+                    let vel1 = rb1
+                        .map(|rb| rb.velocity_at_point(&contact.point))
+                        .unwrap_or_else(Vector::zeros);
+                    let vel2 = rb2
+                        .map(|rb| rb.velocity_at_point(&contact.point))
+                        .unwrap_or_else(Vector::zeros);
+                    let impact_velocity = (vel1 - vel2).dot(&manifold.data.normal);
+
+                    // NOTE: the strict inequality is important here, so we don’t
+                    //       trigger an event for resting contacts if the threshold is 0.0.
+                    //       Speculative contacts that won’t be hit during this timestep are
+                    //       ignored so a single impact doesn’t trigger multiple events.
+                    if impact_velocity > threshold && contact.dist <= impact_velocity * dt {
+                        let contact_id = contact.contact_id as usize;
+                        let tracked = &manifold.points[contact_id];
+                        let event = ContactImpactEvent {
+                            collider1: pair.collider1,
+                            collider2: pair.collider2,
+                            point: contact.point,
+                            normal: manifold.data.normal,
+                            impact_velocity,
+                            subshape1: manifold.subshape1,
+                            subshape2: manifold.subshape2,
+                            feature1: tracked.fid1,
+                            feature2: tracked.fid2,
+                            feature_material1: manifold.data.feature_material1,
+                            feature_material2: manifold.data.feature_material2,
+                            impulse: 0.0,
+                        };
+                        self.impact_events.push((event, manifold_id, contact_id));
+                    }
+                }
+            }
+        }
+    }
+
+    fn integrate_positions(
+        &mut self,
+        dt: Real,
+        islands: &IslandManager,
+        bodies: &mut RigidBodySet,
+        multibody_joints: &MultibodyJointSet,
+        hooks: &dyn PhysicsHooks,
+    ) {
+        self.solved_velocities.clear();
+        self.solved_velocities.extend(
+            islands
+                .active_dynamic_bodies()
+                .iter()
+                .map(|handle| bodies[*handle].vels),
+        );
+
+        hooks.after_velocity_solve(&mut StepStageContext {
+            dt,
+            bodies,
+            islands,
+        });
+
+        // NOTE: the positions of the multibody links were already integrated by the solver.
+        for (handle, solved_vels) in islands
+            .active_dynamic_bodies()
+            .iter()
+            .zip(self.solved_velocities.iter())
+        {
+            if multibody_joints.rigid_body_link(*handle).is_some() {
+                continue;
+            }
+
+            let rb = bodies.index_mut_internal(*handle);
+            // Apply the velocity changes made by the hooks to the velocities to integrate.
+            rb.integrated_vels.linvel += rb.vels.linvel - solved_vels.linvel;
+            rb.integrated_vels.angvel += rb.vels.angvel - solved_vels.angvel;
+            rb.pos.next_position = rb.integrated_vels.integrate(
+                dt,
+                &rb.pos.position,
+                &rb.mprops.local_mprops.local_com,
+            );
+        }
+    }
+
     fn run_ccd_motion_clamping(
         &mut self,
+        elapsed_time: Real,
         integration_parameters: &IntegrationParameters,
         islands: &IslandManager,
         bodies: &mut RigidBodySet,
@@ -339,6 +590,7 @@ impl PhysicsPipeline {
         self.counters.ccd.toi_computation_time.start();
         // Handle CCD
         let impacts = ccd_solver.predict_impacts_at_next_positions(
+            elapsed_time,
             integration_parameters.dt,
             islands,
             bodies,
@@ -405,6 +657,80 @@ impl PhysicsPipeline {
     /// This is the same as `self.step_generic`, except that it is specialized
     /// to work with `RigidBodySet` and `ColliderSet`.
     pub fn step(
+        &mut self,
+        gravity: &Vector<Real>,
+        integration_parameters: &IntegrationParameters,
+        islands: &mut IslandManager,
+        broad_phase: &mut BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+        query_pipeline: Option<&mut QueryPipeline>,
+        hooks: &dyn PhysicsHooks,
+        events: &dyn EventHandler,
+    ) {
+        self.step_internal(
+            gravity,
+            integration_parameters,
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+            query_pipeline,
+            None,
+            hooks,
+            events,
+        )
+    }
+
+    /// Executes one timestep of the physics simulation, applying the forces of the given
+    /// force generators to the active rigid-bodies.
+    ///
+    /// This is the same as `self.step`, with the force generators applied after the
+    /// computation of the other forces, at each CCD substep.
+    pub fn step_with_force_generators(
+        &mut self,
+        gravity: &Vector<Real>,
+        integration_parameters: &IntegrationParameters,
+        islands: &mut IslandManager,
+        broad_phase: &mut BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+        query_pipeline: Option<&mut QueryPipeline>,
+        force_generators: &mut ForceGeneratorSet,
+        hooks: &dyn PhysicsHooks,
+        events: &dyn EventHandler,
+    ) {
+        self.step_internal(
+            gravity,
+            integration_parameters,
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+            query_pipeline,
+            Some(force_generators),
+            hooks,
+            events,
+        )
+    }
+
+    fn step_internal(
         &mut self,
         gravity: &Vector<Real>,
         integration_parameters: &IntegrationParameters,
@@ -417,11 +743,13 @@ impl PhysicsPipeline {
         multibody_joints: &mut MultibodyJointSet,
         ccd_solver: &mut CCDSolver,
         mut query_pipeline: Option<&mut QueryPipeline>,
+        mut force_generators: Option<&mut ForceGeneratorSet>,
         hooks: &dyn PhysicsHooks,
         events: &dyn EventHandler,
     ) {
         self.counters.reset();
         self.counters.step_started();
+        islands.clear_sleep_transitions();
 
         // Apply some of delayed wake-ups.
         for handle in impulse_joints
@@ -496,7 +824,8 @@ impl PhysicsPipeline {
         self.clear_modified_bodies(bodies, &mut modified_bodies);
         removed_colliders.clear();
 
-        let mut remaining_time = integration_parameters.dt;
+        let total_dt = integration_parameters.dt;
+        let mut remaining_time = total_dt;
         let mut integration_parameters = *integration_parameters;
 
         let (ccd_is_enabled, mut remaining_substeps) =
@@ -571,11 +900,14 @@ impl PhysicsPipeline {
                 gravity,
                 &integration_parameters,
                 islands,
+                broad_phase,
                 narrow_phase,
                 bodies,
                 colliders,
                 impulse_joints,
                 multibody_joints,
+                force_generators.as_deref_mut(),
+                hooks,
                 events,
             );
 
@@ -589,8 +921,12 @@ impl PhysicsPipeline {
                     integration_parameters.dt,
                     false,
                 );
+                // The time elapsed since the beginning of the timestep, at the beginning of
+                // this substep.
+                let elapsed_time = total_dt - remaining_time - integration_parameters.dt;
                 if ccd_active {
                     self.run_ccd_motion_clamping(
+                        elapsed_time,
                         &integration_parameters,
                         islands,
                         bodies,
@@ -600,8 +936,24 @@ impl PhysicsPipeline {
                         events,
                     );
                 }
+
+                ccd_solver.detect_sensor_pass_throughs(
+                    elapsed_time,
+                    integration_parameters.dt,
+                    islands,
+                    broad_phase,
+                    bodies,
+                    colliders,
+                    events,
+                );
             }
 
+            hooks.after_ccd(&mut StepStageContext {
+                dt: integration_parameters.dt,
+                bodies,
+                islands,
+            });
+
             self.advance_to_final_positions(islands, bodies, colliders, &mut modified_colliders);
 
             self.detect_collisions(
@@ -632,6 +984,38 @@ impl PhysicsPipeline {
             self.clear_modified_colliders(colliders, &mut modified_colliders);
         }
 
+        // Emit the contact persisted events once per timestep, even if the same contact pair
+        // was solved by several CCD substeps. The stable sort keeps the impulses of each pair
+        // in substep order, so their sum is deterministic.
+        self.persisted_contact_pairs
+            .sort_by_key(|(h1, h2, ..)| (h1.into_raw_parts(), h2.into_raw_parts()));
+        self.persisted_contact_pairs.dedup_by(
+            |(h1, h2, impulse, magnitude), (kept1, kept2, total, total_magnitude)| {
+                if (h1, h2) == (kept1, kept2) {
+                    *total += *impulse;
+                    *total_magnitude += *magnitude;
+                    true
+                } else {
+                    false
+                }
+            },
+        );
+        for (handle1, handle2, total_impulse, total_impulse_magnitude) in
+            self.persisted_contact_pairs.drain(..)
+        {
+            if let Some(pair) = narrow_phase.contact_pair(handle1, handle2) {
+                if pair.has_any_active_contact {
+                    events.handle_contact_persisted_event(
+                        bodies,
+                        colliders,
+                        pair,
+                        total_impulse,
+                        total_impulse_magnitude,
+                    );
+                }
+            }
+        }
+
         // Finally, make sure we update the world mass-properties of the rigid-bodies
         // that moved. Otherwise, users may end up applying forces wrt. an outdated
         // center of mass.
@@ -655,6 +1039,7 @@ mod test {
     };
     use crate::geometry::{BroadPhase, ColliderBuilder, ColliderSet, NarrowPhase};
     use crate::math::Vector;
+    use crate::pipeline::test_utils;
     use crate::pipeline::PhysicsPipeline;
     use crate::prelude::MultibodyJointSet;
 
@@ -855,4 +1240,254 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn solver_substeps() {
+        use crate::dynamics::RevoluteJointBuilder;
+        use crate::math::{Point, Real};
+
+        let mut world = test_utils::world();
+        world.integration_parameters.num_solver_substeps = 4;
+
+        // A chain with a high mass ratio.
+        let num_links = 10;
+        let mut handles = vec![world.insert_rigid_body(RigidBodyBuilder::fixed())];
+        for i in 1..=num_links {
+            let rb = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * i as Real)
+                .additional_mass(if i == num_links { 100.0 } else { 1.0 });
+            handles.push(world.insert_rigid_body(rb));
+        }
+
+        for link in handles.windows(2) {
+            #[cfg(feature = "dim2")]
+            let joint = RevoluteJointBuilder::new();
+            #[cfg(feature = "dim3")]
+            let joint = RevoluteJointBuilder::new(Vector::z_axis());
+            let joint = joint.local_anchor2(Point::from(-Vector::x()));
+            world.insert_impulse_joint(link[0], link[1], joint, true);
+        }
+
+        // A ball resting on the ground.
+        let ground =
+            world.insert_rigid_body(RigidBodyBuilder::fixed().translation(Vector::y() * -20.0));
+        let ground_shape = test_utils::cuboid(1.0, 1.0);
+        world.insert_collider_with_parent(ground_shape, ground);
+        let ball =
+            world.insert_rigid_body(RigidBodyBuilder::dynamic().translation(Vector::y() * -18.5));
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.5), ball);
+
+        for _ in 0..200 {
+            world.step(&(), &());
+        }
+
+        for link in handles.windows(2) {
+            let dist =
+                (world.bodies[link[1]].translation() - world.bodies[link[0]].translation()).norm();
+            assert!((dist - 1.0).abs() < 1.0e-3);
+        }
+
+        assert!((world.bodies[ball].translation().y + 18.5).abs() < 1.0e-2);
+
+        // The contact impulses are accumulated over all the substeps.
+        let weight = world.bodies[ball].mass() * 10.0;
+        let pair = world.narrow_phase.contact_pairs().next().unwrap();
+        let force = pair.total_impulse_magnitude() / world.integration_parameters.dt;
+        assert!((force - weight).abs() < weight * 1.0e-2);
+    }
+
+    #[test]
+    fn solver_substeps_with_multibody() {
+        use crate::dynamics::{RevoluteJointBuilder, RigidBodyHandle};
+        use crate::math::{Point, Real};
+        use crate::pipeline::PhysicsWorld;
+
+        // A double pendulum released horizontally.
+        fn double_pendulum(
+            dt: Real,
+            num_solver_substeps: usize,
+        ) -> (PhysicsWorld, RigidBodyHandle) {
+            let mut world = test_utils::world();
+            world.integration_parameters.dt = dt;
+            world.integration_parameters.num_solver_substeps = num_solver_substeps;
+
+            let mut parent = world.insert_rigid_body(RigidBodyBuilder::fixed());
+            for i in 1..=2 {
+                let rb = RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * i as Real)
+                    .additional_mass(1.0);
+                let child = world.insert_rigid_body(rb);
+                #[cfg(feature = "dim2")]
+                let joint = RevoluteJointBuilder::new();
+                #[cfg(feature = "dim3")]
+                let joint = RevoluteJointBuilder::new(Vector::z_axis());
+                let joint = joint.local_anchor2(Point::from(-Vector::x()));
+                world.insert_multibody_joint(parent, child, joint, true);
+                parent = child;
+            }
+
+            (world, parent)
+        }
+
+        let (mut world, tip) = double_pendulum(1.0 / 60.0, 4);
+        let (mut reference, reference_tip) = double_pendulum(1.0 / 240.0, 1);
+
+        for _ in 0..30 {
+            world.step(&(), &());
+
+            for _ in 0..4 {
+                reference.step(&(), &());
+            }
+        }
+
+        // The links moved between the substeps, so the multibody dynamics must follow them.
+        let error = world.bodies[tip].translation() - reference.bodies[reference_tip].translation();
+        assert!(error.norm() < 1.0e-4);
+    }
+
+    #[test]
+    fn step_stage_hooks() {
+        use crate::pipeline::{PhysicsHooks, PhysicsWorld, StepStageContext};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Default)]
+        struct SpeedLimit {
+            num_calls: AtomicUsize,
+        }
+
+        impl PhysicsHooks for SpeedLimit {
+            fn after_forces_computation(&self, _: &mut StepStageContext) {
+                self.num_calls.fetch_add(1, Ordering::SeqCst);
+            }
+
+            fn after_velocity_solve(&self, context: &mut StepStageContext) {
+                for handle in context.islands.active_dynamic_bodies() {
+                    let rb = &mut context.bodies[*handle];
+                    let linvel = rb.linvel().cap_magnitude(1.0);
+                    rb.set_linvel(linvel, false);
+                }
+            }
+
+            fn after_ccd(&self, _: &mut StepStageContext) {
+                self.num_calls.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let mut world = PhysicsWorld::new();
+        let handle = world.insert_rigid_body(
+            RigidBodyBuilder::dynamic()
+                .linvel(Vector::x() * 10.0)
+                .additional_mass(1.0),
+        );
+        let hooks = SpeedLimit::default();
+
+        for _ in 0..10 {
+            world.step(&hooks, &());
+        }
+
+        let expected = world.integration_parameters.dt * 10.0;
+        assert!((world.bodies[handle].translation().x - expected).abs() < 1.0e-5);
+        assert_eq!(world.bodies[handle].linvel().x, 1.0);
+        assert_eq!(hooks.num_calls.load(Ordering::SeqCst), 20);
+    }
+
+    #[test]
+    fn contact_persisted_events() {
+        use crate::pipeline::ActiveEvents;
+
+        let mut world = test_utils::world();
+        let ground = ColliderBuilder::ball(10.0).translation(Vector::y() * -10.0);
+        world.insert_collider(ground);
+        let rb = world.insert_rigid_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .can_sleep(false),
+        );
+        let co = world.insert_collider_with_parent(
+            ColliderBuilder::ball(0.5)
+                .mass(2.0)
+                .active_events(ActiveEvents::CONTACT_PERSISTED_EVENTS),
+            rb,
+        );
+
+        let events = test_utils::EventChannels::new();
+        for _ in 0..100 {
+            world.step(&(), &events.collector);
+        }
+
+        let persisted: Vec<_> = events.persisted.try_iter().collect();
+        assert_eq!(persisted.len(), 100);
+
+        // At rest, the contact impulse compensates the gravity.
+        let last = persisted.last().unwrap();
+        assert!(last.collider1 == co || last.collider2 == co);
+        assert_eq!(last.num_active_contacts, 1);
+        let expected = 2.0 * 10.0 * world.integration_parameters.dt;
+        assert!((last.total_impulse_magnitude - expected).abs() < 1.0e-2 * expected);
+        assert!((last.total_impulse.norm() - expected).abs() < 1.0e-2 * expected);
+
+        // Only one event per timestep, even with several CCD substeps.
+        world.integration_parameters.max_ccd_substeps = 4;
+        let wall = test_utils::cuboid(0.1, 10.0);
+        world.insert_collider(wall.translation(Vector::x() * 20.0));
+        let bullet = world.insert_rigid_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 5.0 - Vector::x() * 10.0)
+                .linvel(Vector::x() * 1000.0)
+                .ccd_enabled(true),
+        );
+        world.insert_collider_with_parent(ColliderBuilder::ball(0.1), bullet);
+
+        // The bullet hits the wall during the second timestep. The impulses of the resting ball
+        // are summed over all the substeps, so they still compensate the gravity.
+        for _ in 0..2 {
+            world.step(&(), &events.collector);
+            let persisted: Vec<_> = events.persisted.try_iter().collect();
+            assert_eq!(persisted.len(), 1);
+            let magnitude = persisted[0].total_impulse_magnitude;
+            assert!((magnitude - expected).abs() < 5.0e-2 * expected);
+        }
+    }
+
+    #[test]
+    fn contact_impact_events() {
+        use crate::pipeline::ActiveEvents;
+
+        let mut world = test_utils::world();
+        let ground = ColliderBuilder::ball(10.0).translation(Vector::y() * -10.0);
+        world.insert_collider(ground);
+        let rb =
+            world.insert_rigid_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 3.0));
+        let co = world.insert_collider_with_parent(
+            ColliderBuilder::ball(0.5)
+                .active_events(ActiveEvents::CONTACT_IMPACT_EVENTS)
+                .min_impact_velocity(1.0),
+            rb,
+        );
+
+        let events = test_utils::EventChannels::new();
+        for _ in 0..200 {
+            world.step(&(), &events.collector);
+        }
+
+        // The ball hits the ground once, after falling by 2.5m, then rests.
+        let impacts: Vec<_> = events.impacts.try_iter().collect();
+        assert_eq!(impacts.len(), 1);
+        let impact = impacts[0];
+        let expected_velocity = (2.0 * 10.0 * 2.5 as crate::math::Real).sqrt();
+        assert!(impact.collider1 == co || impact.collider2 == co);
+        assert!((impact.impact_velocity - expected_velocity).abs() < 0.2);
+        assert!(impact.impulse > 0.0);
+        assert!(impact.point.y.abs() < 0.1);
+
+        // Negative thresholds are replaced by zero.
+        world.colliders[co].set_min_impact_velocity(-1.0);
+        assert_eq!(world.colliders[co].min_impact_velocity(), 0.0);
+
+        // The contact pairs aren’t checked once no collider emits impact events.
+        assert_eq!(world.colliders.impact_event_colliders, [co]);
+        world.colliders[co].set_active_events(ActiveEvents::empty());
+        world.step(&(), &events.collector);
+        assert!(world.colliders.impact_event_colliders.is_empty());
+    }
 }
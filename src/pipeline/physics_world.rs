@@ -0,0 +1,509 @@
+//! A physics world aggregating all the simulation state.
+
+use crate::dynamics::{
+    CCDSolver, ForceGeneratorSet, GenericJoint, ImpulseJoint, ImpulseJointHandle, ImpulseJointSet,
+    IntegrationParameters, IslandManager, MultibodyJointHandle, MultibodyJointSet, RigidBody,
+    RigidBodyHandle, RigidBodySet,
+};
+use crate::geometry::{BroadPhase, Collider, ColliderHandle, ColliderSet, NarrowPhase};
+use crate::math::{Real, Vector};
+use crate::pipeline::{EventHandler, PhysicsHooks, PhysicsPipeline, QueryPipeline};
+
+/// A physics world, owning every structure needed to run a simulation.
+///
+/// This bundles the rigid-body, collider, and joint sets together with the broad-phase,
+/// narrow-phase, island manager, CCD solver, and query pipeline, so the whole simulation
+/// can be stepped with a single call to [`PhysicsWorld::step`].
+///
+/// The insertion and removal methods of this structure keep all the sets consistent with
+/// each other (for example, removing a rigid-body also removes its attached colliders and
+/// joints, and updates the island manager).
+///
+/// With the `serde-serialize` feature enabled, the whole world can be serialized as a single
+/// unit. The [`PhysicsPipeline`] only contains workspace data, so it is not serialized. The force
+/// generators are trait-objects, so they are not serialized either and must be inserted again
+/// after deserialization. They are cloned along with the world, though.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct PhysicsWorld {
+    /// The gravity applied to every dynamic rigid-body of this world.
+    pub gravity: Vector<Real>,
+    /// The parameters used for stepping this world.
+    pub integration_parameters: IntegrationParameters,
+    /// The island manager, tracking the set of active rigid-bodies.
+    pub islands: IslandManager,
+    /// The broad-phase, responsible for finding pairs of potentially colliding colliders.
+    pub broad_phase: BroadPhase,
+    /// The narrow-phase, responsible for computing contacts and intersections.
+    pub narrow_phase: NarrowPhase,
+    /// The set of rigid-bodies of this world.
+    pub bodies: RigidBodySet,
+    /// The set of colliders of this world.
+    pub colliders: ColliderSet,
+    /// The set of impulse joints of this world.
+    pub impulse_joints: ImpulseJointSet,
+    /// The set of multibody joints of this world.
+    pub multibody_joints: MultibodyJointSet,
+    /// The solver responsible for continuous collision-detection.
+    pub ccd_solver: CCDSolver,
+    /// The query pipeline, updated automatically at each step.
+    pub query_pipeline: QueryPipeline,
+    /// The force generators applied to the active rigid-bodies at each step.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    pub force_generators: ForceGeneratorSet,
+    /// The physics pipeline used for stepping this world.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    pub pipeline: PhysicsPipeline,
+}
+
+impl Default for PhysicsWorld {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for PhysicsWorld {
+    fn clone(&self) -> Self {
+        Self {
+            gravity: self.gravity,
+            integration_parameters: self.integration_parameters,
+            islands: self.islands.clone(),
+            broad_phase: self.broad_phase.clone(),
+            narrow_phase: self.narrow_phase.clone(),
+            bodies: self.bodies.clone(),
+            colliders: self.colliders.clone(),
+            impulse_joints: self.impulse_joints.clone(),
+            multibody_joints: self.multibody_joints.clone(),
+            ccd_solver: self.ccd_solver.clone(),
+            query_pipeline: self.query_pipeline.clone(),
+            force_generators: self.force_generators.clone(),
+            // The pipeline only contains workspace data.
+            pipeline: PhysicsPipeline::new(),
+        }
+    }
+}
+
+impl PhysicsWorld {
+    /// Creates a new empty physics world with a zero gravity and default integration parameters.
+    pub fn new() -> Self {
+        Self {
+            gravity: Vector::zeros(),
+            integration_parameters: IntegrationParameters::default(),
+            islands: IslandManager::new(),
+            broad_phase: BroadPhase::new(),
+            narrow_phase: NarrowPhase::new(),
+            bodies: RigidBodySet::new(),
+            colliders: ColliderSet::new(),
+            impulse_joints: ImpulseJointSet::new(),
+            multibody_joints: MultibodyJointSet::new(),
+            ccd_solver: CCDSolver::new(),
+            query_pipeline: QueryPipeline::new(),
+            force_generators: ForceGeneratorSet::new(),
+            pipeline: PhysicsPipeline::new(),
+        }
+    }
+
+    /// Creates a new empty physics world with the given gravity.
+    pub fn with_gravity(gravity: Vector<Real>) -> Self {
+        Self {
+            gravity,
+            ..Self::new()
+        }
+    }
+
+    /// Executes one timestep of the physics simulation.
+    ///
+    /// Use `&()` for `hooks` and `events` if no physics hooks or event handler are needed.
+    pub fn step(&mut self, hooks: &dyn PhysicsHooks, events: &dyn EventHandler) {
+        self.pipeline.step_with_force_generators(
+            &self.gravity,
+            &self.integration_parameters,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd_solver,
+            Some(&mut self.query_pipeline),
+            &mut self.force_generators,
+            hooks,
+            events,
+        );
+    }
+
+    /// Translates the whole simulation by `translation`.
+    ///
+    /// Floating-point positions lose precision as they get far from the origin, which makes
+    /// large open worlds jittery. Shifting the origin regularly so that it stays close to the
+    /// area of interest (typically the player or the camera) keeps the simulation accurate.
+    ///
+    /// Every rigid-body, collider, broad-phase proxy, query pipeline entry, and cached contact
+    /// is moved in one call. Contact manifolds are preserved, so warm-starting is not affected,
+    /// and no rigid-body is woken up. This must be called between two timesteps.
+    ///
+    /// The rounding errors of the translation can make the bounding boxes of some colliders stop
+    /// overlapping. The contact pairs removed this way are reported to `events`.
+    pub fn shift_origin(&mut self, translation: &Vector<Real>, events: &dyn EventHandler) {
+        self.bodies.shift_origin(translation);
+        self.colliders.shift_origin(translation);
+
+        // The root of each multibody stores its own world-space pose.
+        for (_, multibody) in self.multibody_joints.multibodies.iter_mut() {
+            multibody.update_root_type(&mut self.bodies);
+            multibody.forward_kinematics(&mut self.bodies, false);
+        }
+
+        let mut pair_events = vec![];
+        self.broad_phase.shift_origin(
+            self.integration_parameters.prediction_distance,
+            &mut self.colliders,
+            &mut pair_events,
+        );
+        self.narrow_phase.replace_pairs(
+            Some(&mut self.islands),
+            &self.colliders,
+            &mut self.bodies,
+            &pair_events,
+            events,
+        );
+        self.narrow_phase.shift_origin(translation);
+        self.query_pipeline.shift_origin(&self.colliders);
+    }
+
+    /// Inserts a rigid-body into this world and retrieve its handle.
+    pub fn insert_rigid_body(&mut self, rb: impl Into<RigidBody>) -> RigidBodyHandle {
+        self.bodies.insert(rb)
+    }
+
+    /// Removes a rigid-body from this world.
+    ///
+    /// All the joints attached to this rigid-body are removed too. If `remove_attached_colliders`
+    /// is `true`, the colliders attached to this rigid-body are removed. Otherwise, they are
+    /// simply detached from it.
+    pub fn remove_rigid_body(
+        &mut self,
+        handle: RigidBodyHandle,
+        remove_attached_colliders: bool,
+    ) -> Option<RigidBody> {
+        self.bodies.remove(
+            handle,
+            &mut self.islands,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            remove_attached_colliders,
+        )
+    }
+
+    /// Inserts a collider, not attached to any rigid-body, into this world.
+    pub fn insert_collider(&mut self, collider: impl Into<Collider>) -> ColliderHandle {
+        self.colliders.insert(collider)
+    }
+
+    /// Inserts a collider into this world and attach it to the given rigid-body.
+    pub fn insert_collider_with_parent(
+        &mut self,
+        collider: impl Into<Collider>,
+        parent: RigidBodyHandle,
+    ) -> ColliderHandle {
+        self.colliders
+            .insert_with_parent(collider, parent, &mut self.bodies)
+    }
+
+    /// Removes a collider from this world.
+    ///
+    /// If `wake_up` is `true`, the rigid-body the removed collider is attached to
+    /// will be woken up.
+    pub fn remove_collider(&mut self, handle: ColliderHandle, wake_up: bool) -> Option<Collider> {
+        self.colliders
+            .remove(handle, &mut self.islands, &mut self.bodies, wake_up)
+    }
+
+    /// Inserts an impulse joint between two rigid-bodies of this world.
+    ///
+    /// If `wake_up` is set to `true`, then the bodies attached to this joint will be
+    /// automatically woken up during the next timestep.
+    pub fn insert_impulse_joint(
+        &mut self,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        data: impl Into<GenericJoint>,
+        wake_up: bool,
+    ) -> ImpulseJointHandle {
+        self.impulse_joints.insert(body1, body2, data, wake_up)
+    }
+
+    /// Removes an impulse joint from this world.
+    ///
+    /// If `wake_up` is set to `true`, then the bodies attached to this joint will be
+    /// automatically woken up during the next timestep.
+    pub fn remove_impulse_joint(
+        &mut self,
+        handle: ImpulseJointHandle,
+        wake_up: bool,
+    ) -> Option<ImpulseJoint> {
+        self.impulse_joints.remove(handle, wake_up)
+    }
+
+    /// Inserts a multibody joint between two rigid-bodies of this world.
+    ///
+    /// Returns `None` if the joint would result in an invalid multibody configuration.
+    pub fn insert_multibody_joint(
+        &mut self,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        data: impl Into<GenericJoint>,
+        wake_up: bool,
+    ) -> Option<MultibodyJointHandle> {
+        self.multibody_joints.insert(body1, body2, data, wake_up)
+    }
+
+    /// Removes a multibody joint from this world.
+    pub fn remove_multibody_joint(&mut self, handle: MultibodyJointHandle, wake_up: bool) {
+        self.multibody_joints.remove(handle, wake_up)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PhysicsWorld;
+    use crate::dynamics::{
+        FixedJointBuilder, RigidBodyBuilder, RigidBodyGravity, RigidBodyVelocityLimits,
+    };
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_utils;
+
+    #[test]
+    fn remove_rigid_body_cascades() {
+        let mut world = test_utils::world();
+
+        let rb1 = world.insert_rigid_body(RigidBodyBuilder::dynamic());
+        let rb2 = world.insert_rigid_body(RigidBodyBuilder::dynamic());
+        let co1 = world.insert_collider_with_parent(ColliderBuilder::ball(0.5), rb1);
+        let co2 = world.insert_collider_with_parent(ColliderBuilder::ball(0.5), rb2);
+        let joint = world.insert_impulse_joint(rb1, rb2, FixedJointBuilder::new(), true);
+
+        for _ in 0..5 {
+            world.step(&(), &());
+        }
+
+        assert!(world.remove_rigid_body(rb1, true).is_some());
+        assert!(!world.colliders.contains(co1));
+        assert!(world.colliders.contains(co2));
+        assert!(!world.impulse_joints.contains(joint));
+        assert!(!world.islands.active_dynamic_bodies().contains(&rb1));
+
+        for _ in 0..5 {
+            world.step(&(), &());
+        }
+
+        assert!(world.islands.active_dynamic_bodies().contains(&rb2));
+    }
+
+    #[test]
+    fn shift_origin_keeps_contacts_and_sleep() {
+        let mut world = test_utils::world();
+        let ground = world.insert_rigid_body(RigidBodyBuilder::fixed());
+        let ground_shape = test_utils::cuboid(10.0, 0.1);
+        let co1 = world.insert_collider_with_parent(ground_shape, ground);
+        let rb =
+            world.insert_rigid_body(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.6));
+        let co2 = world.insert_collider_with_parent(ColliderBuilder::ball(0.5), rb);
+
+        for _ in 0..200 {
+            world.step(&(), &());
+        }
+
+        assert!(world.bodies[rb].is_sleeping());
+        let height = world.bodies[rb].translation().y;
+        let shift = Vector::x() * 1.0e4;
+        world.shift_origin(&shift, &());
+
+        assert!(world.bodies[rb].is_sleeping());
+        assert!(world.narrow_phase.contact_pair(co1, co2).is_some());
+        assert_eq!(world.colliders[co2].translation().x, 1.0e4);
+        assert_eq!(world.bodies[ground].translation().x, 1.0e4);
+
+        for _ in 0..10 {
+            world.step(&(), &());
+        }
+
+        assert!(world.bodies[rb].is_sleeping());
+        assert!(
+            world
+                .narrow_phase
+                .contact_pair(co1, co2)
+                .unwrap()
+                .has_any_active_contact
+        );
+        assert_eq!(world.bodies[rb].translation().y, height);
+        assert!(world
+            .query_pipeline
+            .intersection_with_shape(
+                &world.bodies,
+                &world.colliders,
+                &(shift + Vector::y() * height).into(),
+                &crate::geometry::Ball::new(0.1),
+                crate::pipeline::QueryFilter::default(),
+            )
+            .is_some());
+
+        // The pairs of a collider moved far away right before the shift are removed. The
+        // collider is moved back with its parent during the next step, restoring the pair.
+        world.colliders[co2].set_translation(shift + Vector::x() * 100.0);
+        world.shift_origin(&-shift, &());
+        assert!(world.narrow_phase.contact_pair(co1, co2).is_none());
+        world.step(&(), &());
+        assert!(world.narrow_phase.contact_pair(co1, co2).is_some());
+    }
+
+    #[test]
+    fn per_body_gravity() {
+        let mut world = test_utils::world();
+
+        let global = world.insert_rigid_body(RigidBodyBuilder::dynamic().additional_mass(1.0));
+        let zero_g = world.insert_rigid_body(
+            RigidBodyBuilder::dynamic()
+                .additional_mass(1.0)
+                .gravity(RigidBodyGravity::Constant(Vector::zeros())),
+        );
+        let planet = world.insert_rigid_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * 10.0)
+                .additional_mass(1.0)
+                .gravity(RigidBodyGravity::Planet {
+                    center: Point::origin(),
+                    radius: 5.0,
+                    surface_gravity: 4.0,
+                }),
+        );
+
+        world.step(&(), &());
+
+        assert!(world.bodies[global].linvel().y < 0.0);
+        assert_eq!(*world.bodies[zero_g].linvel(), Vector::zeros());
+        // A quarter of the surface gravity at twice the radius, toward the center.
+        let dt = world.integration_parameters.dt;
+        let linvel = world.bodies[planet].linvel();
+        assert!((linvel.x + dt).abs() < 1.0e-5 && linvel.y == 0.0);
+        assert!(
+            world.bodies[planet]
+                .predict_position_using_velocity_and_forces(dt)
+                .translation
+                .x
+                < world.bodies[planet].translation().x
+        );
+        assert_eq!(
+            world.bodies[zero_g].gravitational_potential_energy(dt, world.gravity),
+            0.0
+        );
+        assert!(world.bodies[planet].gravitational_potential_energy(dt, world.gravity) < 0.0);
+    }
+
+    #[test]
+    fn velocity_limits() {
+        let mut world = PhysicsWorld::new();
+
+        let mut fast_ball = |max_linvel: Real, max_angvel: Real| {
+            #[cfg(feature = "dim2")]
+            let angvel = 100.0;
+            #[cfg(feature = "dim3")]
+            let angvel = Vector::z() * 100.0;
+            let body = world.insert_rigid_body(
+                RigidBodyBuilder::dynamic()
+                    .linvel(Vector::x() * 1000.0)
+                    .angvel(angvel)
+                    .max_linear_velocity(max_linvel)
+                    .max_angular_velocity(max_angvel)
+                    .ccd_enabled(true),
+            );
+            world.insert_collider_with_parent(ColliderBuilder::ball(0.5), body);
+            body
+        };
+        let unbounded = fast_ball(Real::MAX, Real::MAX);
+        let bounded = fast_ball(2.0, 1.0);
+
+        world.step(&(), &());
+
+        let rb = &world.bodies[bounded];
+        assert!((rb.linvel().norm() - 2.0).abs() < 1.0e-5);
+        #[cfg(feature = "dim2")]
+        assert!((rb.angvel() - 1.0).abs() < 1.0e-5);
+        #[cfg(feature = "dim3")]
+        assert!((rb.angvel().norm() - 1.0).abs() < 1.0e-5);
+        // The bounded body is too slow to tunnel, so CCD doesn’t activate for it.
+        assert!(!rb.is_ccd_active());
+        assert!(world.bodies[unbounded].is_ccd_active());
+        assert_eq!(world.bodies[unbounded].linvel().x, 1000.0);
+
+        // Invalid limits set directly on the public fields stop the rigid-body instead of panicking.
+        let limits = RigidBodyVelocityLimits {
+            max_linear_velocity: -1.0,
+            max_angular_velocity: Real::NAN,
+        };
+        let vels = world.bodies[unbounded].vels.apply_limits(&limits);
+        assert_eq!(vels.linvel, Vector::zeros());
+        #[cfg(feature = "dim2")]
+        assert_eq!(vels.angvel, 0.0);
+        #[cfg(feature = "dim3")]
+        assert_eq!(vels.angvel, Vector::zeros());
+
+        // The setters replace invalid limits by zero.
+        let rb = &mut world.bodies[bounded];
+        rb.set_max_linear_velocity(-1.0);
+        rb.set_max_angular_velocity(Real::NAN);
+        assert_eq!(rb.max_linear_velocity(), 0.0);
+        assert_eq!(rb.max_angular_velocity(), 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "dim3")]
+    fn gyroscopic_forces() {
+        let mut world = PhysicsWorld::new();
+
+        // Boxes spinning around their intermediate principal axis, with a small perturbation.
+        let mut spinning_box = |x: Real, gyroscopic: bool| {
+            let body = world.insert_rigid_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * x)
+                    .angvel(Vector::new(0.1, 2.0, 0.0))
+                    .gyroscopic_forces_enabled(gyroscopic),
+            );
+            world.insert_collider_with_parent(ColliderBuilder::cuboid(1.0, 0.5, 0.2), body);
+            body
+        };
+        let gyroscopic = spinning_box(0.0, true);
+        let not_gyroscopic = spinning_box(5.0, false);
+
+        let angular_momentum = |world: &PhysicsWorld| {
+            let rb = &world.bodies[gyroscopic];
+            let frame = rb.rotation()
+                * rb.mass_properties()
+                    .local_mprops
+                    .principal_inertia_local_frame;
+            let inertia = rb.mass_properties().local_mprops.principal_inertia();
+            frame * inertia.component_mul(&frame.inverse_transform_vector(rb.angvel()))
+        };
+
+        world.step(&(), &());
+        let init_momentum = angular_momentum(&world);
+        let mut flipped = false;
+
+        for _ in 0..600 {
+            world.step(&(), &());
+            // The Dzhanibekov effect flips the body around, but the angular momentum is conserved.
+            let rb = &world.bodies[gyroscopic];
+            flipped = flipped || rb.rotation().inverse_transform_vector(rb.angvel()).y < 0.0;
+            let momentum = angular_momentum(&world);
+            assert!((momentum - init_momentum).norm() < init_momentum.norm() * 1.0e-1);
+        }
+
+        assert!(flipped);
+        assert_eq!(
+            *world.bodies[not_gyroscopic].angvel(),
+            Vector::new(0.1, 2.0, 0.0)
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use crate::dynamics::{MultibodyJoint, RigidBody};
+use crate::math::{ANG_DIM, DIM};
+use crate::pipeline::{EventHandler, PhysicsHooks, PhysicsWorld, PhysicsWorldSnapshot};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Flat buffers of observations extracted from a [`PhysicsWorldBatch`].
+///
+/// The observations of each world are written one after the other, in the order of the worlds
+/// of the batch. Within a world, the rigid-bodies and multibody joints are written in the order
+/// of iteration of the `RigidBodySet` and of the `MultibodyJointSet`. If all the worlds have the
+/// same structure, the observations of the `i`-th world start at `i * buffer.len() / num_worlds`.
+#[derive(Clone, Debug, Default)]
+pub struct BatchObservations {
+    /// The positions of the rigid-bodies.
+    ///
+    /// Each rigid-body writes its translation, followed by its rotation angle in 2D, or by the
+    /// `[i, j, k, w]` components of its rotation quaternion in 3D.
+    pub positions: Vec<f32>,
+    /// The velocities of the rigid-bodies.
+    ///
+    /// Each rigid-body writes its linear velocity followed by its angular velocity.
+    pub velocities: Vec<f32>,
+    /// The coordinates of the multibody joints.
+    ///
+    /// Each multibody joint writes one coordinate per degree of freedom: its free translations,
+    /// followed by its free rotation angle if it has a single angular degree of freedom, or by
+    /// the scaled axis of its rotation if it has three angular degrees of freedom.
+    pub joint_coordinates: Vec<f32>,
+}
+
+impl BatchObservations {
+    /// Creates new empty observation buffers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn clear(&mut self) {
+        self.positions.clear();
+        self.velocities.clear();
+        self.joint_coordinates.clear();
+    }
+}
+
+/// A batch of independent physics worlds, stepped together.
+///
+/// With the `parallel` feature enabled, the worlds are distributed across the rayon thread-pool.
+/// This is much more efficient than relying on the parallelism within each world when the
+/// worlds are small, which is typically the case for reinforcement learning environments.
+///
+/// Each world can be given a reset snapshot that it can be rolled back to with
+/// [`PhysicsWorldBatch::reset`] or [`PhysicsWorldBatch::reset_where`].
+#[derive(Clone, Default)]
+pub struct PhysicsWorldBatch {
+    /// The worlds of this batch.
+    pub worlds: Vec<PhysicsWorld>,
+    reset_snapshots: Vec<Option<PhysicsWorldSnapshot>>,
+}
+
+impl PhysicsWorldBatch {
+    /// Creates a new batch from the given worlds, with no reset snapshots.
+    pub fn new(worlds: Vec<PhysicsWorld>) -> Self {
+        let reset_snapshots = vec![None; worlds.len()];
+        Self {
+            worlds,
+            reset_snapshots,
+        }
+    }
+
+    /// The number of worlds in this batch.
+    pub fn len(&self) -> usize {
+        self.worlds.len()
+    }
+
+    /// Is this batch empty?
+    pub fn is_empty(&self) -> bool {
+        self.worlds.is_empty()
+    }
+
+    /// Takes a snapshot of the current state of every world and uses it as its reset snapshot.
+    pub fn save_reset_snapshots(&mut self) {
+        self.reset_snapshots.resize_with(self.worlds.len(), || None);
+
+        for (world, snapshot) in self.worlds.iter().zip(self.reset_snapshots.iter_mut()) {
+            match snapshot {
+                Some(snapshot) => world.take_snapshot_into(snapshot),
+                None => *snapshot = Some(world.take_snapshot()),
+            }
+        }
+    }
+
+    /// Sets the snapshot the `i`-th world is rolled back to when it is reset.
+    pub fn set_reset_snapshot(&mut self, i: usize, snapshot: Option<PhysicsWorldSnapshot>) {
+        if self.reset_snapshots.len() <= i {
+            self.reset_snapshots.resize_with(i + 1, || None);
+        }
+
+        self.reset_snapshots[i] = snapshot;
+    }
+
+    /// The snapshot the `i`-th world is rolled back to when it is reset.
+    pub fn reset_snapshot(&self, i: usize) -> Option<&PhysicsWorldSnapshot> {
+        self.reset_snapshots.get(i)?.as_ref()
+    }
+
+    /// Rolls the `i`-th world back to its reset snapshot.
+    ///
+    /// Returns `false` if the world doesn’t exist, has no reset snapshot, or if the
+    /// snapshot could not be restored.
+    pub fn reset(&mut self, i: usize) -> bool {
+        match (self.worlds.get_mut(i), self.reset_snapshots.get(i)) {
+            (Some(world), Some(Some(snapshot))) => world.restore_snapshot(snapshot),
+            _ => false,
+        }
+    }
+
+    /// Rolls back every world `i` such that `mask[i]` is `true` to its reset snapshot.
+    ///
+    /// Worlds without reset snapshot are left unchanged.
+    pub fn reset_where(&mut self, mask: &[bool]) {
+        let snapshots = &self.reset_snapshots;
+        let reset = |(i, world): (usize, &mut PhysicsWorld)| {
+            if mask.get(i).copied().unwrap_or(false) {
+                if let Some(Some(snapshot)) = snapshots.get(i) {
+                    world.restore_snapshot(snapshot);
+                }
+            }
+        };
+
+        #[cfg(not(feature = "parallel"))]
+        self.worlds.iter_mut().enumerate().for_each(reset);
+        #[cfg(feature = "parallel")]
+        self.worlds.par_iter_mut().enumerate().for_each(reset);
+    }
+
+    /// Executes one timestep on every world of this batch.
+    ///
+    /// The same physics hooks and event handler are shared by all the worlds, so they may be
+    /// called concurrently from multiple threads.
+    pub fn step(&mut self, hooks: &dyn PhysicsHooks, events: &dyn EventHandler) {
+        #[cfg(not(feature = "parallel"))]
+        self.worlds
+            .iter_mut()
+            .for_each(|world| world.step(hooks, events));
+        #[cfg(feature = "parallel")]
+        self.worlds
+            .par_iter_mut()
+            .for_each(|world| world.step(hooks, events));
+    }
+
+    /// Writes the positions, velocities, and joint coordinates of every world into `out`.
+    ///
+    /// The buffers of `out` are cleared first. See [`BatchObservations`] for the layout.
+    pub fn extract_observations(&self, out: &mut BatchObservations) {
+        out.clear();
+
+        for world in &self.worlds {
+            for (_, rb) in world.bodies.iter() {
+                write_position(rb, &mut out.positions);
+                write_velocity(rb, &mut out.velocities);
+            }
+
+            for (_, _, link) in world.multibody_joints.iter() {
+                write_joint_coordinates(&link.joint, &mut out.joint_coordinates);
+            }
+        }
+    }
+}
+
+fn write_position(rb: &RigidBody, out: &mut Vec<f32>) {
+    let pos = rb.position();
+    out.extend(pos.translation.vector.iter().map(|x| *x as f32));
+    #[cfg(feature = "dim2")]
+    out.push(pos.rotation.angle() as f32);
+    #[cfg(feature = "dim3")]
+    out.extend(pos.rotation.coords.iter().map(|x| *x as f32));
+}
+
+fn write_velocity(rb: &RigidBody, out: &mut Vec<f32>) {
+    out.extend(rb.linvel().iter().map(|x| *x as f32));
+    #[cfg(feature = "dim2")]
+    out.push(rb.angvel() as f32);
+    #[cfg(feature = "dim3")]
+    out.extend(rb.angvel().iter().map(|x| *x as f32));
+}
+
+fn write_joint_coordinates(joint: &MultibodyJoint, out: &mut Vec<f32>) {
+    let locked_bits = joint.data.locked_axes.bits();
+    let locked_ang_bits = locked_bits >> DIM;
+
+    for i in 0..DIM {
+        if (locked_bits & (1 << i)) == 0 {
+            out.push(joint.coords[i] as f32);
+        }
+    }
+
+    #[cfg(feature = "dim3")]
+    if locked_ang_bits == 0 {
+        // The angular coordinates of ball joints aren’t tracked, use the joint rotation instead.
+        out.extend(joint.joint_rot.scaled_axis().iter().map(|x| *x as f32));
+        return;
+    }
+
+    for i in 0..ANG_DIM {
+        if (locked_ang_bits & (1 << i)) == 0 {
+            out.push(joint.coords[DIM + i] as f32);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BatchObservations, PhysicsWorldBatch};
+    use crate::dynamics::{RevoluteJointBuilder, RigidBodyBuilder};
+    use crate::math::{Point, Vector};
+    use crate::pipeline::test_utils;
+
+    #[test]
+    fn batch_step_reset_and_observe() {
+        let mut world = test_utils::world();
+        let root = world.insert_rigid_body(RigidBodyBuilder::fixed());
+        let arm = world.insert_rigid_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x())
+                .additional_mass(1.0),
+        );
+        #[cfg(feature = "dim2")]
+        let joint = RevoluteJointBuilder::new();
+        #[cfg(feature = "dim3")]
+        let joint = RevoluteJointBuilder::new(Vector::z_axis());
+        let joint = joint.local_anchor2(Point::from(-Vector::x()));
+        world
+            .insert_multibody_joint(root, arm, joint, true)
+            .unwrap();
+
+        let mut batch = PhysicsWorldBatch::new(vec![world; 4]);
+        batch.save_reset_snapshots();
+
+        for _ in 0..10 {
+            batch.step(&(), &());
+        }
+
+        let mut obs = BatchObservations::new();
+        batch.extract_observations(&mut obs);
+        assert_eq!(obs.joint_coordinates.len(), 4);
+        assert!(obs.joint_coordinates[0] < 0.0);
+        assert!(obs
+            .joint_coordinates
+            .iter()
+            .all(|x| *x == obs.joint_coordinates[0]));
+
+        batch.reset_where(&[false, true, false, true]);
+        batch.extract_observations(&mut obs);
+        assert!(obs.joint_coordinates[0] < 0.0);
+        assert_eq!(obs.joint_coordinates[1], 0.0);
+        assert_eq!(obs.joint_coordinates[3], 0.0);
+        assert_eq!(batch.worlds[1].bodies[arm].translation().x, 1.0);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::data::Index;
+use crate::dynamics::{RigidBody, RigidBodyHandle};
+use crate::math::{Isometry, Real};
+use crate::pipeline::PhysicsWorld;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A 64-bits FNV-1a hasher fed with the exact bit patterns of the hashed values.
+///
+/// Contrary to `std::collections::hash_map::DefaultHasher`, its output is specified and
+/// doesn’t depend on the platform or on the Rust version.
+#[derive(Copy, Clone, Debug)]
+struct StateHasher(u64);
+
+impl StateHasher {
+    fn new() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    fn write_real(&mut self, value: Real) {
+        self.write_bytes(&value.to_bits().to_le_bytes());
+    }
+
+    fn write_reals<'a>(&mut self, values: impl IntoIterator<Item = &'a Real>) {
+        for value in values {
+            self.write_real(*value);
+        }
+    }
+
+    fn write_index(&mut self, index: Index) {
+        let (id, generation) = index.into_raw_parts();
+        self.write_u32(id);
+        self.write_u32(generation);
+    }
+
+    fn write_isometry(&mut self, pos: &Isometry<Real>) {
+        self.write_reals(pos.translation.vector.iter());
+        #[cfg(feature = "dim2")]
+        self.write_reals([pos.rotation.re, pos.rotation.im].iter());
+        #[cfg(feature = "dim3")]
+        self.write_reals(pos.rotation.coords.iter());
+    }
+
+    fn write_rigid_body(&mut self, rb: &RigidBody) {
+        self.write_isometry(rb.position());
+        self.write_reals(rb.linvel().iter());
+        #[cfg(feature = "dim2")]
+        self.write_real(rb.angvel());
+        #[cfg(feature = "dim3")]
+        self.write_reals(rb.angvel().iter());
+        self.write_bytes(&[rb.is_sleeping() as u8]);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl PhysicsWorld {
+    /// Computes a hash of the simulation state of this world.
+    ///
+    /// The hash covers the poses, velocities, and sleep states of every rigid-body, as well as
+    /// the impulses of every impulse joint and the coordinates of every multibody joint. It is
+    /// computed from the exact bit patterns of these values, with a hash function that doesn’t
+    /// depend on the platform. When the `enhanced-determinism` feature is enabled, two worlds
+    /// simulated from the same initial state will have the same hash on every platform, so
+    /// comparing hashes at each timestep can be used to detect desynchronizations.
+    pub fn state_hash(&self) -> u64 {
+        self.compute_state_hash(None)
+    }
+
+    /// Computes a hash of the simulation state of this world, and the hash of each rigid-body.
+    ///
+    /// The returned global hash is the same as the one computed by [`Self::state_hash`]. The
+    /// `breakdown` vector is cleared and filled with the hash of the pose, velocity, and sleep
+    /// state of each rigid-body. Comparing breakdowns helps finding which rigid-body diverged
+    /// first.
+    pub fn state_hash_with_breakdown(&self, breakdown: &mut Vec<(RigidBodyHandle, u64)>) -> u64 {
+        breakdown.clear();
+        self.compute_state_hash(Some(breakdown))
+    }
+
+    fn compute_state_hash(&self, mut breakdown: Option<&mut Vec<(RigidBodyHandle, u64)>>) -> u64 {
+        let mut hasher = StateHasher::new();
+
+        hasher.write_u64(self.bodies.len() as u64);
+        for (handle, rb) in self.bodies.iter() {
+            let mut body_hasher = StateHasher::new();
+            body_hasher.write_rigid_body(rb);
+            let body_hash = body_hasher.finish();
+
+            hasher.write_index(handle.0);
+            hasher.write_u64(body_hash);
+
+            if let Some(breakdown) = &mut breakdown {
+                breakdown.push((handle, body_hash));
+            }
+        }
+
+        hasher.write_u64(self.impulse_joints.len() as u64);
+        for (handle, joint) in self.impulse_joints.iter() {
+            hasher.write_index(handle.0);
+            hasher.write_reals(joint.impulses.iter());
+        }
+
+        for (handle, _, link) in self.multibody_joints.iter() {
+            hasher.write_index(handle.0);
+            hasher.write_reals(link.joint.coords.iter());
+            #[cfg(feature = "dim2")]
+            hasher.write_reals([link.joint.joint_rot.re, link.joint.joint_rot.im].iter());
+            #[cfg(feature = "dim3")]
+            hasher.write_reals(link.joint.joint_rot.coords.iter());
+        }
+
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::test_utils;
+
+    #[test]
+    fn state_hash_detects_divergence() {
+        let mut world1 = test_utils::world();
+        let rb1 = world1.insert_rigid_body(RigidBodyBuilder::dynamic());
+        let rb2 = world1.insert_rigid_body(RigidBodyBuilder::dynamic().translation(Vector::x()));
+        world1.insert_collider_with_parent(ColliderBuilder::ball(0.4), rb1);
+        world1.insert_collider_with_parent(ColliderBuilder::ball(0.4), rb2);
+        let mut world2 = world1.clone();
+
+        for _ in 0..10 {
+            world1.step(&(), &());
+            world2.step(&(), &());
+        }
+
+        assert_eq!(world1.state_hash(), world2.state_hash());
+
+        world2.bodies[rb2].set_linvel(Vector::y(), false);
+
+        let mut breakdown1 = vec![];
+        let mut breakdown2 = vec![];
+        let hash1 = world1.state_hash_with_breakdown(&mut breakdown1);
+        let hash2 = world2.state_hash_with_breakdown(&mut breakdown2);
+
+        assert_ne!(hash1, hash2);
+        assert_eq!(hash1, world1.state_hash());
+        assert_eq!(breakdown1[0], breakdown2[0]);
+        assert_ne!(breakdown1[1], breakdown2[1]);
+    }
+}
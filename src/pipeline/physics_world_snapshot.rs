@@ -0,0 +1,269 @@
+use crate::dynamics::{
+    ForceGeneratorSetState, ImpulseJointHandle, IslandManagerState, MultibodyJointSetState,
+    RigidBodyActivation, RigidBodyForces, RigidBodyHandle, RigidBodyIds, RigidBodyPosition,
+    RigidBodyVelocity,
+};
+use crate::geometry::{BroadPhaseState, ColliderHandle, ColliderPosition, NarrowPhaseState};
+use crate::math::{Real, SpacialVector};
+use crate::pipeline::PhysicsWorld;
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone)]
+struct RigidBodyState {
+    handle: RigidBodyHandle,
+    pos: RigidBodyPosition,
+    vels: RigidBodyVelocity,
+    integrated_vels: RigidBodyVelocity,
+    forces: RigidBodyForces,
+    activation: RigidBodyActivation,
+    ids: RigidBodyIds,
+    ccd_active: bool,
+}
+
+/// A snapshot of the mutable simulation state of a [`PhysicsWorld`].
+///
+/// Contrary to serializing the whole world, a snapshot only captures the state modified by
+/// the simulation: rigid-body poses, velocities, forces, and activation states, collider poses,
+/// joint impulses, multibody coordinates and velocities, active sets and islands, the contact
+/// pairs (including the contact impulses used for warm-starting), the broad-phase proxies
+/// and endpoints, and the internal state of the force generators. Static data like shapes, mass-properties, joint descriptions, or collider
+/// materials remain owned by the world and are not copied.
+///
+/// As a result, a snapshot can only be restored into the world it was taken from, as long as no
+/// rigid-body, collider, joint, or force generator was inserted or removed in-between. This makes snapshots suited
+/// for rollback: when the `enhanced-determinism` feature is enabled, stepping the world after
+/// restoring a snapshot gives bit-exact results.
+///
+/// The same snapshot can be reused with [`PhysicsWorld::take_snapshot_into`] to avoid
+/// re-allocating its buffers each time. Restoring a snapshot reuses the top-level buffers of
+/// the world, like its arrays of contact pairs and broad-phase proxies.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct PhysicsWorldSnapshot {
+    bodies: Vec<RigidBodyState>,
+    colliders: Vec<(ColliderHandle, ColliderPosition)>,
+    impulse_joints: Vec<(ImpulseJointHandle, SpacialVector<Real>)>,
+    multibody_joints: MultibodyJointSetState,
+    force_generators: ForceGeneratorSetState,
+    islands: IslandManagerState,
+    broad_phase: BroadPhaseState,
+    narrow_phase: NarrowPhaseState,
+}
+
+impl Default for PhysicsWorldSnapshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PhysicsWorldSnapshot {
+    /// Creates a new empty snapshot.
+    pub fn new() -> Self {
+        Self {
+            bodies: vec![],
+            colliders: vec![],
+            impulse_joints: vec![],
+            multibody_joints: MultibodyJointSetState::default(),
+            force_generators: ForceGeneratorSetState::default(),
+            islands: IslandManagerState::default(),
+            broad_phase: BroadPhaseState::default(),
+            narrow_phase: NarrowPhaseState::default(),
+        }
+    }
+
+    /// The number of rigid-bodies captured by this snapshot.
+    pub fn num_rigid_bodies(&self) -> usize {
+        self.bodies.len()
+    }
+}
+
+impl PhysicsWorld {
+    /// Captures the mutable simulation state of this world.
+    ///
+    /// See [`PhysicsWorldSnapshot`] for details on what is captured.
+    pub fn take_snapshot(&self) -> PhysicsWorldSnapshot {
+        let mut snapshot = PhysicsWorldSnapshot::new();
+        self.take_snapshot_into(&mut snapshot);
+        snapshot
+    }
+
+    /// Captures the mutable simulation state of this world into an existing snapshot.
+    ///
+    /// This reuses the buffers already allocated by `snapshot`.
+    pub fn take_snapshot_into(&self, snapshot: &mut PhysicsWorldSnapshot) {
+        snapshot.bodies.clear();
+        snapshot
+            .bodies
+            .extend(self.bodies.iter().map(|(handle, rb)| RigidBodyState {
+                handle,
+                pos: rb.pos,
+                vels: rb.vels,
+                integrated_vels: rb.integrated_vels,
+                forces: rb.forces,
+                activation: rb.activation,
+                ids: rb.ids,
+                ccd_active: rb.ccd.ccd_active,
+            }));
+
+        snapshot.colliders.clear();
+        snapshot
+            .colliders
+            .extend(self.colliders.iter().map(|(handle, co)| (handle, co.pos)));
+
+        snapshot.impulse_joints.clear();
+        snapshot.impulse_joints.extend(
+            self.impulse_joints
+                .iter()
+                .map(|(handle, joint)| (handle, joint.impulses)),
+        );
+
+        self.multibody_joints
+            .save_state(&mut snapshot.multibody_joints);
+        self.force_generators
+            .save_state(&mut snapshot.force_generators);
+        self.islands.save_state(&mut snapshot.islands);
+        self.broad_phase.save_state(&mut snapshot.broad_phase);
+        self.narrow_phase.save_state(&mut snapshot.narrow_phase);
+    }
+
+    /// Restores the simulation state captured by `snapshot`.
+    ///
+    /// Returns `false`, without modifying this world, if a rigid-body, collider, joint, or force
+    /// generator was inserted or removed since the snapshot was taken.
+    pub fn restore_snapshot(&mut self, snapshot: &PhysicsWorldSnapshot) -> bool {
+        let same_structure = self.bodies.len() == snapshot.bodies.len()
+            && self.colliders.len() == snapshot.colliders.len()
+            && self.impulse_joints.len() == snapshot.impulse_joints.len()
+            && snapshot
+                .bodies
+                .iter()
+                .all(|state| self.bodies.contains(state.handle))
+            && snapshot
+                .colliders
+                .iter()
+                .all(|(handle, _)| self.colliders.contains(*handle))
+            && snapshot
+                .impulse_joints
+                .iter()
+                .all(|(handle, _)| self.impulse_joints.contains(*handle))
+            && snapshot
+                .multibody_joints
+                .is_compatible_with(&self.multibody_joints)
+            && snapshot
+                .force_generators
+                .is_compatible_with(&self.force_generators);
+
+        if !same_structure {
+            return false;
+        }
+
+        for state in &snapshot.bodies {
+            let rb = self.bodies.index_mut_internal(state.handle);
+            rb.pos = state.pos;
+            rb.vels = state.vels;
+            rb.integrated_vels = state.integrated_vels;
+            rb.forces = state.forces;
+            rb.activation = state.activation;
+            rb.ids = state.ids;
+            rb.ccd.ccd_active = state.ccd_active;
+            rb.update_world_mass_properties();
+        }
+
+        for (handle, pos) in &snapshot.colliders {
+            self.colliders.index_mut_internal(*handle).pos = *pos;
+        }
+
+        for (handle, impulses) in &snapshot.impulse_joints {
+            if let Some(joint) = self.impulse_joints.get_mut(*handle) {
+                joint.impulses = *impulses;
+            }
+        }
+
+        self.multibody_joints
+            .restore_state(&snapshot.multibody_joints, &mut self.bodies);
+        self.force_generators
+            .restore_state(&snapshot.force_generators);
+        self.islands.restore_state(&snapshot.islands);
+        self.broad_phase.restore_state(&snapshot.broad_phase);
+        self.narrow_phase.restore_state(&snapshot.narrow_phase);
+        self.query_pipeline.update(&self.bodies, &self.colliders);
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{RevoluteJointBuilder, RigidBodyBuilder, Wind};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_utils;
+    use crate::pipeline::PhysicsWorld;
+
+    #[test]
+    fn restore_snapshot_rollback() {
+        let mut world = test_utils::world();
+        let ground = world.insert_rigid_body(RigidBodyBuilder::fixed());
+        let ground_shape = test_utils::cuboid(10.0, 0.1);
+        world.insert_collider_with_parent(ground_shape, ground);
+
+        let mut handles = vec![];
+        for i in 0..3 {
+            let rb = RigidBodyBuilder::dynamic().translation(Vector::y() * (0.6 + i as Real));
+            let handle = world.insert_rigid_body(rb);
+            world.insert_collider_with_parent(ColliderBuilder::ball(0.5), handle);
+            handles.push(handle);
+        }
+
+        // A pendulum simulated with a multibody joint.
+        let anchor = world.insert_rigid_body(
+            RigidBodyBuilder::fixed().translation(Vector::x() * 4.0 + Vector::y() * 3.0),
+        );
+        let pendulum = world.insert_rigid_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * 5.0 + Vector::y() * 3.0)
+                .additional_mass(1.0),
+        );
+        #[cfg(feature = "dim2")]
+        let joint = RevoluteJointBuilder::new();
+        #[cfg(feature = "dim3")]
+        let joint = RevoluteJointBuilder::new(Vector::z_axis());
+        let joint = joint.local_anchor2(Point::from(-Vector::x()));
+        let pendulum_joint = world
+            .insert_multibody_joint(anchor, pendulum, joint, true)
+            .unwrap();
+        handles.push(pendulum);
+
+        // The turbulences depend on the internal time of the wind.
+        let wind = Wind::new(Vector::x(), 0.5).turbulence(0.5);
+        world.force_generators.insert(wind, Default::default());
+
+        for _ in 0..10 {
+            world.step(&(), &());
+        }
+
+        let snapshot = world.take_snapshot();
+        let run = |world: &mut PhysicsWorld| {
+            for _ in 0..20 {
+                world.step(&(), &());
+            }
+            handles
+                .iter()
+                .map(|h| (*world.bodies[*h].position(), *world.bodies[*h].linvel()))
+                .collect::<Vec<_>>()
+        };
+
+        let expected = run(&mut world);
+        assert!(world.restore_snapshot(&snapshot));
+        assert_eq!(run(&mut world), expected);
+
+        // The snapshot can’t be restored after a structural change.
+        world.remove_multibody_joint(pendulum_joint, true);
+        world
+            .insert_multibody_joint(anchor, handles[2], joint, true)
+            .unwrap();
+        assert!(!world.restore_snapshot(&snapshot));
+        world.remove_rigid_body(handles[0], true);
+        assert!(!world.restore_snapshot(&snapshot));
+    }
+}
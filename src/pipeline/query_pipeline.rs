@@ -352,6 +352,21 @@ impl QueryPipeline {
         }
     }
 
+    /// Refits the acceleration structure after every collider was translated by the same amount.
+    ///
+    /// Contrary to [`Self::update`], this keeps the current tree structure and only recomputes
+    /// the Aabbs of its nodes. This is meant to be used for shifting the origin of the whole
+    /// simulation, see `PhysicsWorld::shift_origin`.
+    pub fn shift_origin(&mut self, colliders: &ColliderSet) {
+        for (handle, _) in colliders.iter_enabled() {
+            self.qbvh.pre_update_or_insert(handle);
+        }
+
+        let _ = self.qbvh.refit(0.0, &mut self.workspace, |handle| {
+            colliders[*handle].compute_aabb()
+        });
+    }
+
     /// Update the acceleration structure on the query pipeline.
     pub fn update(&mut self, bodies: &RigidBodySet, colliders: &ColliderSet) {
         self.update_with_mode(bodies, colliders, QueryPipelineMode::CurrentPosition)
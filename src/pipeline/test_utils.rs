@@ -0,0 +1,59 @@
+//! Fixtures shared by the unit tests of the simulation.
+
+use crate::dynamics::JointBrokenEvent;
+use crate::geometry::{
+    ColliderBuilder, CollisionEvent, ContactForceEvent, ContactImpactEvent, ContactPersistedEvent,
+    Cuboid, SensorPassThroughEvent, SharedShape,
+};
+use crate::math::{Real, Vector};
+use crate::pipeline::{ChannelEventCollector, PhysicsWorld};
+use crossbeam::channel::{unbounded, Receiver};
+
+/// An empty world with a gravity of `10` along the negative `y` axis.
+pub(crate) fn world() -> PhysicsWorld {
+    PhysicsWorld::with_gravity(Vector::y() * -10.0)
+}
+
+/// A cuboid with the half-extent `hy` along the `y` axis, and `h` along the other axes.
+pub(crate) fn cuboid(h: Real, hy: Real) -> ColliderBuilder {
+    let mut half_extents = Vector::repeat(h);
+    half_extents.y = hy;
+    ColliderBuilder::new(SharedShape::new(Cuboid::new(half_extents)))
+}
+
+/// An event collector sending each kind of event to its own channel.
+pub(crate) struct EventChannels {
+    pub collector: ChannelEventCollector,
+    pub collisions: Receiver<CollisionEvent>,
+    pub forces: Receiver<ContactForceEvent>,
+    pub persisted: Receiver<ContactPersistedEvent>,
+    pub impacts: Receiver<ContactImpactEvent>,
+    pub broken_joints: Receiver<JointBrokenEvent>,
+    pub pass_throughs: Receiver<SensorPassThroughEvent>,
+}
+
+impl EventChannels {
+    pub fn new() -> Self {
+        let (collision_send, collisions) = unbounded();
+        let (force_send, forces) = unbounded();
+        let (persisted_send, persisted) = unbounded();
+        let (impact_send, impacts) = unbounded();
+        let (broken_joint_send, broken_joints) = unbounded();
+        let (pass_through_send, pass_throughs) = unbounded();
+        let collector = ChannelEventCollector::new(collision_send, force_send)
+            .with_contact_persisted_events(persisted_send)
+            .with_contact_impact_events(impact_send)
+            .with_joint_broken_events(broken_joint_send)
+            .with_sensor_pass_through_events(pass_through_send);
+
+        Self {
+            collector,
+            collisions,
+            forces,
+            persisted,
+            impacts,
+            broken_joints,
+            pass_throughs,
+        }
+    }
+}